@@ -14,6 +14,9 @@ pub trait BackendRow {
     /// Get a 32-bit float value from a column (used for distances/scores).
     fn get_f32(&self, column: &str) -> Result<Option<f32>>;
 
+    /// Get a 64-bit float value from a column (used for high-precision distances).
+    fn get_f64(&self, column: &str) -> Result<Option<f64>>;
+
     /// Get a 64-bit integer value from a column (used for counts).
     fn get_i64(&self, column: &str) -> Result<Option<i64>>;
 
@@ -21,25 +24,6 @@ pub trait BackendRow {
     fn get_string_by_index(&self, index: usize) -> Result<Option<String>>;
 }
 
-/// Asynchronous SQL backend abstraction.
-///
-/// This trait is defined for future embedded/server backends; for now it is
-/// implemented only for `ServerClient`. Collection/admin code can gradually
-/// migrate to depend on this trait instead of a concrete client.
-#[async_trait::async_trait]
-pub trait SqlBackend: Send + Sync {
-    type Row: BackendRow + Send + Sync;
-
-    /// Execute a SQL statement that does not return rows.
-    async fn execute(&self, sql: &str) -> Result<()>;
-
-    /// Fetch all rows for the given SQL query.
-    async fn fetch_all(&self, sql: &str) -> Result<Vec<Self::Row>>;
-
-    /// Return a short mode string (e.g., "server", "embedded") for logging.
-    fn mode(&self) -> &'static str;
-}
-
 impl BackendRow for sqlx::mysql::MySqlRow {
     fn get_bytes(&self, column: &str) -> Result<Option<Vec<u8>>> {
         use sqlx::Row;
@@ -60,6 +44,21 @@ impl BackendRow for sqlx::mysql::MySqlRow {
         v.map(Some).map_err(Into::into)
     }
 
+    fn get_f64(&self, column: &str) -> Result<Option<f64>> {
+        use sqlx::{Row, TypeInfo, ValueRef};
+        let raw = self.try_get_raw(column)?;
+        if raw.is_null() {
+            return Ok(None);
+        }
+        // FLOAT columns arrive as 4 bytes; widen them rather than decoding as DOUBLE.
+        if raw.type_info().name() == "FLOAT" {
+            let v: f32 = self.try_get(column)?;
+            return Ok(Some(f64::from(v)));
+        }
+        let v: f64 = self.try_get(column)?;
+        Ok(Some(v))
+    }
+
     fn get_i64(&self, column: &str) -> Result<Option<i64>> {
         use sqlx::Row;
         let v: std::result::Result<i64, sqlx::Error> = self.try_get(column);
@@ -1,14 +1,22 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use crate::backend::{BackendRow, SqlBackend};
-use crate::config::DistanceMetric;
-use crate::embedding::EmbeddingFunction;
+use crate::backend::BackendRow;
+use crate::config::{DistanceMetric, VectorField};
+use crate::embedding::{EmbeddingFunction, l2_normalize};
 use crate::error::{Result, SeekDbError};
-use crate::filters::{DocFilter, Filter, build_where_clause};
+use crate::filters::{DocFilter, Filter, JsonDialect, build_where_clause_with_dialect};
+use crate::ids::IdStrategy;
 use crate::meta::CollectionNames;
-use crate::server::ServerClient;
-use crate::types::{Embedding, GetResult, IncludeField, Metadata, QueryResult};
+use crate::server::{OpKind, PinnedSession, ServerClient, WriteSession, append_limit_offset};
+use crate::types::{
+    CollectionSchema, Embedding, GetResult, IncludeField, IncludeFlags, Metadata, PageToken,
+    QueryGroup, QueryResult, Record, UpsertReport, VectorLiteralFormat,
+};
+use futures::{Stream, StreamExt, TryStreamExt};
 use serde_json::{Value, json};
+use sqlx::Execute;
+use sqlx::mysql::MySqlRow;
 
 /// Batch parameters for `Collection::add_batch`.
 ///
@@ -19,6 +27,8 @@ pub struct AddBatch<'a> {
     embeddings: Option<&'a [Embedding]>,
     metadatas: Option<&'a [Metadata]>,
     documents: Option<&'a [String]>,
+    vectors: Vec<(&'a str, &'a [Embedding])>,
+    on_conflict: OnConflict,
 }
 
 impl<'a> AddBatch<'a> {
@@ -28,6 +38,8 @@ impl<'a> AddBatch<'a> {
             embeddings: None,
             metadatas: None,
             documents: None,
+            vectors: Vec::new(),
+            on_conflict: OnConflict::default(),
         }
     }
 
@@ -45,6 +57,38 @@ impl<'a> AddBatch<'a> {
         self.documents = Some(documents);
         self
     }
+
+    /// Values for the named vector field `name` (see
+    /// [`HnswConfig::with_vector_field`](crate::HnswConfig::with_vector_field)),
+    /// one per id. Fields not given are stored as NULL.
+    pub fn vector_field(mut self, name: &'a str, embeddings: &'a [Embedding]) -> Self {
+        self.vectors.push((name, embeddings));
+        self
+    }
+
+    /// What to do with ids that already exist (default [`OnConflict::Error`]).
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.on_conflict = on_conflict;
+        self
+    }
+}
+
+/// How `add` and [`Collection::merge_from`] treat an id that already exists
+/// in the collection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Fail with the server's duplicate-key error
+    /// ([`SeekDbError::Database`] with code 1062).
+    #[default]
+    Error,
+    /// Keep the stored row and drop the new one (`INSERT IGNORE`). The server
+    /// also downgrades other row errors, such as out-of-range values, to
+    /// warnings in this mode.
+    Skip,
+    /// Overwrite the stored row's document, metadata and embedding, and any
+    /// named vector fields given with the new row (`ON DUPLICATE KEY UPDATE`).
+    /// Named vector fields not given keep their stored values.
+    Replace,
 }
 
 /// Batch parameters for `Collection::update_batch`.
@@ -125,6 +169,12 @@ pub struct GetQuery<'a> {
     include: Option<&'a [IncludeField]>,
 }
 
+impl<'a> Default for GetQuery<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> GetQuery<'a> {
     pub fn new() -> Self {
         Self {
@@ -179,6 +229,12 @@ pub struct DeleteQuery<'a> {
     where_doc: Option<&'a DocFilter>,
 }
 
+impl<'a> Default for DeleteQuery<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> DeleteQuery<'a> {
     pub fn new() -> Self {
         Self {
@@ -208,6 +264,155 @@ impl<'a> DeleteQuery<'a> {
     }
 }
 
+/// Optional knobs for `Collection::query_embeddings_with_options`.
+#[derive(Clone, Debug, Default)]
+pub struct QueryOptions {
+    max_distance: Option<f32>,
+    f64_distances: bool,
+    ef_search: Option<u32>,
+    assume_normalized: bool,
+    then_by: Option<(String, SortDir)>,
+    vector_field: Option<String>,
+    concurrency: Option<usize>,
+    dimension_fit: DimensionFit,
+}
+
+/// Direction of a secondary sort key, see [`QueryOptions::with_then_by`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortDir {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDir {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortDir::Asc => "ASC",
+            SortDir::Desc => "DESC",
+        }
+    }
+}
+
+/// How a query vector whose length differs from the searched column's
+/// dimension is handled, see [`QueryOptions::with_dimension_fit`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DimensionFit {
+    /// Send the vector as given; a length mismatch is rejected.
+    #[default]
+    Exact,
+    /// Cut longer vectors to the dimension and pad shorter ones with zeros.
+    PadOrTruncate,
+}
+
+impl QueryOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return hits whose distance is at most `max_distance`, even if
+    /// that leaves fewer than `n_results` per query. Under inner product,
+    /// where higher is closer, it keeps hits scoring at least `max_distance`.
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    pub fn max_distance(&self) -> Option<f32> {
+        self.max_distance
+    }
+
+    /// Also return distances as `f64` in `QueryResult::distances_f64`, for
+    /// threshold comparisons that need more than `f32` precision.
+    pub fn with_f64_distances(mut self, enabled: bool) -> Self {
+        self.f64_distances = enabled;
+        self
+    }
+
+    /// HNSW candidate list size for this query only. Larger values trade
+    /// latency for recall; the collection's index settings are untouched.
+    pub fn with_ef_search(mut self, ef_search: u32) -> Self {
+        self.ef_search = Some(ef_search);
+        self
+    }
+
+    pub fn ef_search(&self) -> Option<u32> {
+        self.ef_search
+    }
+
+    /// The query vectors are already unit length: skip the collection's
+    /// client-side normalization (see `Collection::with_normalize_queries`)
+    /// for this call.
+    pub fn with_assume_normalized(mut self, assume_normalized: bool) -> Self {
+        self.assume_normalized = assume_normalized;
+        self
+    }
+
+    /// Order hits at equal distance by `metadata[field]` (a dotted path, as in
+    /// filters), e.g. `("ts", SortDir::Desc)` to prefer newer documents. Distance
+    /// stays the primary key and `_id` the final tie-break. Values are compared
+    /// as JSON; hits without the field sort first ascending and last descending.
+    pub fn with_then_by(mut self, field: impl Into<String>, dir: SortDir) -> Self {
+        self.then_by = Some((field.into(), dir));
+        self
+    }
+
+    pub fn then_by(&self) -> Option<(&str, SortDir)> {
+        self.then_by
+            .as_ref()
+            .map(|(field, dir)| (field.as_str(), *dir))
+    }
+
+    /// Search the named vector field instead of the main `embedding` column.
+    /// The query vectors must have that field's dimension.
+    pub fn with_vector_field(mut self, name: impl Into<String>) -> Self {
+        self.vector_field = Some(name.into());
+        self
+    }
+
+    pub fn vector_field(&self) -> Option<&str> {
+        self.vector_field.as_deref()
+    }
+
+    /// Run at most `concurrency` (at least 1) of a batch's per-vector searches
+    /// at once; defaults to the pool's `max_connections`, or the client's
+    /// `max_inflight` if that is lower, so a batch cannot overload its own
+    /// client under [`OverloadPolicy::Reject`](crate::OverloadPolicy::Reject).
+    /// Results keep the order of the query vectors either way. With
+    /// [`with_ef_search`](Self::with_ef_search) the searches share one
+    /// connection and run one after another.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    pub fn concurrency(&self) -> Option<usize> {
+        self.concurrency
+    }
+
+    /// Fit query vectors to the searched column's dimension instead of
+    /// failing on a mismatch, for models with nested (Matryoshka) embeddings
+    /// whose prefixes are embeddings too. Only allowed under cosine and inner
+    /// product distance; other metrics return `SeekDbError::InvalidInput`.
+    ///
+    /// With [`DimensionFit::PadOrTruncate`], a short query is padded with
+    /// zeros, so its inner product with a stored vector is exactly the inner
+    /// product with that vector's prefix. Cosine distance still divides by the
+    /// stored vector's full norm, so hits are ranked by a blend of prefix
+    /// similarity and how much of each stored vector lies in its prefix;
+    /// expect lower recall than searching prefix-length vectors. A long query
+    /// keeps only its first components, which is the usual way to search
+    /// shorter stored embeddings with a full-size query.
+    pub fn with_dimension_fit(mut self, fit: DimensionFit) -> Self {
+        self.dimension_fit = fit;
+        self
+    }
+
+    pub fn dimension_fit(&self) -> DimensionFit {
+        self.dimension_fit
+    }
+}
+
 /// High-level full-text / scalar query configuration for hybrid_search.
 /// Mirrors Python `Collection.hybrid_search(query=...)` semantics.
 #[derive(Clone, Debug)]
@@ -253,8 +458,31 @@ pub struct Collection<Ef = Box<dyn EmbeddingFunction>> {
     distance: DistanceMetric,
     embedding_function: Option<Ef>,
     metadata: Option<serde_json::Value>,
+    max_statement_bytes: usize,
+    max_metadata_bytes: usize,
+    insert_batch_size: usize,
+    transactional: bool,
+    vector_literal_format: VectorLiteralFormat,
+    normalize_queries: bool,
+    id_strategy: IdStrategy,
+    snapshot_scans: bool,
+    vector_fields: Vec<VectorField>,
 }
 
+/// Default per-statement byte budget used by the write paths: 4 MiB, the
+/// smallest `max_allowed_packet` default among supported servers.
+pub const DEFAULT_MAX_STATEMENT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Default cap on one record's metadata, serialized as JSON: 1 MiB.
+pub const DEFAULT_MAX_METADATA_BYTES: usize = 1024 * 1024;
+
+/// Default number of rows written by one multi-row INSERT.
+pub const DEFAULT_INSERT_BATCH_SIZE: usize = 1000;
+/// Upper bound on rows per INSERT: four placeholders per row must stay within
+/// the protocol's 65535 prepared-statement parameters. Collections with named
+/// vector fields use one more placeholder per field and fewer rows.
+pub const MAX_INSERT_BATCH_SIZE: usize = u16::MAX as usize / 4;
+
 impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
     pub fn new(
         client: Arc<ServerClient>,
@@ -273,6 +501,169 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             distance,
             embedding_function,
             metadata,
+            max_statement_bytes: DEFAULT_MAX_STATEMENT_BYTES,
+            max_metadata_bytes: DEFAULT_MAX_METADATA_BYTES,
+            insert_batch_size: DEFAULT_INSERT_BATCH_SIZE,
+            transactional: true,
+            vector_literal_format: VectorLiteralFormat::default(),
+            normalize_queries: false,
+            id_strategy: IdStrategy::default(),
+            snapshot_scans: false,
+            vector_fields: Vec::new(),
+        }
+    }
+
+    /// Set the estimated size (SQL text plus bound parameters) a single write
+    /// statement may reach. Keep this at or below the server's
+    /// `max_allowed_packet`. Multi-row INSERTs are split into smaller chunks to
+    /// fit; a single row that cannot fit is rejected up front with
+    /// [`SeekDbError::InvalidInput`] instead of an opaque "packet too large".
+    pub fn with_max_statement_bytes(mut self, bytes: usize) -> Self {
+        self.max_statement_bytes = bytes;
+        self
+    }
+
+    pub fn max_statement_bytes(&self) -> usize {
+        self.max_statement_bytes
+    }
+
+    /// Set the largest metadata object, in bytes of serialized JSON, that
+    /// `add`/`update`/`upsert` accept for one record (default
+    /// [`DEFAULT_MAX_METADATA_BYTES`]). Larger metadata is rejected with
+    /// [`SeekDbError::InvalidInput`] before anything is written, instead of
+    /// failing on the server's JSON or packet limits partway through a batch.
+    pub fn with_max_metadata_bytes(mut self, bytes: usize) -> Self {
+        self.max_metadata_bytes = bytes;
+        self
+    }
+
+    pub fn max_metadata_bytes(&self) -> usize {
+        self.max_metadata_bytes
+    }
+
+    /// Set how many rows `add`/`upsert` write per INSERT statement
+    /// (default [`DEFAULT_INSERT_BATCH_SIZE`], clamped to `1..=MAX_INSERT_BATCH_SIZE`).
+    pub fn with_insert_batch_size(mut self, rows: usize) -> Self {
+        self.insert_batch_size = rows.clamp(1, MAX_INSERT_BATCH_SIZE);
+        self
+    }
+
+    pub fn insert_batch_size(&self) -> usize {
+        self.insert_batch_size
+    }
+
+    /// Run `add`/`update`/`upsert` in autocommit mode instead of one transaction
+    /// per call. Statements then take effect as they run, so a failure can leave
+    /// earlier rows stored; it is reported as [`SeekDbError::PartialWrite`].
+    pub fn no_transaction(mut self) -> Self {
+        self.transactional = false;
+        self
+    }
+
+    /// Whether `add`/`update`/`upsert` run inside a transaction (the default).
+    pub fn is_transactional(&self) -> bool {
+        self.transactional
+    }
+
+    /// Choose how embeddings are written into statements by the write paths
+    /// and vector queries.
+    pub fn with_vector_literal_format(mut self, format: VectorLiteralFormat) -> Self {
+        self.vector_literal_format = format;
+        self
+    }
+
+    pub fn vector_literal_format(&self) -> VectorLiteralFormat {
+        self.vector_literal_format
+    }
+
+    /// L2-normalize query vectors on the client before searching, for
+    /// collections of unit vectors (e.g. inner-product search over normalized
+    /// embeddings). Skip it per call with [`QueryOptions::with_assume_normalized`].
+    pub fn with_normalize_queries(mut self, enabled: bool) -> Self {
+        self.normalize_queries = enabled;
+        self
+    }
+
+    pub fn normalizes_queries(&self) -> bool {
+        self.normalize_queries
+    }
+
+    /// Choose how [`add_auto`](Self::add_auto) generates ids (default
+    /// [`IdStrategy::UuidV4`]).
+    pub fn with_id_strategy(mut self, strategy: IdStrategy) -> Self {
+        self.id_strategy = strategy;
+        self
+    }
+
+    pub fn id_strategy(&self) -> IdStrategy {
+        self.id_strategy
+    }
+
+    /// Run long scans ([`get_stream`](Self::get_stream),
+    /// [`iter_all`](Self::iter_all), [`export_jsonl`](Self::export_jsonl),
+    /// [`export_npy`](Self::export_npy)) inside one read-only `REPEATABLE READ`
+    /// transaction, so every page sees the collection as of the first read and
+    /// writes made during the scan are not reflected in it.
+    ///
+    /// The scan then holds one pooled connection from start to finish, and the
+    /// server must keep the row versions the snapshot can still see until it
+    /// ends; on a busy collection a long scan makes the server retain old
+    /// versions (more memory and storage, slower version cleanup). No row locks
+    /// are taken, so writers are not blocked. Drop or finish the scan promptly.
+    pub fn with_snapshot_scans(mut self, enabled: bool) -> Self {
+        self.snapshot_scans = enabled;
+        self
+    }
+
+    pub fn snapshot_scans(&self) -> bool {
+        self.snapshot_scans
+    }
+
+    /// Replace the embedding function, e.g. after upgrading the model.
+    ///
+    /// The new function must produce vectors of the collection's
+    /// [`dimension`](Self::dimension): the table's vector column is fixed at
+    /// creation, so mismatched embeddings are rejected by `add`/`upsert`/
+    /// `query_texts`. Vectors already stored are not re-embedded; rows written
+    /// by the old model are only comparable to the new one's queries if both
+    /// models share an embedding space.
+    pub fn set_embedding_function(&mut self, ef: Ef) {
+        self.embedding_function = Some(ef);
+    }
+
+    /// Builder-style [`set_embedding_function`](Self::set_embedding_function).
+    pub fn with_embedding_function(mut self, ef: Ef) -> Self {
+        self.set_embedding_function(ef);
+        self
+    }
+
+    pub fn embedding_function(&self) -> Option<&Ef> {
+        self.embedding_function.as_ref()
+    }
+
+    /// Erase the embedding function type, so collections using different
+    /// functions can share one type and the function can be swapped for any
+    /// other at runtime.
+    pub fn boxed(self) -> Collection<Box<dyn EmbeddingFunction>> {
+        Collection {
+            client: self.client,
+            name: self.name,
+            id: self.id,
+            dimension: self.dimension,
+            distance: self.distance,
+            embedding_function: self
+                .embedding_function
+                .map(|ef| Box::new(ef) as Box<dyn EmbeddingFunction>),
+            metadata: self.metadata,
+            max_statement_bytes: self.max_statement_bytes,
+            max_metadata_bytes: self.max_metadata_bytes,
+            insert_batch_size: self.insert_batch_size,
+            transactional: self.transactional,
+            vector_literal_format: self.vector_literal_format,
+            normalize_queries: self.normalize_queries,
+            id_strategy: self.id_strategy,
+            snapshot_scans: self.snapshot_scans,
+            vector_fields: self.vector_fields,
         }
     }
 
@@ -296,10 +687,189 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         self.metadata.as_ref()
     }
 
+    /// Named vector columns besides `embedding`, as created with
+    /// [`HnswConfig::with_vector_field`](crate::HnswConfig::with_vector_field).
+    ///
+    /// They are written by `add` (see [`AddBatch::vector_field`]) and searched
+    /// with [`QueryOptions::with_vector_field`]. `get`, [`Record`], `update`,
+    /// `upsert` and `reembed` only see `embedding`; `update` and `upsert`
+    /// leave named vectors untouched, and `merge_from` and `export_jsonl`
+    /// refuse collections that have them.
+    pub fn vector_fields(&self) -> &[VectorField] {
+        &self.vector_fields
+    }
+
+    pub(crate) fn with_vector_fields(mut self, fields: Vec<VectorField>) -> Self {
+        self.vector_fields = fields;
+        self
+    }
+
+    /// The named vector field `name`, or `InvalidInput` if there is none.
+    fn vector_field(&self, name: &str) -> Result<&VectorField> {
+        self.vector_fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                SeekDbError::InvalidInput(format!(
+                    "collection {} has no vector field '{name}'",
+                    self.name
+                ))
+            })
+    }
+
+    /// Tenant the collection lives in.
+    pub fn tenant(&self) -> &str {
+        self.client.tenant()
+    }
+
+    fn check_metadata_sizes(&self, ids: &[String], metadatas: Option<&[Metadata]>) -> Result<()> {
+        for (id, meta) in ids.iter().zip(metadatas.unwrap_or_default()) {
+            check_metadata_size(id, meta, self.max_metadata_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Check named vectors given to `add` against the collection's vector
+    /// fields and return their column names in order.
+    fn check_named_vectors<'a>(
+        &'a self,
+        ids: &[String],
+        vectors: &[(&str, &[Embedding])],
+    ) -> Result<Vec<&'a str>> {
+        let mut columns: Vec<&str> = Vec::with_capacity(vectors.len());
+        for (name, embs) in vectors {
+            let field = self.vector_field(name)?;
+            if columns.contains(&field.name.as_str()) {
+                return Err(SeekDbError::InvalidInput(format!(
+                    "vector field '{name}' given more than once"
+                )));
+            }
+            if embs.len() != ids.len() {
+                return Err(SeekDbError::InvalidInput(format!(
+                    "vector field '{name}' has {} embeddings for {} ids",
+                    embs.len(),
+                    ids.len()
+                )));
+            }
+            for emb in embs.iter() {
+                validate_vector(emb)?;
+                if emb.len() as u32 != field.dimension {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "vector field '{name}' has dimension {}, got an embedding of {}",
+                        field.dimension,
+                        emb.len()
+                    )));
+                }
+            }
+            columns.push(&field.name);
+        }
+        Ok(columns)
+    }
+
+    fn check_statement_size(&self, id: &str, estimated: usize) -> Result<()> {
+        if estimated > self.max_statement_bytes {
+            return Err(SeekDbError::InvalidInput(format!(
+                "write statement for id '{id}' is about {estimated} bytes, above the {} byte budget; \
+                 raise the server's max_allowed_packet and Collection::with_max_statement_bytes, \
+                 or shrink the document/metadata/embedding",
+                self.max_statement_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write `rows` with multi-row INSERT statements. Each statement holds at
+    /// most `insert_batch_size` rows and stays under the statement byte budget.
+    /// `written_before` counts rows the caller already stored. If rows were
+    /// stored before a failure, the error is a [`SeekDbError::PartialWrite`].
+    /// Returns the server's affected-row count summed over the statements.
+    async fn insert_rows(
+        &self,
+        session: &mut WriteSession<'_>,
+        rows: &[InsertRow<'_>],
+        written_before: usize,
+        on_conflict: OnConflict,
+        extra_columns: &[&str],
+    ) -> Result<u64> {
+        let table = CollectionNames::table_name(&self.name);
+        let prefix = insert_prefix(&table, on_conflict, extra_columns);
+        let suffix = insert_suffix(on_conflict, extra_columns);
+        let overhead = prefix.len() + suffix.len();
+        let placeholders = insert_row_placeholders(extra_columns.len());
+        let max_rows = self
+            .insert_batch_size
+            .min(u16::MAX as usize / (4 + extra_columns.len()));
+
+        // +2 for the ", " separator between row tuples.
+        let row_bytes: Vec<usize> = rows
+            .iter()
+            .map(|row| estimate_statement_bytes(&placeholders, &row.param_lens()) + 2)
+            .collect();
+        // Reject oversized rows before anything is written.
+        for (row, bytes) in rows.iter().zip(&row_bytes) {
+            self.check_statement_size(row.id, overhead + bytes)?;
+        }
+        let chunks = plan_insert_chunks(&row_bytes, overhead, max_rows, self.max_statement_bytes);
+
+        let mut written = written_before;
+        let mut affected = 0;
+        for range in chunks {
+            let chunk = &rows[range];
+            let sql = format!(
+                "{prefix}{}{suffix}",
+                vec![placeholders.as_str(); chunk.len()].join(", ")
+            );
+            let mut query = sqlx::query(&sql);
+            for row in chunk {
+                query = query
+                    .bind(row.id.as_bytes())
+                    .bind(row.document.as_str())
+                    .bind(row.metadata.as_deref())
+                    .bind(row.vector.as_str());
+                for vector in &row.extra_vectors {
+                    query = query.bind(vector.as_str());
+                }
+            }
+            affected += session
+                .execute(query)
+                .await
+                .map_err(|e| partial_write(written, e))?
+                .rows_affected();
+            written += chunk.len();
+        }
+        Ok(affected)
+    }
+
+    /// Schema of the underlying table: dimension, metric, vector/full-text index
+    /// details, columns and the current row count.
+    pub async fn describe(&self) -> Result<CollectionSchema> {
+        self.client.describe_collection(&self.name).await
+    }
+
+    /// Check that rows described by `other` can be written into this
+    /// collection: same dimension and distance metric, and every column of
+    /// `other` present here. Mismatches are reported as
+    /// [`SeekDbError::InvalidInput`], e.g. `"dimension 384 != 768"`.
+    pub async fn schema_compatible_with(&self, other: &CollectionSchema) -> Result<()> {
+        schema_mismatch(&self.describe().await?, other).map_or(Ok(()), |msg| {
+            Err(SeekDbError::InvalidInput(format!(
+                "collection {} is not compatible with {}: {msg}",
+                self.name, other.name
+            )))
+        })
+    }
+
     /// Builder-style wrapper around `add` that accepts an [`AddBatch`].
     pub async fn add_batch(&self, batch: AddBatch<'_>) -> Result<()> {
-        self.add(batch.ids, batch.embeddings, batch.metadatas, batch.documents)
-            .await
+        self.add_rows(
+            batch.ids,
+            batch.embeddings,
+            batch.metadatas,
+            batch.documents,
+            &batch.vectors,
+            batch.on_conflict,
+        )
+        .await
     }
 
     // DML
@@ -309,25 +879,49 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         embeddings: Option<&[Embedding]>,
         metadatas: Option<&[Metadata]>,
         documents: Option<&[String]>,
+    ) -> Result<()> {
+        self.add_rows(
+            ids,
+            embeddings,
+            metadatas,
+            documents,
+            &[],
+            OnConflict::Error,
+        )
+        .await
+    }
+
+    async fn add_rows(
+        &self,
+        ids: &[String],
+        embeddings: Option<&[Embedding]>,
+        metadatas: Option<&[Metadata]>,
+        documents: Option<&[String]>,
+        vectors: &[(&str, &[Embedding])],
+        on_conflict: OnConflict,
     ) -> Result<()> {
         if ids.is_empty() {
             return Err(SeekDbError::InvalidInput("ids must not be empty".into()));
         }
         // Validate document/metadata lengths (when provided)
-        if let Some(docs) = documents {
-            if !docs.is_empty() && docs.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "documents length does not match ids length".into(),
-                ));
-            }
+        if let Some(docs) = documents
+            && !docs.is_empty()
+            && docs.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "documents length does not match ids length".into(),
+            ));
         }
-        if let Some(metas) = metadatas {
-            if !metas.is_empty() && metas.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "metadatas length does not match ids length".into(),
-                ));
-            }
+        if let Some(metas) = metadatas
+            && !metas.is_empty()
+            && metas.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "metadatas length does not match ids length".into(),
+            ));
         }
+        self.check_metadata_sizes(ids, metadatas)?;
+        let extra_columns = self.check_named_vectors(ids, vectors)?;
 
         // Determine embeddings: prefer provided, otherwise auto-generate from documents using embedding_function.
         let embeddings: Vec<Embedding> = if let Some(embs) = embeddings {
@@ -349,6 +943,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 )));
             }
             for emb in &generated {
+                validate_vector(emb)?;
                 if emb.len() as u32 != self.dimension {
                     return Err(SeekDbError::InvalidInput(format!(
                         "embedding dimension {} does not match collection dimension {}",
@@ -364,86 +959,314 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             ));
         };
 
-        let table = CollectionNames::table_name(&self.name);
-        let sql = format!(
-            "INSERT INTO `{table}` (_id, document, metadata, embedding) VALUES (?, ?, ?, ?)"
-        );
-
-        for i in 0..ids.len() {
-            let id_bytes = ids[i].as_bytes();
-            let doc = documents
-                .and_then(|d| d.get(i))
-                .map(|s| s.as_str())
-                .unwrap_or("");
-            let meta = metadatas.and_then(|m| m.get(i));
-            let emb = &embeddings[i];
-
-            sqlx::query(&sql)
-                .bind(id_bytes)
-                .bind(doc)
-                .bind(meta.map(|v| serde_json::to_string(v).unwrap_or_default()))
-                .bind(vector_to_string(emb))
-                .execute(self.client.pool())
-                .await?;
-        }
-
-        Ok(())
-    }
-
-    /// Builder-style wrapper around `update` that accepts an [`UpdateBatch`].
-    pub async fn update_batch(&self, batch: UpdateBatch<'_>) -> Result<()> {
-        self.update(batch.ids, batch.embeddings, batch.metadatas, batch.documents)
-            .await
+        let rows: Vec<InsertRow<'_>> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| InsertRow {
+                id,
+                document: documents
+                    .and_then(|d| d.get(i))
+                    .cloned()
+                    .unwrap_or_default(),
+                metadata: metadatas
+                    .and_then(|m| m.get(i))
+                    .map(|v| serde_json::to_string(v).unwrap_or_default()),
+                vector: vector_to_string(&embeddings[i], self.vector_literal_format),
+                extra_vectors: vectors
+                    .iter()
+                    .map(|(_, embs)| vector_to_string(&embs[i], self.vector_literal_format))
+                    .collect(),
+            })
+            .collect();
+        let mut session = self.client.write_session(self.transactional).await?;
+        let result = self
+            .insert_rows(&mut session, &rows, 0, on_conflict, &extra_columns)
+            .await;
+        session.finish(result).await.map(|_| ())
     }
 
-    pub async fn update(
+    /// [`add`](Self::add) without ids: one id per row is generated with the
+    /// collection's [`IdStrategy`] and returned in row order.
+    ///
+    /// Rows are counted from `embeddings`, or from `documents` when they are
+    /// embedded with the collection's embedding function. With
+    /// [`IdStrategy::ContentHash`] re-adding identical content fails on the
+    /// existing id instead of storing a duplicate.
+    pub async fn add_auto(
         &self,
-        ids: &[String],
         embeddings: Option<&[Embedding]>,
         metadatas: Option<&[Metadata]>,
         documents: Option<&[String]>,
-    ) -> Result<()> {
-        if embeddings.is_none() && metadatas.is_none() && documents.is_none() {
+    ) -> Result<Vec<String>> {
+        // Content hashes cover the embedding, so generate it before the ids.
+        let generated: Vec<Embedding>;
+        let embeddings = match (embeddings, documents) {
+            (Some(embs), _) => embs,
+            (None, Some(docs)) => {
+                let ef = self.embedding_function.as_ref().ok_or_else(|| {
+                    SeekDbError::InvalidInput(
+                        "documents provided but no embeddings and no embedding function; provide embeddings or set embedding_function"
+                            .into(),
+                    )
+                })?;
+                generated = ef.embed_documents(docs).await?;
+                &generated
+            }
+            (None, None) => {
+                return Err(SeekDbError::InvalidInput(
+                    "either provide embeddings or provide documents with embedding_function".into(),
+                ));
+            }
+        };
+        if embeddings.is_empty() {
             return Err(SeekDbError::InvalidInput(
-                "nothing to update: provide embeddings/documents/metadatas".into(),
+                "embeddings must not be empty".into(),
             ));
         }
+        let ids: Vec<String> = embeddings
+            .iter()
+            .enumerate()
+            .map(|(i, emb)| {
+                let document = documents.and_then(|d| d.get(i)).map(String::as_str);
+                self.id_strategy.generate(document, emb)
+            })
+            .collect();
+        self.add(&ids, Some(embeddings), metadatas, documents)
+            .await?;
+        Ok(ids)
+    }
 
-        // Validate lengths only for provided fields
-        if let Some(docs) = documents {
-            if !docs.is_empty() && docs.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "documents length does not match ids length".into(),
-                ));
-            }
+    /// Insert pre-embedded items from a stream, `batch_size` rows per INSERT
+    /// round, and return how many rows were stored.
+    ///
+    /// The stream is not polled while a batch is being written, so a slow
+    /// database applies backpressure to the producer. Each batch is written
+    /// like [`add`](Self::add) (in its own transaction unless
+    /// [`no_transaction`](Self::no_transaction) is set); a failure after earlier
+    /// batches were stored is reported as [`SeekDbError::PartialWrite`].
+    pub async fn add_stream<S>(&self, items: S, batch_size: usize) -> Result<u64>
+    where
+        S: Stream<Item = (String, Embedding, Option<Metadata>, Option<String>)>,
+    {
+        if batch_size == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "batch_size must be greater than zero".into(),
+            ));
         }
-        if let Some(metas) = metadatas {
-            if !metas.is_empty() && metas.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "metadatas length does not match ids length".into(),
-                ));
+
+        let mut items = std::pin::pin!(items);
+        let mut batch: Vec<(String, Embedding, Option<Metadata>, Option<String>)> =
+            Vec::with_capacity(batch_size);
+        let mut stored = 0usize;
+        loop {
+            let next = items.next().await;
+            let done = next.is_none();
+            if let Some(item) = next {
+                validate_vector(&item.1).map_err(|e| partial_write(stored, e))?;
+                if item.1.len() as u32 != self.dimension {
+                    return Err(partial_write(
+                        stored,
+                        SeekDbError::InvalidInput(format!(
+                            "embedding dimension {} does not match collection dimension {} for id {}",
+                            item.1.len(),
+                            self.dimension,
+                            item.0
+                        )),
+                    ));
+                }
+                batch.push(item);
             }
-        }
-        let embeddings: Option<Vec<Embedding>> = if let Some(embs) = embeddings {
-            if embs.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "embeddings length does not match ids length".into(),
-                ));
+
+            if batch.len() >= batch_size || (done && !batch.is_empty()) {
+                self.write_item_batch(&batch, stored).await?;
+                stored += batch.len();
+                batch.clear();
             }
-            for emb in embs {
-                if emb.len() as u32 != self.dimension {
-                    return Err(SeekDbError::InvalidInput(format!(
-                        "embedding dimension {} does not match collection dimension {}",
-                        emb.len(),
-                        self.dimension
-                    )));
-                }
+            if done {
+                return Ok(stored as u64);
             }
-            Some(embs.to_vec())
-        } else if let Some(docs) = documents {
-            let ef = self.embedding_function.as_ref().ok_or_else(|| {
-                SeekDbError::InvalidInput(
-                    "documents provided but no embeddings and no embedding function; provide embeddings or set embedding_function"
+        }
+    }
+
+    /// Restore rows written by [`export_jsonl`](Self::export_jsonl): each
+    /// non-blank line is a [`Record`] JSON object, inserted `batch_size` rows
+    /// per round like [`add_stream`](Self::add_stream). Returns the number of
+    /// rows stored.
+    ///
+    /// Every record needs an embedding of the collection's dimension. A line
+    /// that fails to parse or validate is reported with its 1-based line
+    /// number, as [`SeekDbError::PartialWrite`] if earlier batches were stored.
+    pub async fn import_jsonl<R: tokio::io::AsyncBufRead + Unpin>(
+        &self,
+        reader: R,
+        batch_size: usize,
+    ) -> Result<u64> {
+        use tokio::io::AsyncBufReadExt;
+
+        if batch_size == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "batch_size must be greater than zero".into(),
+            ));
+        }
+
+        let mut batch: Vec<(String, Embedding, Option<Metadata>, Option<String>)> =
+            Vec::with_capacity(batch_size);
+        let mut stored = 0usize;
+        let mut lines = reader.lines();
+        let mut line_no = 0usize;
+        loop {
+            line_no += 1;
+            let line_err = |msg: String| {
+                partial_write(
+                    stored,
+                    SeekDbError::InvalidInput(format!("line {line_no}: {msg}")),
+                )
+            };
+            let Some(line) = lines
+                .next_line()
+                .await
+                .map_err(|e| line_err(e.to_string()))?
+            else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: Record =
+                serde_json::from_str(&line).map_err(|e| line_err(e.to_string()))?;
+            let embedding = record
+                .embedding
+                .ok_or_else(|| line_err(format!("record {} has no embedding", record.id)))?;
+            validate_vector(&embedding).map_err(|e| line_err(e.to_string()))?;
+            if embedding.len() as u32 != self.dimension {
+                return Err(line_err(format!(
+                    "embedding dimension {} does not match collection dimension {}",
+                    embedding.len(),
+                    self.dimension
+                )));
+            }
+            batch.push((record.id, embedding, record.metadata, record.document));
+
+            if batch.len() >= batch_size {
+                self.write_item_batch(&batch, stored).await?;
+                stored += batch.len();
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            self.write_item_batch(&batch, stored).await?;
+            stored += batch.len();
+        }
+        Ok(stored as u64)
+    }
+
+    /// Insert one batch of validated `(id, embedding, metadata, document)`
+    /// items in its own write session; `stored` counts rows written by earlier
+    /// batches, for [`SeekDbError::PartialWrite`].
+    async fn write_item_batch(
+        &self,
+        batch: &[(String, Embedding, Option<Metadata>, Option<String>)],
+        stored: usize,
+    ) -> Result<()> {
+        for (id, _, metadata, _) in batch {
+            if let Some(meta) = metadata {
+                check_metadata_size(id, meta, self.max_metadata_bytes)
+                    .map_err(|e| partial_write(stored, e))?;
+            }
+        }
+        let rows: Vec<InsertRow<'_>> = batch
+            .iter()
+            .map(|(id, embedding, metadata, document)| InsertRow {
+                id,
+                document: document.clone().unwrap_or_default(),
+                metadata: metadata
+                    .as_ref()
+                    .map(|v| serde_json::to_string(v).unwrap_or_default()),
+                vector: vector_to_string(embedding, self.vector_literal_format),
+                extra_vectors: Vec::new(),
+            })
+            .collect();
+        let result = async {
+            let mut session = self.client.write_session(self.transactional).await?;
+            let result = self
+                .insert_rows(&mut session, &rows, stored, OnConflict::Error, &[])
+                .await;
+            session.finish(result).await
+        }
+        .await;
+        // Without a transaction, insert_rows already counts this batch's stored rows.
+        result.map(|_| ()).map_err(|e| match e {
+            SeekDbError::PartialWrite { .. } => e,
+            other => partial_write(stored, other),
+        })
+    }
+
+    /// Builder-style wrapper around `update` that accepts an [`UpdateBatch`].
+    pub async fn update_batch(&self, batch: UpdateBatch<'_>) -> Result<u64> {
+        self.update(
+            batch.ids,
+            batch.embeddings,
+            batch.metadatas,
+            batch.documents,
+        )
+        .await
+    }
+
+    /// Update the given ids in place and return how many rows matched.
+    ///
+    /// Ids that do not exist are skipped and do not count towards the total.
+    pub async fn update(
+        &self,
+        ids: &[String],
+        embeddings: Option<&[Embedding]>,
+        metadatas: Option<&[Metadata]>,
+        documents: Option<&[String]>,
+    ) -> Result<u64> {
+        if embeddings.is_none() && metadatas.is_none() && documents.is_none() {
+            return Err(SeekDbError::InvalidInput(
+                "nothing to update: provide embeddings/documents/metadatas".into(),
+            ));
+        }
+
+        // Validate lengths only for provided fields
+        if let Some(docs) = documents
+            && !docs.is_empty()
+            && docs.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "documents length does not match ids length".into(),
+            ));
+        }
+        if let Some(metas) = metadatas
+            && !metas.is_empty()
+            && metas.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "metadatas length does not match ids length".into(),
+            ));
+        }
+        self.check_metadata_sizes(ids, metadatas)?;
+        let embeddings: Option<Vec<Embedding>> = if let Some(embs) = embeddings {
+            if embs.len() != ids.len() {
+                return Err(SeekDbError::InvalidInput(
+                    "embeddings length does not match ids length".into(),
+                ));
+            }
+            for emb in embs {
+                validate_vector(emb)?;
+                if emb.len() as u32 != self.dimension {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "embedding dimension {} does not match collection dimension {}",
+                        emb.len(),
+                        self.dimension
+                    )));
+                }
+            }
+            Some(embs.to_vec())
+        } else if let Some(docs) = documents {
+            let ef = self.embedding_function.as_ref().ok_or_else(|| {
+                SeekDbError::InvalidInput(
+                    "documents provided but no embeddings and no embedding function; provide embeddings or set embedding_function"
                         .into(),
                 )
             })?;
@@ -454,6 +1277,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 ));
             }
             for emb in &generated {
+                validate_vector(emb)?;
                 if emb.len() as u32 != self.dimension {
                     return Err(SeekDbError::InvalidInput(format!(
                         "embedding dimension {} does not match collection dimension {}",
@@ -469,50 +1293,68 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
 
         let table = CollectionNames::table_name(&self.name);
 
-        for i in 0..ids.len() {
-            let mut sets: Vec<(String, String)> = Vec::new();
-            if let Some(docs) = documents {
-                if let Some(doc) = docs.get(i) {
+        let mut session = self.client.write_session(self.transactional).await?;
+        let result = async {
+            let mut written = 0usize;
+            let mut affected = 0u64;
+            for (i, id) in ids.iter().enumerate() {
+                let mut sets: Vec<(String, String)> = Vec::new();
+                if let Some(docs) = documents
+                    && let Some(doc) = docs.get(i)
+                {
                     sets.push(("document".to_string(), doc.clone()));
                 }
-            }
-            if let Some(metas) = metadatas {
-                if let Some(meta) = metas.get(i) {
+                if let Some(metas) = metadatas
+                    && let Some(meta) = metas.get(i)
+                {
                     sets.push((
                         "metadata".to_string(),
                         serde_json::to_string(meta).unwrap_or_default(),
                     ));
                 }
-            }
-            if let Some(embs) = embeddings.as_ref() {
-                if let Some(emb) = embs.get(i) {
-                    sets.push(("embedding".to_string(), vector_to_string(emb)));
+                if let Some(embs) = embeddings.as_ref()
+                    && let Some(emb) = embs.get(i)
+                {
+                    sets.push((
+                        "embedding".to_string(),
+                        vector_to_string(emb, self.vector_literal_format),
+                    ));
                 }
-            }
 
-            if sets.is_empty() {
-                continue;
-            }
+                if sets.is_empty() {
+                    continue;
+                }
 
-            let set_clause = sets
-                .iter()
-                .map(|(k, _)| format!("{k} = ?"))
-                .collect::<Vec<_>>()
-                .join(", ");
-            let sql = format!("UPDATE `{table}` SET {set_clause} WHERE _id = ?");
-            let mut query = sqlx::query(&sql);
-            for (_, v) in &sets {
-                query = query.bind(v);
+                let set_clause = sets
+                    .iter()
+                    .map(|(k, _)| format!("{k} = ?"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let sql = format!("UPDATE `{table}` SET {set_clause} WHERE _id = ?");
+                let mut params: Vec<usize> = sets.iter().map(|(_, v)| v.len()).collect();
+                params.push(id.len());
+                self.check_statement_size(id, estimate_statement_bytes(&sql, &params))
+                    .map_err(|e| partial_write(written, e))?;
+                let mut query = sqlx::query(&sql);
+                for (_, v) in &sets {
+                    query = query.bind(v);
+                }
+                query = query.bind(id.as_bytes());
+                let done = session
+                    .execute(query)
+                    .await
+                    .map_err(|e| partial_write(written, e))?;
+                affected += done.rows_affected();
+                written += 1;
             }
-            query = query.bind(ids[i].as_bytes());
-            query.execute(self.client.pool()).await?;
+            Ok(affected)
         }
-
-        Ok(())
+        .await;
+        session.finish(result).await
     }
 
     /// Builder-style wrapper around `upsert` that accepts an [`UpsertBatch`].
-    pub async fn upsert_batch(&self, batch: UpsertBatch<'_>) -> Result<()> {
+    pub async fn upsert_batch(&self, batch: UpsertBatch<'_>) -> Result<UpsertReport> {
         self.upsert(
             batch.ids,
             batch.embeddings,
@@ -528,7 +1370,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         embeddings: Option<&[Embedding]>,
         metadatas: Option<&[Metadata]>,
         documents: Option<&[String]>,
-    ) -> Result<()> {
+    ) -> Result<UpsertReport> {
         // Mirror Python semantics:
         // - metadata-only upsert allowed
         // - Only fields provided in this call are updated; others keep existing values
@@ -544,20 +1386,23 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             ));
         }
 
-        if let Some(docs) = documents {
-            if !docs.is_empty() && docs.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "documents length does not match ids length".into(),
-                ));
-            }
+        if let Some(docs) = documents
+            && !docs.is_empty()
+            && docs.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "documents length does not match ids length".into(),
+            ));
         }
-        if let Some(metas) = metadatas {
-            if !metas.is_empty() && metas.len() != ids.len() {
-                return Err(SeekDbError::InvalidInput(
-                    "metadatas length does not match ids length".into(),
-                ));
-            }
+        if let Some(metas) = metadatas
+            && !metas.is_empty()
+            && metas.len() != ids.len()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "metadatas length does not match ids length".into(),
+            ));
         }
+        self.check_metadata_sizes(ids, metadatas)?;
         let embeddings: Option<Vec<Embedding>> = if let Some(embs) = embeddings {
             validate_lengths(ids, embs, metadatas, documents, self.dimension)?;
             Some(embs.to_vec())
@@ -571,6 +1416,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                     ));
                 }
                 for emb in &generated {
+                    validate_vector(emb)?;
                     if emb.len() as u32 != self.dimension {
                         return Err(SeekDbError::InvalidInput(format!(
                             "embedding dimension {} does not match collection dimension {}",
@@ -589,122 +1435,159 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         };
 
         let table = CollectionNames::table_name(&self.name);
+        const LOOKUP_INCLUDE: [IncludeField; 3] = [
+            IncludeField::Documents,
+            IncludeField::Metadatas,
+            IncludeField::Embeddings,
+        ];
+        let lookup_sql = format!(
+            "SELECT {} FROM `{table}` WHERE _id = ? LIMIT 1",
+            build_select_clause(Some(&LOOKUP_INCLUDE))
+        );
 
-        for i in 0..ids.len() {
-            let id = &ids[i];
-
-            // Fetch existing row
-            let existing = self
-                .get(
-                    Some(&[id.clone()]),
-                    None,
-                    None,
-                    Some(1),
-                    Some(0),
-                    Some(&[
-                        IncludeField::Documents,
-                        IncludeField::Metadatas,
-                        IncludeField::Embeddings,
-                    ]),
-                )
-                .await?;
-
-            let exists = !existing.ids.is_empty();
-            let existing_doc = existing
-                .documents
-                .as_ref()
-                .and_then(|docs| docs.first())
-                .cloned();
-            let existing_meta = existing
-                .metadatas
-                .as_ref()
-                .and_then(|ms| ms.first())
-                .cloned();
-            let existing_emb = existing
-                .embeddings
-                .as_ref()
-                .and_then(|es| es.first())
-                .cloned();
-
-            let new_doc = documents.and_then(|d| d.get(i)).cloned();
-            let new_meta = metadatas.and_then(|m| m.get(i)).cloned();
-            let new_emb = embeddings.as_ref().and_then(|e| e.get(i)).cloned();
-
-            let (final_doc, final_meta, final_emb) = merge_values(
-                existing_doc,
-                existing_meta,
-                existing_emb,
-                new_doc,
-                new_meta,
-                new_emb,
-            );
-
-            if exists {
-                // Update only provided fields
-                let mut sets: Vec<(String, String)> = Vec::new();
-                if documents.is_some() {
-                    sets.push(("document".to_string(), final_doc.unwrap_or_default()));
-                }
-                if metadatas.is_some() {
-                    sets.push((
-                        "metadata".to_string(),
-                        serde_json::to_string(&final_meta).unwrap_or_default(),
-                    ));
-                }
-                if embeddings.is_some() {
-                    if let Some(emb) = final_emb.as_ref() {
-                        sets.push(("embedding".to_string(), vector_to_string(emb)));
-                    }
+        let mut session = self.client.write_session(self.transactional).await?;
+        let result = async {
+            // New rows are buffered and written with multi-row INSERTs; `written`
+            // counts rows already stored so a failure can report it.
+            let mut pending: Vec<InsertRow<'_>> = Vec::new();
+            let mut pending_ids: HashSet<&str> = HashSet::new();
+            let mut written = 0usize;
+            let mut report = UpsertReport::default();
+            let mut seen: HashSet<&str> = HashSet::new();
+            for (i, id) in ids.iter().enumerate() {
+                // A repeated id must see the row inserted for its first occurrence.
+                if pending_ids.contains(id.as_str()) {
+                    self.insert_rows(&mut session, &pending, written, OnConflict::Error, &[])
+                        .await?;
+                    written += pending.len();
+                    pending.clear();
+                    pending_ids.clear();
                 }
 
-                if !sets.is_empty() {
-                    let set_clause = sets
-                        .iter()
-                        .map(|(k, _)| format!("{k} = ?"))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    let sql = format!("UPDATE `{table}` SET {set_clause} WHERE _id = ?");
-                    let mut query = sqlx::query(&sql);
-                    for (_, v) in &sets {
-                        query = query.bind(v);
+                // Fetch existing row on the write connection so rows written
+                // earlier in this call are visible.
+                let rows = session
+                    .fetch_all(sqlx::query(&lookup_sql).bind(id.as_bytes()))
+                    .await
+                    .map_err(|e| partial_write(written, e))?;
+                let existing = get_result_from_rows(rows, Some(&LOOKUP_INCLUDE));
+
+                let exists = !existing.ids.is_empty();
+                if seen.insert(id.as_str()) {
+                    if exists {
+                        report.updated.push(id.clone());
+                    } else {
+                        report.inserted.push(id.clone());
                     }
-                    query = query.bind(id.as_bytes());
-                    query.execute(self.client.pool()).await?;
                 }
-            } else {
-                // Insert new row
-                let sql = format!(
-                    "INSERT INTO `{table}` (_id, document, metadata, embedding) VALUES (?, ?, ?, ?)"
+                let existing_doc = existing
+                    .documents
+                    .as_ref()
+                    .and_then(|docs| docs.first())
+                    .cloned();
+                let existing_meta = existing
+                    .metadatas
+                    .as_ref()
+                    .and_then(|ms| ms.first())
+                    .cloned();
+                let existing_emb = existing
+                    .embeddings
+                    .as_ref()
+                    .and_then(|es| es.first())
+                    .cloned();
+
+                let new_doc = documents.and_then(|d| d.get(i)).cloned();
+                let new_meta = metadatas.and_then(|m| m.get(i)).cloned();
+                let new_emb = embeddings.as_ref().and_then(|e| e.get(i)).cloned();
+
+                let (final_doc, final_meta, final_emb) = merge_values(
+                    existing_doc,
+                    existing_meta,
+                    existing_emb,
+                    new_doc,
+                    new_meta,
+                    new_emb,
                 );
-                sqlx::query(&sql)
-                    .bind(id.as_bytes())
-                    .bind(final_doc.unwrap_or_default())
-                    .bind(serde_json::to_string(&final_meta).unwrap_or_default())
-                    .bind(
-                        final_emb
+
+                if exists {
+                    // Update only provided fields
+                    let mut sets: Vec<(String, String)> = Vec::new();
+                    if documents.is_some() {
+                        sets.push(("document".to_string(), final_doc.unwrap_or_default()));
+                    }
+                    if metadatas.is_some() {
+                        sets.push((
+                            "metadata".to_string(),
+                            serde_json::to_string(&final_meta).unwrap_or_default(),
+                        ));
+                    }
+                    if embeddings.is_some()
+                        && let Some(emb) = final_emb.as_ref()
+                    {
+                        sets.push((
+                            "embedding".to_string(),
+                            vector_to_string(emb, self.vector_literal_format),
+                        ));
+                    }
+
+                    if !sets.is_empty() {
+                        let set_clause = sets
+                            .iter()
+                            .map(|(k, _)| format!("{k} = ?"))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let sql = format!("UPDATE `{table}` SET {set_clause} WHERE _id = ?");
+                        let mut params: Vec<usize> = sets.iter().map(|(_, v)| v.len()).collect();
+                        params.push(id.len());
+                        self.check_statement_size(id, estimate_statement_bytes(&sql, &params))
+                            .map_err(|e| partial_write(written, e))?;
+                        let mut query = sqlx::query(&sql);
+                        for (_, v) in &sets {
+                            query = query.bind(v);
+                        }
+                        query = query.bind(id.as_bytes());
+                        session
+                            .execute(query)
+                            .await
+                            .map_err(|e| partial_write(written, e))?;
+                        written += 1;
+                    }
+                } else {
+                    pending_ids.insert(id);
+                    pending.push(InsertRow {
+                        id,
+                        document: final_doc.unwrap_or_default(),
+                        metadata: Some(serde_json::to_string(&final_meta).unwrap_or_default()),
+                        vector: final_emb
                             .as_ref()
-                            .map(vector_to_string)
+                            .map(|emb| vector_to_string(emb, self.vector_literal_format))
                             .unwrap_or_else(|| "[]".into()),
-                    )
-                    .execute(self.client.pool())
-                    .await?;
+                        extra_vectors: Vec::new(),
+                    });
+                }
             }
-        }
 
-        Ok(())
+            self.insert_rows(&mut session, &pending, written, OnConflict::Error, &[])
+                .await?;
+            Ok(report)
+        }
+        .await;
+        session.finish(result).await
     }
 
     /// Builder-style wrapper around `delete` that accepts a [`DeleteQuery`].
-    pub async fn delete_query(&self, query: DeleteQuery<'_>) -> Result<()> {
-        self.delete(query.ids, query.where_meta, query.where_doc).await
+    pub async fn delete_query(&self, query: DeleteQuery<'_>) -> Result<u64> {
+        self.delete(query.ids, query.where_meta, query.where_doc)
+            .await
     }
 
+    /// Delete rows matching the ids and filters and return how many were removed.
     pub async fn delete(
         &self,
         ids: Option<&[String]>,
         where_meta: Option<&Filter>,
         where_doc: Option<&DocFilter>,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         if ids.is_none() && where_meta.is_none() && where_doc.is_none() {
             return Err(SeekDbError::InvalidInput(
                 "must provide at least one of ids/where_meta/where_doc".into(),
@@ -712,14 +1595,220 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         }
 
         let table = CollectionNames::table_name(&self.name);
-        let sql_where = build_where_clause(where_meta, where_doc, ids);
+        let sql_where =
+            build_where_clause_with_dialect(where_meta, where_doc, ids, self.client.json_dialect());
         let sql = format!("DELETE FROM `{table}` {}", sql_where.clause);
         let mut query = sqlx::query(&sql);
         for p in sql_where.params {
             query = bind_metadata(query, &p);
         }
-        query.execute(self.client.pool()).await?;
-        Ok(())
+        let done = self
+            .client
+            .timed_sql(
+                OpKind::Write,
+                query.sql(),
+                query.execute(self.client.pool()),
+            )
+            .await?;
+        Ok(done.rows_affected())
+    }
+
+    /// Remove every record with `TRUNCATE TABLE`, keeping the table, its
+    /// schema and its HNSW and fulltext index definitions, and return the
+    /// number of records it held.
+    ///
+    /// TRUNCATE resets the table rather than deleting row by row, so it is
+    /// fast on large collections, but it is DDL: it commits implicitly and
+    /// cannot be rolled back. The count is taken just before truncating;
+    /// records written between the two statements are removed without being
+    /// counted.
+    pub async fn clear(&self) -> Result<u64> {
+        let count = self.count().await?;
+        let table = CollectionNames::table_name(&self.name);
+        let sql = format!("TRUNCATE TABLE `{table}`");
+        self.client
+            .timed_sql(
+                OpKind::Write,
+                &sql,
+                sqlx::query(&sql).execute(self.client.pool()),
+            )
+            .await?;
+        Ok(count)
+    }
+
+    /// Delete every record matching `filter` in batches of at most
+    /// `batch_size` rows, so a huge delete never holds one long-running lock.
+    ///
+    /// Matching ids are collected in `_id` order and deleted batch by batch;
+    /// `progress` is called after each batch with the cumulative number of
+    /// deleted rows. Returns the total. Rows that start matching after the scan
+    /// has passed their id are not revisited.
+    pub async fn delete_where_streaming(
+        &self,
+        filter: &Filter,
+        batch_size: u32,
+        mut progress: impl FnMut(u64),
+    ) -> Result<u64> {
+        if batch_size == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "batch_size must be greater than 0".into(),
+            ));
+        }
+        let mut deleted = 0u64;
+        let mut after: Option<String> = None;
+        loop {
+            let rows = self
+                .keyset_page("_id", Some(filter), None, after.as_deref(), batch_size)
+                .await?;
+            let ids: Vec<String> = rows.iter().map(id_from_row).collect();
+            let Some(last) = ids.last().cloned() else {
+                break;
+            };
+            deleted += self.delete(Some(&ids), None, None).await?;
+            progress(deleted);
+            if ids.len() < batch_size as usize {
+                break;
+            }
+            after = Some(last);
+        }
+        Ok(deleted)
+    }
+
+    /// Copy every row of `source` into this collection, resolving ids present
+    /// in both according to `on_conflict`. Returns the number of rows written;
+    /// with [`OnConflict::Skip`] rows already in this collection are not
+    /// counted.
+    ///
+    /// Rows are read in `_id` order a page at a time and written per page, so
+    /// memory stays bounded; a failure part-way reports the rows already
+    /// merged as [`SeekDbError::PartialWrite`]. The source schema must pass
+    /// [`schema_compatible_with`](Self::schema_compatible_with). Sources with
+    /// named vector fields are rejected, since only `embedding` is copied.
+    pub async fn merge_from<S: EmbeddingFunction + 'static>(
+        &self,
+        source: &Collection<S>,
+        on_conflict: OnConflict,
+    ) -> Result<u64> {
+        reject_vector_fields(source, "merge_from")?;
+        self.schema_compatible_with(&source.describe().await?)
+            .await?;
+        let select_clause = build_select_clause(Some(&ALL_FIELDS));
+        let mut merged = 0u64;
+        let mut after: Option<String> = None;
+        loop {
+            let rows = source
+                .keyset_page(
+                    &select_clause,
+                    None,
+                    None,
+                    after.as_deref(),
+                    EXPORT_PAGE_SIZE,
+                )
+                .await
+                .map_err(|e| partial_write(merged as usize, e))?;
+            let Some(last) = rows.last() else {
+                return Ok(merged);
+            };
+            after = Some(id_from_row(last));
+            let records = get_result_from_rows(rows, Some(&ALL_FIELDS)).into_records();
+            let rows: Vec<InsertRow<'_>> = records
+                .iter()
+                .map(|r| InsertRow {
+                    id: &r.id,
+                    document: r.document.clone().unwrap_or_default(),
+                    metadata: r
+                        .metadata
+                        .as_ref()
+                        .map(|v| serde_json::to_string(v).unwrap_or_default()),
+                    vector: vector_to_string(
+                        r.embedding.as_ref().unwrap_or(&Vec::new()),
+                        self.vector_literal_format,
+                    ),
+                    extra_vectors: Vec::new(),
+                })
+                .collect();
+
+            let result = async {
+                let mut session = self.client.write_session(self.transactional).await?;
+                let result = self
+                    .insert_rows(&mut session, &rows, merged as usize, on_conflict, &[])
+                    .await;
+                session.finish(result).await
+            }
+            .await;
+            // Without a transaction, insert_rows already counts this page's stored rows.
+            let affected = result.map_err(|e| match e {
+                SeekDbError::PartialWrite { .. } => e,
+                other => partial_write(merged as usize, other),
+            })?;
+            merged += match on_conflict {
+                OnConflict::Skip => affected,
+                OnConflict::Error | OnConflict::Replace => rows.len() as u64,
+            };
+        }
+    }
+
+    /// Regenerate every stored embedding from its document with `new_ef`,
+    /// `batch_size` rows at a time, and return how many rows were re-embedded.
+    ///
+    /// For migrating to a new model of the same dimension; a different
+    /// dimension needs a new collection, since the vector column is fixed at
+    /// creation, and is rejected up front. Rows without a document keep their
+    /// embedding. Each batch is written with [`update`](Self::update); a failure
+    /// after earlier batches is reported as [`SeekDbError::PartialWrite`].
+    /// The collection's own embedding function is left unchanged; see
+    /// [`set_embedding_function`](Self::set_embedding_function).
+    pub async fn reembed(&self, new_ef: &dyn EmbeddingFunction, batch_size: u32) -> Result<u64> {
+        if batch_size == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "batch_size must be greater than 0".into(),
+            ));
+        }
+        if new_ef.dimension() != self.dimension as usize {
+            return Err(SeekDbError::InvalidInput(format!(
+                "embedding function dimension {} != collection dimension {}; \
+                 create a new collection to change dimension",
+                new_ef.dimension(),
+                self.dimension
+            )));
+        }
+        let select_clause = build_select_clause(Some(&[IncludeField::Documents]));
+        let mut reembedded = 0u64;
+        let mut after: Option<String> = None;
+        loop {
+            let rows = self
+                .keyset_page(&select_clause, None, None, after.as_deref(), batch_size)
+                .await
+                .map_err(|e| partial_write(reembedded as usize, e))?;
+            let Some(last) = rows.last() else {
+                return Ok(reembedded);
+            };
+            after = Some(id_from_row(last));
+            let full = rows.len() == batch_size as usize;
+            let mut ids = Vec::new();
+            let mut docs = Vec::new();
+            for row in &rows {
+                let doc = row
+                    .get_string("document")
+                    .map_err(|e| partial_write(reembedded as usize, e))?;
+                if let Some(doc) = doc.filter(|d| !d.is_empty()) {
+                    ids.push(id_from_row(row));
+                    docs.push(doc);
+                }
+            }
+            if !ids.is_empty() {
+                let written = async {
+                    let embeddings = new_ef.embed_documents(&docs).await?;
+                    self.update(&ids, Some(&embeddings), None, None).await
+                }
+                .await;
+                written.map_err(|e| partial_write(reembedded as usize, e))?;
+                reembedded += ids.len() as u64;
+            }
+            if !full {
+                return Ok(reembedded);
+            }
+        }
     }
 
     // DQL
@@ -730,15 +1819,134 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         where_meta: Option<&Filter>,
         where_doc: Option<&DocFilter>,
         include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        self.query_embeddings_with_options(
+            query_embeddings,
+            n_results,
+            where_meta,
+            where_doc,
+            include,
+            &QueryOptions::default(),
+        )
+        .await
+    }
+
+    /// Candidate generation: only `(id, distance)` pairs per query, nearest first.
+    ///
+    /// Selects nothing but `_id` and the distance, for a later stage that
+    /// fetches details for the ids it keeps.
+    pub async fn query_ids(
+        &self,
+        query_embeddings: &[Embedding],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+    ) -> Result<Vec<Vec<(String, f32)>>> {
+        let result = self
+            .query_embeddings(
+                query_embeddings,
+                n_results,
+                where_meta,
+                None,
+                Some(&[IncludeField::Distances]),
+            )
+            .await?;
+        let distances = result.distances.unwrap_or_default();
+        Ok(result
+            .ids
+            .into_iter()
+            .zip(distances)
+            .map(|(ids, dists)| ids.into_iter().zip(dists).collect())
+            .collect())
+    }
+
+    /// Nearest hits for one query vector, grouped by `metadata[group_field]`
+    /// ("top 3 per category").
+    ///
+    /// Fetches `total * GROUP_OVERFETCH` candidates, then walks them nearest
+    /// first and keeps at most `per_group` hits per group until `total` hits
+    /// are kept. Groups are ordered by their nearest hit; hits without the
+    /// field share the `Null` group. Grouping happens client-side, so a group
+    /// whose hits all rank below the candidate set is not returned.
+    pub async fn query_grouped(
+        &self,
+        query: &[f32],
+        group_field: &str,
+        per_group: u32,
+        total: u32,
+        where_meta: Option<&Filter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<Vec<QueryGroup>> {
+        if per_group == 0 || total == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "per_group and total must be at least 1".into(),
+            ));
+        }
+        // Metadata is needed to group; it is dropped again if not requested.
+        let mut fetch: Vec<IncludeField> = match include {
+            Some(fields) => fields.to_vec(),
+            None => vec![
+                IncludeField::Documents,
+                IncludeField::Metadatas,
+                IncludeField::Distances,
+            ],
+        };
+        let keep_metadatas = include_metadatas(include);
+        if !keep_metadatas {
+            fetch.push(IncludeField::Metadatas);
+        }
+        let candidates = self
+            .query_embeddings(
+                &[query.to_vec()],
+                total.saturating_mul(GROUP_OVERFETCH),
+                where_meta,
+                None,
+                Some(&fetch),
+            )
+            .await?;
+        Ok(group_query_hits(
+            candidates,
+            group_field,
+            per_group as usize,
+            total as usize,
+            keep_metadatas,
+        ))
+    }
+
+    /// `query_embeddings` with extra [`QueryOptions`] such as a distance threshold.
+    pub async fn query_embeddings_with_options(
+        &self,
+        query_embeddings: &[Embedding],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+        options: &QueryOptions,
     ) -> Result<QueryResult> {
         if query_embeddings.is_empty() {
             return Err(SeekDbError::InvalidInput(
                 "query_embeddings cannot be empty".into(),
             ));
         }
+        if let Some(max) = options.max_distance
+            && max.is_nan()
+        {
+            return Err(SeekDbError::InvalidInput(
+                "max_distance must not be NaN".into(),
+            ));
+        }
+        if options.ef_search == Some(0) {
+            return Err(SeekDbError::InvalidInput(
+                "ef_search must be greater than 0".into(),
+            ));
+        }
 
         let table = CollectionNames::table_name(&self.name);
-        let sql_where = build_where_clause(where_meta, where_doc, None);
+        let sql_where = build_where_clause_with_dialect(
+            where_meta,
+            where_doc,
+            None,
+            self.client.json_dialect(),
+        );
         let select_clause = build_select_clause(include);
 
         let mut all_ids = Vec::new();
@@ -746,30 +1954,188 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         let mut all_metas = Vec::new();
         let mut all_embs = Vec::new();
         let mut all_dists = Vec::new();
+        let mut all_dists_f64 = Vec::new();
 
         for emb in query_embeddings {
-            let distance_func = distance_fn(self.distance);
-            let vector_str = vector_to_string(emb);
-            let sql = format!(
-                "SELECT {select_clause}, {distance_func}(embedding, '{vector_str}') AS distance \
-                 FROM `{table}` {where_clause} \
-                 ORDER BY {distance_func}(embedding, '{vector_str}') \
-                 LIMIT {limit}",
-                where_clause = sql_where.clause,
-                limit = n_results
-            );
-
-            let mut query = sqlx::query(&sql);
-            for p in &sql_where.params {
-                query = bind_metadata(query, p);
+            validate_vector(emb)?;
+        }
+        let fitted: Vec<Embedding>;
+        let query_embeddings = match options.dimension_fit {
+            DimensionFit::Exact => query_embeddings,
+            DimensionFit::PadOrTruncate => {
+                if !matches!(
+                    self.distance,
+                    DistanceMetric::Cosine | DistanceMetric::InnerProduct
+                ) {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "DimensionFit::PadOrTruncate needs cosine or inner product distance, not {}",
+                        self.distance.as_str()
+                    )));
+                }
+                let dimension = match options.vector_field() {
+                    Some(name) => self.vector_field(name)?.dimension,
+                    None => self.dimension,
+                };
+                fitted = query_embeddings
+                    .iter()
+                    .map(|e| fit_dimension(e, dimension as usize))
+                    .collect();
+                fitted.as_slice()
             }
-            let rows = query.fetch_all(self.client.pool()).await?;
-
-            let mut ids = Vec::new();
-            let mut docs = Vec::new();
+        };
+        let normalized: Vec<Embedding>;
+        let query_embeddings = if self.normalize_queries && !options.assume_normalized {
+            normalized = query_embeddings
+                .iter()
+                .map(|e| {
+                    let mut e = e.clone();
+                    l2_normalize(&mut e);
+                    e
+                })
+                .collect();
+            normalized.as_slice()
+        } else {
+            query_embeddings
+        };
+
+        // Named vector fields share the collection's metric.
+        let vector_column = match options.vector_field() {
+            Some(name) => {
+                let field = self.vector_field(name)?;
+                if let Some(emb) = query_embeddings
+                    .iter()
+                    .find(|e| e.len() as u32 != field.dimension)
+                {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "vector field '{name}' has dimension {}, got a query of {}",
+                        field.dimension,
+                        emb.len()
+                    )));
+                }
+                format!("`{}`", field.name)
+            }
+            None => {
+                if let Some(emb) = query_embeddings
+                    .iter()
+                    .find(|e| e.len() as u32 != self.dimension)
+                {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "embedding dimension {} does not match collection dimension {}",
+                        emb.len(),
+                        self.dimension
+                    )));
+                }
+                "embedding".to_string()
+            }
+        };
+        let distance_func = distance_fn(self.distance);
+        let with_distances = include_distances(include);
+        // HAVING can reference the `distance` alias and is applied before LIMIT.
+        // For inner product the threshold is a minimum score instead.
+        let having_clause = match options.max_distance {
+            Some(_) if self.distance.higher_is_closer() => "HAVING distance >= ? ",
+            Some(_) => "HAVING distance <= ? ",
+            None => "",
+        };
+        // The distance column is only selected when returned or filtered on;
+        // ordering recomputes it either way.
+        let select_distance = with_distances || options.max_distance.is_some();
+        let distance_column = if select_distance {
+            format!(", {distance_func}({vector_column}, ?) AS distance")
+        } else {
+            String::new()
+        };
+        let order_by = query_order_by(
+            self.distance,
+            &vector_column,
+            options.then_by(),
+            self.client.json_dialect(),
+        );
+        let sql = format!(
+            "SELECT {select_clause}{distance_column} \
+             FROM `{table}` {where_clause} \
+             {having_clause}\
+             {order_by} \
+             LIMIT {limit}",
+            where_clause = sql_where.clause,
+            limit = n_results
+        );
+
+        // `ob_hnsw_ef_search` is a session variable, so the SET, the searches
+        // and the restore must all run on the same pooled connection. The
+        // connection's previous value is put back before it returns to the pool.
+        let mut session = match options.ef_search {
+            Some(ef_search) => {
+                let mut session = self.client.pinned_session().await?;
+                let previous = session
+                    .fetch_all("SELECT CAST(@@ob_hnsw_ef_search AS SIGNED) AS ef_search")
+                    .await?
+                    .first()
+                    .and_then(|row| row.get_i64("ef_search").ok().flatten());
+                session
+                    .execute(&format!("SET ob_hnsw_ef_search = {ef_search}"))
+                    .await?;
+                Some((session, restore_ef_search_sql(previous)))
+            }
+            None => None,
+        };
+
+        let vector_strs: Vec<String> = query_embeddings
+            .iter()
+            .map(|emb| vector_to_string(emb, self.vector_literal_format))
+            .collect();
+        let bound_query = |i: usize| {
+            let vector_str = vector_strs[i].as_str();
+            let mut query = sqlx::query(&sql);
+            if select_distance {
+                query = query.bind(vector_str);
+            }
+            for p in &sql_where.params {
+                query = bind_metadata(query, p);
+            }
+            if let Some(max) = options.max_distance {
+                query = query.bind(max);
+            }
+            query.bind(vector_str)
+        };
+        // The pinned session is one connection, so its searches run in turn;
+        // otherwise they overlap, collected back in query order.
+        let row_sets: Vec<Vec<MySqlRow>> = match session.as_mut() {
+            Some((session, _)) => {
+                let mut row_sets = Vec::with_capacity(vector_strs.len());
+                for i in 0..vector_strs.len() {
+                    row_sets.push(session.fetch_query(bound_query(i)).await?);
+                }
+                row_sets
+            }
+            None => {
+                let concurrency = options
+                    .concurrency
+                    .unwrap_or_else(|| self.client.default_concurrency());
+                futures::stream::iter(0..vector_strs.len())
+                    .map(|i| async move {
+                        let query = bound_query(i);
+                        self.client
+                            .timed_sql(
+                                OpKind::Read,
+                                query.sql(),
+                                query.fetch_all(self.client.pool()),
+                            )
+                            .await
+                    })
+                    .buffered(concurrency)
+                    .try_collect()
+                    .await?
+            }
+        };
+
+        for rows in row_sets {
+            let mut ids = Vec::new();
+            let mut docs = Vec::new();
             let mut metas = Vec::new();
             let mut embs = Vec::new();
             let mut dists = Vec::new();
+            let mut dists_f64 = Vec::new();
 
             for row in rows {
                 ids.push(id_from_row(&row));
@@ -783,17 +2149,24 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 if include_metadatas(include) {
                     metas.push(metadata_from_row(&row));
                 }
-                if include_embeddings(include) {
-                    if let Some(v) = row.get_string("embedding").unwrap_or(None) {
-                        embs.push(parse_vector_string(v));
-                    }
+                if include_embeddings(include)
+                    && let Some(v) = embedding_from_row(&row)
+                {
+                    embs.push(v);
+                }
+                if with_distances && options.f64_distances {
+                    let dist = row.get_f64("distance").unwrap_or(None).unwrap_or(0.0);
+                    dists_f64.push(dist);
+                    dists.push(dist as f32);
+                } else if with_distances {
+                    let dist = row.get_f32("distance").unwrap_or(None).unwrap_or(0.0);
+                    dists.push(dist);
                 }
-                let dist = row.get_f32("distance").unwrap_or(None).unwrap_or(0.0);
-                dists.push(dist);
             }
 
             all_ids.push(ids);
             all_dists.push(dists);
+            all_dists_f64.push(dists_f64);
             if include_documents(include) {
                 all_docs.push(docs);
             }
@@ -804,6 +2177,9 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 all_embs.push(embs);
             }
         }
+        if let Some((session, restore_sql)) = session {
+            session.release(&restore_sql).await;
+        }
 
         Ok(QueryResult {
             ids: all_ids,
@@ -822,10 +2198,110 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             } else {
                 None
             },
-            distances: Some(all_dists),
+            distances: with_distances.then_some(all_dists),
+            distances_f64: (with_distances && options.f64_distances).then_some(all_dists_f64),
+            included: IncludeFlags::from_include(include),
         })
     }
 
+    /// "More like this" for many seeds: for each id in `ids`, the `n_results`
+    /// nearest other records, aligned with `ids` (query `i` of the result
+    /// belongs to `ids[i]`).
+    ///
+    /// All seed embeddings are read with one `get`; the per-seed searches then
+    /// run concurrently, at most as many at once as the pool has connections
+    /// (or the client's `max_inflight`, if lower).
+    /// The seed itself is left out of its own hits, and a seed id that does not
+    /// exist gets an empty hit list. `where_meta` filters the neighbours.
+    pub async fn query_by_ids(
+        &self,
+        ids: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        if ids.is_empty() {
+            return Err(SeekDbError::InvalidInput("ids must not be empty".into()));
+        }
+
+        let seeds = self
+            .get(
+                Some(ids),
+                None,
+                None,
+                None,
+                None,
+                Some(&[IncludeField::Embeddings]),
+            )
+            .await?;
+        let seed_embeddings: HashMap<String, Embedding> = seeds
+            .ids
+            .into_iter()
+            .zip(seeds.embeddings.unwrap_or_default())
+            .collect();
+
+        let concurrency = self.client.default_concurrency();
+        let parts: Vec<Result<Option<QueryResult>>> = futures::stream::iter(ids)
+            .map(|id| {
+                let seed = seed_embeddings.get(id);
+                async move {
+                    let Some(seed) = seed else {
+                        return Ok(None);
+                    };
+                    // One extra hit, since the seed usually finds itself.
+                    self.query_embeddings(
+                        std::slice::from_ref(seed),
+                        n_results.saturating_add(1),
+                        where_meta,
+                        None,
+                        include,
+                    )
+                    .await
+                    .map(Some)
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let mut out = QueryResult {
+            ids: Vec::with_capacity(ids.len()),
+            documents: include_documents(include).then(Vec::new),
+            metadatas: include_metadatas(include).then(Vec::new),
+            embeddings: include_embeddings(include).then(Vec::new),
+            distances: include_distances(include).then(Vec::new),
+            distances_f64: None,
+            included: IncludeFlags::from_include(include),
+        };
+        for (seed_id, part) in ids.iter().zip(parts) {
+            let part = part?.unwrap_or_else(|| empty_query_result(include));
+            let keep: Vec<usize> = part
+                .ids
+                .first()
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter(|(_, hit)| *hit != seed_id)
+                .map(|(i, _)| i)
+                .take(n_results as usize)
+                .collect();
+            out.ids.push(first_query_hits(part.ids, &keep));
+            if let (Some(out), Some(col)) = (out.documents.as_mut(), part.documents) {
+                out.push(first_query_hits(col, &keep));
+            }
+            if let (Some(out), Some(col)) = (out.metadatas.as_mut(), part.metadatas) {
+                out.push(first_query_hits(col, &keep));
+            }
+            if let (Some(out), Some(col)) = (out.embeddings.as_mut(), part.embeddings) {
+                out.push(first_query_hits(col, &keep));
+            }
+            if let (Some(out), Some(col)) = (out.distances.as_mut(), part.distances) {
+                out.push(first_query_hits(col, &keep));
+            }
+        }
+        Ok(out)
+    }
+
     pub async fn query_texts(
         &self,
         texts: &[String],
@@ -833,6 +2309,28 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         where_meta: Option<&Filter>,
         where_doc: Option<&DocFilter>,
         include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        self.query_texts_with_options(
+            texts,
+            n_results,
+            where_meta,
+            where_doc,
+            include,
+            &QueryOptions::default(),
+        )
+        .await
+    }
+
+    /// `query_texts` with extra [`QueryOptions`], e.g. a per-query
+    /// `with_ef_search` to trade latency for recall.
+    pub async fn query_texts_with_options(
+        &self,
+        texts: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+        options: &QueryOptions,
     ) -> Result<QueryResult> {
         if texts.is_empty() {
             return Err(SeekDbError::InvalidInput("texts must not be empty".into()));
@@ -844,26 +2342,74 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             )
         })?;
 
-        let embeddings = ef.embed_documents(texts).await?;
+        let embeddings = ef.embed_queries(texts).await?;
         if embeddings.len() != texts.len() {
-            return Err(SeekDbError::InvalidInput(format!(
-                "embeddings length {} does not match texts length {}",
-                embeddings.len(),
-                texts.len()
+            return Err(SeekDbError::Embedding(format!(
+                "expected {} query embeddings, got {}",
+                texts.len(),
+                embeddings.len()
             )));
         }
-        for emb in &embeddings {
-            if emb.len() as u32 != self.dimension {
-                return Err(SeekDbError::InvalidInput(format!(
-                    "embedding dimension {} does not match collection dimension {}",
-                    emb.len(),
-                    self.dimension
-                )));
-            }
+
+        // Dimensions are checked against the searched field after any
+        // `DimensionFit` has been applied.
+        self.query_embeddings_with_options(
+            &embeddings,
+            n_results,
+            where_meta,
+            where_doc,
+            include,
+            options,
+        )
+        .await
+    }
+
+    /// Like [`query_texts`](Self::query_texts), but embeds and runs each text on
+    /// its own and returns one `Result` per text, in input order. An embedding
+    /// or query failure for one text does not affect the others; each `Ok`
+    /// holds a single-query `QueryResult`.
+    pub async fn query_texts_lenient(
+        &self,
+        texts: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<Vec<Result<QueryResult>>> {
+        if texts.is_empty() {
+            return Err(SeekDbError::InvalidInput("texts must not be empty".into()));
         }
 
-        self.query_embeddings(&embeddings, n_results, where_meta, where_doc, include)
-            .await
+        let ef = self.embedding_function.as_ref().ok_or_else(|| {
+            SeekDbError::Embedding(
+                "Text embedding is not implemented. Provide query_embeddings directly or set embedding_function on collection.".into(),
+            )
+        })?;
+
+        let mut results = Vec::with_capacity(texts.len());
+        for text in texts {
+            let result = async {
+                let embedding = ef.embed_query(text).await?;
+                if embedding.len() as u32 != self.dimension {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "embedding dimension {} does not match collection dimension {}",
+                        embedding.len(),
+                        self.dimension
+                    )));
+                }
+                self.query_embeddings(
+                    std::slice::from_ref(&embedding),
+                    n_results,
+                    where_meta,
+                    where_doc,
+                    include,
+                )
+                .await
+            }
+            .await;
+            results.push(result);
+        }
+        Ok(results)
     }
 
     /// Hybrid search combining vector and keyword/term filters.
@@ -976,16 +2522,32 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         &self,
         search_parm_json: String,
         include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        // `@search_parm` is connection-scoped: SET, GET_SQL and the generated
+        // SELECT must all run on the same pooled connection.
+        let mut session = self.client.pinned_session().await?;
+        let result = self
+            .run_hybrid_search_on(&mut session, &search_parm_json, include)
+            .await;
+        session.release("SET @search_parm = NULL").await;
+        result
+    }
+
+    async fn run_hybrid_search_on(
+        &self,
+        session: &mut PinnedSession<'_>,
+        search_parm_json: &str,
+        include: Option<&[IncludeField]>,
     ) -> Result<QueryResult> {
         let table = CollectionNames::table_name(&self.name);
         let escaped = search_parm_json.replace('\'', "''");
         let set_sql = format!("SET @search_parm = '{escaped}'");
-        SqlBackend::execute(&*self.client, &set_sql).await?;
+        session.execute(&set_sql).await?;
 
         let get_sql = format!(
             "SELECT DBMS_HYBRID_SEARCH.GET_SQL('{table}', @search_parm) AS query_sql FROM dual"
         );
-        let rows = SqlBackend::fetch_all(&*self.client, &get_sql).await?;
+        let rows = session.fetch_all(&get_sql).await?;
         if rows.is_empty() {
             return Ok(empty_query_result(include));
         }
@@ -1001,7 +2563,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
             return Ok(empty_query_result(include));
         }
 
-        let result_rows = SqlBackend::fetch_all(&*self.client, &query_sql).await?;
+        let result_rows = session.fetch_all(&query_sql).await?;
         Ok(transform_hybrid_rows(result_rows, include))
     }
 
@@ -1100,7 +2662,7 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 .await?;
 
             let num = get_res.ids.len();
-            let distances = Some(vec![vec![0.0_f32; num]]);
+            let distances = include_distances(include).then(|| vec![vec![0.0_f32; num]]);
 
             return Ok(QueryResult {
                 ids: vec![get_res.ids],
@@ -1108,6 +2670,8 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
                 metadatas: get_res.metadatas.map(|m| vec![m]),
                 embeddings: get_res.embeddings.map(|e| vec![e]),
                 distances,
+                distances_f64: None,
+                included: IncludeFlags::from_include(include),
             });
         }
 
@@ -1139,75 +2703,591 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
         include: Option<&[IncludeField]>,
     ) -> Result<GetResult> {
         let table = CollectionNames::table_name(&self.name);
-        let sql_where = build_where_clause(where_meta, where_doc, ids);
+        let sql_where =
+            build_where_clause_with_dialect(where_meta, where_doc, ids, self.client.json_dialect());
         let select_clause = build_select_clause(include);
         let mut sql = format!("SELECT {select_clause} FROM `{table}` {}", sql_where.clause);
-        if let Some(limit) = limit {
-            sql.push_str(&format!(" LIMIT {limit}"));
+        append_limit_offset(&mut sql, limit, offset, self.client.max_rows());
+
+        let mut query = sqlx::query(&sql);
+        for p in &sql_where.params {
+            query = bind_metadata(query, p);
         }
-        if let Some(offset) = offset {
-            if limit.is_none() {
-                sql.push_str(" LIMIT 18446744073709551615");
-            }
-            sql.push_str(&format!(" OFFSET {offset}"));
+        let rows = self
+            .client
+            .timed_sql(
+                OpKind::Read,
+                query.sql(),
+                query.fetch_all(self.client.pool()),
+            )
+            .await?;
+
+        Ok(get_result_from_rows(rows, include))
+    }
+
+    /// Parent records of the chunks in `chunks`, for retrieval over long
+    /// documents split into chunks.
+    ///
+    /// Each chunk names its parent with a string [`PARENT_ID_KEY`]
+    /// (`"parent_id"`) metadata entry; call this on the collection holding the
+    /// parents, which may be the chunks' own. Parents are returned once each,
+    /// ordered by their best-ranked chunk across all queries. Chunks without
+    /// the key are skipped and parents that no longer exist are left out.
+    /// `chunks` must include metadatas.
+    pub async fn get_parents(
+        &self,
+        chunks: &QueryResult,
+        include: Option<&[IncludeField]>,
+    ) -> Result<GetResult> {
+        let parent_ids = parent_ids(chunks)?;
+        if parent_ids.is_empty() {
+            return Ok(get_result_from_rows(Vec::<MySqlRow>::new(), include));
+        }
+        let parents = self
+            .get(Some(&parent_ids), None, None, None, None, include)
+            .await?;
+        Ok(sort_get_result(parents, &parent_ids))
+    }
+
+    /// First page of a keyset-paged scan in `_id` order.
+    ///
+    /// Returns up to `limit` records plus a [`PageToken`] for the next page, or
+    /// `None` once fewer than `limit` records came back. The token remembers
+    /// the filters, so [`get_next_page`](Self::get_next_page) only needs the
+    /// token itself.
+    pub async fn get_page(
+        &self,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        limit: u32,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<PageToken>)> {
+        let token = PageToken {
+            collection: self.name.clone(),
+            where_meta: where_meta.cloned(),
+            where_doc: where_doc.cloned(),
+            after: String::new(),
+        };
+        self.fetch_page(token, None, limit, include).await
+    }
+
+    /// Continue a scan started by [`get_page`](Self::get_page).
+    pub async fn get_next_page(
+        &self,
+        token: &PageToken,
+        limit: u32,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<PageToken>)> {
+        if token.collection != self.name {
+            return Err(SeekDbError::InvalidInput(format!(
+                "page token belongs to collection '{}', not '{}'",
+                token.collection, self.name
+            )));
+        }
+        let after = token.after.clone();
+        self.fetch_page(token.clone(), Some(&after), limit, include)
+            .await
+    }
+
+    /// One keyset page: up to `limit` records with `_id > last_id` (from the
+    /// start when `None`), in `_id` order.
+    ///
+    /// Returns the records and the cursor to pass as `last_id` for the next
+    /// page, or `None` once fewer than `limit` records came back. Unlike
+    /// `get` with an offset, each page costs the same however deep it is.
+    pub async fn get_after(
+        &self,
+        last_id: Option<&str>,
+        limit: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<String>)> {
+        if limit == 0 {
+            return Err(SeekDbError::InvalidInput(
+                "limit must be greater than 0".into(),
+            ));
         }
+        let rows = self
+            .keyset_page(
+                &build_select_clause(include),
+                where_meta,
+                where_doc,
+                last_id,
+                limit,
+            )
+            .await?;
+        let full = rows.len() == limit as usize;
+        let result = get_result_from_rows(rows, include);
+        let next = result.ids.last().filter(|_| full).cloned();
+        Ok((result, next))
+    }
+
+    async fn fetch_page(
+        &self,
+        mut token: PageToken,
+        after: Option<&str>,
+        limit: u32,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<PageToken>)> {
+        let (result, next) = self
+            .get_after(
+                after,
+                limit,
+                token.where_meta.as_ref(),
+                token.where_doc.as_ref(),
+                include,
+            )
+            .await?;
+        let next = next.map(|last| {
+            token.after = last;
+            token
+        });
+        Ok((result, next))
+    }
+
+    /// Stream the rows matching the filters as pages of at most `batch_size`
+    /// rows, in `_id` order, without holding the whole result in memory.
+    ///
+    /// Pages are fetched lazily with keyset pagination on `_id`, so each page
+    /// costs the same however deep the scan is. The stream ends after the
+    /// first short page, or after yielding an error.
+    pub fn get_stream<'a>(
+        &'a self,
+        where_meta: Option<&'a Filter>,
+        where_doc: Option<&'a DocFilter>,
+        batch_size: u32,
+        include: Option<&'a [IncludeField]>,
+    ) -> impl Stream<Item = Result<GetResult>> + 'a {
+        let select_clause = build_select_clause(include);
+        // `None` once finished; the session is `None` until the first page and
+        // without snapshot scans.
+        let state = Some((None::<String>, None::<Option<PinnedSession<'a>>>));
+        futures::stream::unfold(state, move |state| {
+            let select_clause = select_clause.clone();
+            async move {
+                let (after, session) = state?;
+                if batch_size == 0 {
+                    let err = SeekDbError::InvalidInput("batch_size must be greater than 0".into());
+                    return Some((Err(err), None));
+                }
+                let mut session = match session {
+                    Some(session) => session,
+                    None => match self.begin_scan().await {
+                        Ok(session) => session,
+                        Err(err) => return Some((Err(err), None)),
+                    },
+                };
+                let rows = match self
+                    .keyset_page_in(
+                        session.as_mut(),
+                        &select_clause,
+                        where_meta,
+                        where_doc,
+                        after.as_deref(),
+                        batch_size,
+                    )
+                    .await
+                {
+                    Ok(rows) => rows,
+                    Err(err) => return Some((Err(err), None)),
+                };
+                let full = rows.len() == batch_size as usize;
+                if !full && let Some(session) = session.take() {
+                    session.release("ROLLBACK").await;
+                }
+                if rows.is_empty() {
+                    return None;
+                }
+                let next = full.then(|| (rows.last().map(id_from_row), Some(session)));
+                let page = get_result_from_rows(rows, include);
+                Some((Ok(page), next))
+            }
+        })
+    }
+
+    /// Stream every record in `_id` order, fetching `batch_size` rows at a time.
+    ///
+    /// Pass `after = Some(id)` to resume after a previously yielded id. If a
+    /// page fails (e.g. the connection drops) the error is yielded as the
+    /// final item, typically [`SeekDbError::Connection`]; every record before
+    /// it was delivered in full, so resuming from the last yielded id
+    /// continues without gaps or repeats. Callers that checkpoint less often
+    /// than per record get at-least-once delivery: records after their
+    /// checkpoint are yielded again on resume. An `after` that cannot be a
+    /// stored id (empty, or longer than 512 bytes) is yielded as an
+    /// [`SeekDbError::InvalidInput`] before anything is read.
+    pub fn iter_all<'a>(
+        &'a self,
+        batch_size: u32,
+        include: Option<&'a [IncludeField]>,
+        after: Option<String>,
+    ) -> impl Stream<Item = Result<Record>> + 'a {
+        let select_clause = build_select_clause(include);
+        let state = (
+            after,
+            std::collections::VecDeque::<Record>::new(),
+            false,
+            None::<Option<PinnedSession<'a>>>,
+        );
+        futures::stream::unfold(
+            state,
+            move |(mut after, mut buffered, mut done, session)| {
+                let select_clause = select_clause.clone();
+                async move {
+                    let mut session = session;
+                    loop {
+                        if let Some(record) = buffered.pop_front() {
+                            after = Some(record.id.clone());
+                            return Some((Ok(record), (after, buffered, done, session)));
+                        }
+                        if done {
+                            return None;
+                        }
+                        done = true;
+                        if batch_size == 0 {
+                            let err = SeekDbError::InvalidInput(
+                                "batch_size must be greater than 0".into(),
+                            );
+                            return Some((Err(err), (after, buffered, done, None)));
+                        }
+                        if let Err(err) = validate_cursor(after.as_deref()) {
+                            return Some((Err(err), (after, buffered, done, None)));
+                        }
+                        let scan = match session.as_mut() {
+                            Some(scan) => scan,
+                            None => match self.begin_scan().await {
+                                Ok(scan) => session.insert(scan),
+                                Err(err) => return Some((Err(err), (after, buffered, done, None))),
+                            },
+                        };
+                        let rows = match self
+                            .keyset_page_in(
+                                scan.as_mut(),
+                                &select_clause,
+                                None,
+                                None,
+                                after.as_deref(),
+                                batch_size,
+                            )
+                            .await
+                        {
+                            Ok(rows) => rows,
+                            Err(err) => return Some((Err(err), (after, buffered, done, None))),
+                        };
+                        done = rows.len() < batch_size as usize;
+                        if done && let Some(scan) = session.take().flatten() {
+                            scan.release("ROLLBACK").await;
+                        }
+                        buffered = get_result_from_rows(rows, include).into_records().into();
+                    }
+                }
+            },
+        )
+    }
+
+    pub async fn count(&self) -> Result<u64> {
+        self.count_where(None, None, None).await
+    }
+
+    /// Approximate row count from `information_schema.TABLES.TABLE_ROWS`.
+    ///
+    /// Near-instant regardless of table size, but the value comes from table
+    /// statistics that the server refreshes on its own schedule, so it can lag
+    /// recent writes in either direction. Use [`count`](Self::count) when an
+    /// exact number matters.
+    pub async fn count_approx(&self) -> Result<u64> {
+        let sql = "SELECT CAST(COALESCE(TABLE_ROWS, 0) AS SIGNED) AS cnt \
+                   FROM information_schema.TABLES \
+                   WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? LIMIT 1";
+        let row = self
+            .client
+            .timed_sql(
+                OpKind::Read,
+                sql,
+                sqlx::query(sql)
+                    .bind(self.client.database())
+                    .bind(CollectionNames::table_name(&self.name))
+                    .fetch_optional(self.client.pool()),
+            )
+            .await?
+            .ok_or_else(|| SeekDbError::NotFound(format!("collection not found: {}", self.name)))?;
+        let cnt = row.get_i64("cnt")?.unwrap_or(0);
+        Ok(cnt.max(0) as u64)
+    }
 
+    /// Count rows matching the given filters and ids (all rows when every argument is `None`).
+    pub async fn count_where(
+        &self,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        ids: Option<&[String]>,
+    ) -> Result<u64> {
+        let table = CollectionNames::table_name(&self.name);
+        let sql_where =
+            build_where_clause_with_dialect(where_meta, where_doc, ids, self.client.json_dialect());
+        let sql = format!("SELECT COUNT(*) as cnt FROM `{table}` {}", sql_where.clause);
         let mut query = sqlx::query(&sql);
         for p in &sql_where.params {
             query = bind_metadata(query, p);
         }
-        let rows = query.fetch_all(self.client.pool()).await?;
+        let row = self
+            .client
+            .timed_sql(
+                OpKind::Read,
+                query.sql(),
+                query.fetch_one(self.client.pool()),
+            )
+            .await?;
+        let cnt = row.get_i64("cnt").unwrap_or(Some(0)).unwrap_or(0);
+        Ok(cnt as u64)
+    }
 
-        let mut result = GetResult {
-            ids: Vec::new(),
-            documents: if include_documents(include) {
-                Some(Vec::new())
-            } else {
-                None
-            },
-            metadatas: if include_metadatas(include) {
-                Some(Vec::new())
-            } else {
-                None
-            },
-            embeddings: if include_embeddings(include) {
-                Some(Vec::new())
-            } else {
-                None
-            },
+    /// Write every embedding as a numpy `.npy` array (`<f4`, shape `[n, dim]`)
+    /// to `vectors`, and the matching ids to `ids`, one JSON string per line
+    /// in row order. Returns the number of rows written.
+    ///
+    /// Rows are read in `_id` order, a page at a time. The row count goes into
+    /// the header up front, so rows added or removed during the export make it
+    /// fail rather than produce a file whose shape does not match its data.
+    pub async fn export_npy<W, I>(&self, mut vectors: W, mut ids: I) -> Result<u64>
+    where
+        W: std::io::Write,
+        I: std::io::Write,
+    {
+        let io_err = |e: std::io::Error| SeekDbError::Other(anyhow::Error::new(e));
+        let mut session = self.begin_scan().await?;
+        let total = match session.as_mut() {
+            Some(session) => {
+                let table = CollectionNames::table_name(&self.name);
+                let rows = session
+                    .fetch_all(&format!("SELECT COUNT(*) AS cnt FROM `{table}`"))
+                    .await?;
+                let cnt = rows
+                    .first()
+                    .and_then(|row| row.get_i64("cnt").ok().flatten());
+                cnt.unwrap_or(0) as u64
+            }
+            None => self.count().await?,
         };
-
-        for row in rows {
-            result.ids.push(id_from_row(&row));
-            if let Some(docs) = result.documents.as_mut() {
-                let doc = row
-                    .get_string("document")
-                    .unwrap_or(None)
-                    .unwrap_or_default();
-                docs.push(doc);
+        let dim = self.dimension as usize;
+        vectors.write_all(&npy_header(total, dim)).map_err(io_err)?;
+
+        let select_clause = build_select_clause(Some(&[IncludeField::Embeddings]));
+        let mut after: Option<String> = None;
+        let mut written = 0u64;
+        let mut buf = Vec::with_capacity(dim * 4);
+        loop {
+            let rows = self
+                .keyset_page_in(
+                    session.as_mut(),
+                    &select_clause,
+                    None,
+                    None,
+                    after.as_deref(),
+                    EXPORT_PAGE_SIZE,
+                )
+                .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after = Some(id_from_row(last));
+            for row in &rows {
+                let id = id_from_row(row);
+                let embedding = embedding_from_row(row).unwrap_or_default();
+                if embedding.len() != dim {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "row {id} has a {}-dimensional embedding; expected {dim}",
+                        embedding.len()
+                    )));
+                }
+                if written == total {
+                    return Err(export_changed(total));
+                }
+                written += 1;
+                buf.clear();
+                for v in &embedding {
+                    buf.extend_from_slice(&v.to_le_bytes());
+                }
+                vectors.write_all(&buf).map_err(io_err)?;
+                writeln!(ids, "{}", Value::String(id)).map_err(io_err)?;
             }
-            if let Some(metas) = result.metadatas.as_mut() {
-                metas.push(metadata_from_row(&row));
+        }
+        if let Some(session) = session {
+            session.release("ROLLBACK").await;
+        }
+        if written != total {
+            return Err(export_changed(total));
+        }
+        vectors.flush().map_err(io_err)?;
+        ids.flush().map_err(io_err)?;
+        Ok(written)
+    }
+
+    /// Write every row as one JSON object per line,
+    /// `{"id":..,"document":..,"metadata":..,"embedding":[..]}`, and return the
+    /// number of rows written.
+    ///
+    /// Rows are read in `_id` order, a page at a time, so memory use does not
+    /// grow with the collection. Each line deserializes as a [`Record`].
+    /// Collections with named vector fields are rejected, since a [`Record`]
+    /// has no place for them.
+    pub async fn export_jsonl<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        mut writer: W,
+    ) -> Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        reject_vector_fields(self, "export_jsonl")?;
+        let io_err = |e: std::io::Error| SeekDbError::Other(anyhow::Error::new(e));
+        let select_clause = build_select_clause(Some(&ALL_FIELDS));
+        let mut session = self.begin_scan().await?;
+        let mut after: Option<String> = None;
+        let mut written = 0u64;
+        loop {
+            let rows = self
+                .keyset_page_in(
+                    session.as_mut(),
+                    &select_clause,
+                    None,
+                    None,
+                    after.as_deref(),
+                    EXPORT_PAGE_SIZE,
+                )
+                .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after = Some(id_from_row(last));
+            let page = get_result_from_rows(rows, Some(&ALL_FIELDS));
+            for record in page.into_records() {
+                let mut line = serde_json::to_string(&record)?;
+                line.push('\n');
+                writer.write_all(line.as_bytes()).await.map_err(io_err)?;
+                written += 1;
             }
-            if let Some(embs) = result.embeddings.as_mut() {
-                let emb = row
+        }
+        if let Some(session) = session {
+            session.release("ROLLBACK").await;
+        }
+        writer.flush().await.map_err(io_err)?;
+        Ok(written)
+    }
+
+    /// Scan the whole collection and return the ids whose stored embedding
+    /// does not parse to exactly [`dimension`](Self::dimension) values,
+    /// including rows with no embedding at all.
+    ///
+    /// A diagnostic for data written by misbehaving clients; rows are read a
+    /// page at a time in `_id` order.
+    pub async fn validate_dimensions(&self) -> Result<Vec<String>> {
+        let select_clause = build_select_clause(Some(&[IncludeField::Embeddings]));
+        let mut mismatched = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let rows = self
+                .keyset_page(
+                    &select_clause,
+                    None,
+                    None,
+                    after.as_deref(),
+                    EXPORT_PAGE_SIZE,
+                )
+                .await?;
+            let Some(last) = rows.last() else {
+                return Ok(mismatched);
+            };
+            after = Some(id_from_row(last));
+            for row in &rows {
+                let len = row
                     .get_string("embedding")
                     .unwrap_or(None)
-                    .map(parse_vector_string)
-                    .unwrap_or_default();
-                embs.push(emb);
+                    .map(|s| parse_vector_string(s).len())
+                    .unwrap_or(0);
+                if len != self.dimension as usize {
+                    mismatched.push(id_from_row(row));
+                }
             }
         }
+    }
 
-        Ok(result)
+    /// Rows ordered by `_id` that come strictly after `after` (from the start
+    /// when `None`), at most `limit` of them, selecting `select_clause`.
+    ///
+    /// Keyset pagination: unlike `LIMIT .. OFFSET`, each page costs the same
+    /// however deep into the table it is.
+    async fn keyset_page(
+        &self,
+        select_clause: &str,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<MySqlRow>> {
+        self.keyset_page_in(None, select_clause, where_meta, where_doc, after, limit)
+            .await
     }
 
-    pub async fn count(&self) -> Result<u64> {
+    /// [`keyset_page`](Self::keyset_page) on the scan's snapshot session, if any.
+    async fn keyset_page_in(
+        &self,
+        session: Option<&mut PinnedSession<'_>>,
+        select_clause: &str,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<MySqlRow>> {
         let table = CollectionNames::table_name(&self.name);
-        let sql = format!("SELECT COUNT(*) as cnt FROM `{table}`");
-        let row = sqlx::query(&sql).fetch_one(self.client.pool()).await?;
-        let cnt = row.get_i64("cnt").unwrap_or(Some(0)).unwrap_or(0);
-        Ok(cnt as u64)
+        let sql_where = build_where_clause_with_dialect(
+            where_meta,
+            where_doc,
+            None,
+            self.client.json_dialect(),
+        );
+        let mut where_clause = sql_where.clause;
+        if after.is_some() {
+            where_clause = if where_clause.is_empty() {
+                "WHERE _id > ?".to_string()
+            } else {
+                format!("{where_clause} AND _id > ?")
+            };
+        }
+        let sql = format!(
+            "SELECT {select_clause} FROM `{table}` {where_clause} ORDER BY _id LIMIT {limit}"
+        );
+        let mut query = sqlx::query(&sql);
+        for p in &sql_where.params {
+            query = bind_metadata(query, p);
+        }
+        if let Some(after) = after {
+            query = query.bind(after.as_bytes());
+        }
+        if let Some(session) = session {
+            return session.fetch_query(query).await;
+        }
+        self.client
+            .timed_sql(
+                OpKind::Read,
+                query.sql(),
+                query.fetch_all(self.client.pool()),
+            )
+            .await
+    }
+
+    /// Open the snapshot transaction for a long scan when
+    /// [`with_snapshot_scans`](Self::with_snapshot_scans) is on.
+    async fn begin_scan(&self) -> Result<Option<PinnedSession<'_>>> {
+        if !self.snapshot_scans {
+            return Ok(None);
+        }
+        let mut session = self.client.pinned_session().await?;
+        // Without SESSION this only applies to the next transaction.
+        session
+            .execute("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .await?;
+        session.execute("START TRANSACTION READ ONLY").await?;
+        Ok(Some(session))
     }
 
     pub async fn peek(&self, _limit: u32) -> Result<GetResult> {
@@ -1227,6 +3307,115 @@ impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
     }
 }
 
+/// `ndarray` interop: each row of the matrix is one vector.
+#[cfg(feature = "ndarray")]
+impl<Ef: EmbeddingFunction + 'static> Collection<Ef> {
+    /// [`add`](Self::add) with embeddings given as an `ids.len() x dimension` matrix.
+    pub async fn add_ndarray(
+        &self,
+        ids: &[String],
+        embeddings: ndarray::ArrayView2<'_, f32>,
+        metadatas: Option<&[Metadata]>,
+        documents: Option<&[String]>,
+    ) -> Result<()> {
+        let embeddings = embeddings_from_array(embeddings, self.dimension)?;
+        self.add(ids, Some(&embeddings), metadatas, documents).await
+    }
+
+    /// [`query_embeddings`](Self::query_embeddings) with one query vector per matrix row.
+    pub async fn query_embeddings_ndarray(
+        &self,
+        query_embeddings: ndarray::ArrayView2<'_, f32>,
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        let embeddings = embeddings_from_array(query_embeddings, self.dimension)?;
+        self.query_embeddings(&embeddings, n_results, where_meta, where_doc, include)
+            .await
+    }
+}
+
+/// `v` cut or zero-padded to `dimension` components.
+fn fit_dimension(v: &[f32], dimension: usize) -> Embedding {
+    let mut fitted = v[..v.len().min(dimension)].to_vec();
+    fitted.resize(dimension, 0.0);
+    fitted
+}
+
+/// Reject vectors the server cannot parse: empty ones and non-finite components.
+fn validate_vector(v: &[f32]) -> Result<()> {
+    if v.is_empty() {
+        return Err(SeekDbError::InvalidInput("vector must not be empty".into()));
+    }
+    if let Some((idx, x)) = v.iter().enumerate().find(|(_, x)| !x.is_finite()) {
+        return Err(SeekDbError::InvalidInput(format!(
+            "vector component {idx} is not finite ({x})"
+        )));
+    }
+    Ok(())
+}
+
+/// Rows fetched per round trip by full-collection scans such as `export_npy`
+/// and `validate_dimensions`.
+const EXPORT_PAGE_SIZE: u32 = 1000;
+
+/// Longest `_id`, the width of its `varbinary` column.
+const MAX_ID_BYTES: usize = 512;
+
+/// Reject a resume cursor that no stored `_id` could equal.
+fn validate_cursor(after: Option<&str>) -> Result<()> {
+    match after {
+        Some("") => Err(SeekDbError::InvalidInput(
+            "resume id must not be empty".into(),
+        )),
+        Some(id) if id.len() > MAX_ID_BYTES => Err(SeekDbError::InvalidInput(format!(
+            "resume id is {} bytes; ids are at most {MAX_ID_BYTES}",
+            id.len()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Metadata key under which a chunk stores the id of its parent record; see
+/// [`Collection::get_parents`].
+pub const PARENT_ID_KEY: &str = "parent_id";
+
+/// Candidates fetched per requested hit by `query_grouped`, so that a few
+/// dominant groups do not crowd out the rest.
+const GROUP_OVERFETCH: u32 = 5;
+
+/// Every optional field, for scans that copy whole rows.
+const ALL_FIELDS: [IncludeField; 3] = [
+    IncludeField::Documents,
+    IncludeField::Metadatas,
+    IncludeField::Embeddings,
+];
+
+fn export_changed(declared: u64) -> SeekDbError {
+    SeekDbError::InvalidInput(format!(
+        "collection changed during export; the header already declares {declared} rows"
+    ))
+}
+
+/// NumPy `.npy` (format 1.0) header for a C-order little-endian `f32` matrix
+/// of `rows` x `cols`, padded so the data starts on a 64-byte boundary.
+fn npy_header(rows: u64, cols: usize) -> Vec<u8> {
+    let mut dict =
+        format!("{{'descr': '<f4', 'fortran_order': False, 'shape': ({rows}, {cols}), }}");
+    // magic (6) + version (2) + header length (2) + dict + trailing newline
+    let unpadded = 10 + dict.len() + 1;
+    dict.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    dict.push('\n');
+
+    let mut header = Vec::with_capacity(10 + dict.len());
+    header.extend_from_slice(b"\x93NUMPY\x01\x00");
+    header.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header
+}
+
 fn validate_lengths(
     ids: &[String],
     embeddings: &[Embedding],
@@ -1234,7 +3423,12 @@ fn validate_lengths(
     documents: Option<&[String]>,
     dimension: u32,
 ) -> Result<()> {
-    if !embeddings.is_empty() && embeddings.len() != ids.len() {
+    if embeddings.is_empty() {
+        return Err(SeekDbError::InvalidInput(
+            "empty embeddings slice; pass None to auto-generate".into(),
+        ));
+    }
+    if embeddings.len() != ids.len() {
         return Err(SeekDbError::InvalidInput(format!(
             "embeddings length {} does not match ids length {}",
             embeddings.len(),
@@ -1242,6 +3436,7 @@ fn validate_lengths(
         )));
     }
     for emb in embeddings {
+        validate_vector(emb)?;
         if emb.len() as u32 != dimension {
             return Err(SeekDbError::InvalidInput(format!(
                 "embedding dimension {} does not match collection dimension {}",
@@ -1250,32 +3445,234 @@ fn validate_lengths(
             )));
         }
     }
-    if let Some(docs) = documents {
-        if !docs.is_empty() && docs.len() != ids.len() {
-            return Err(SeekDbError::InvalidInput(
-                "documents length does not match ids length".into(),
-            ));
-        }
+    if let Some(docs) = documents
+        && !docs.is_empty()
+        && docs.len() != ids.len()
+    {
+        return Err(SeekDbError::InvalidInput(
+            "documents length does not match ids length".into(),
+        ));
     }
-    if let Some(metas) = metadatas {
-        if !metas.is_empty() && metas.len() != ids.len() {
-            return Err(SeekDbError::InvalidInput(
-                "metadatas length does not match ids length".into(),
-            ));
-        }
+    if let Some(metas) = metadatas
+        && !metas.is_empty()
+        && metas.len() != ids.len()
+    {
+        return Err(SeekDbError::InvalidInput(
+            "metadatas length does not match ids length".into(),
+        ));
     }
     Ok(())
 }
 
-fn vector_to_string(v: &Embedding) -> String {
+/// Split a matrix into row vectors, checking the column count against `dimension`.
+#[cfg(feature = "ndarray")]
+fn embeddings_from_array(
+    array: ndarray::ArrayView2<'_, f32>,
+    dimension: u32,
+) -> Result<Vec<Embedding>> {
+    if array.ncols() as u32 != dimension {
+        return Err(SeekDbError::InvalidInput(format!(
+            "array has {} columns but collection dimension is {}",
+            array.ncols(),
+            dimension
+        )));
+    }
+    Ok(array.rows().into_iter().map(|row| row.to_vec()).collect())
+}
+
+fn vector_to_string(v: &Embedding, format: VectorLiteralFormat) -> String {
     let inner = v
         .iter()
-        .map(|x| x.to_string())
+        .map(|x| match format {
+            VectorLiteralFormat::Shortest => x.to_string(),
+            VectorLiteralFormat::FullPrecision => format!("{x:.8e}"),
+        })
         .collect::<Vec<_>>()
         .join(",");
     format!("[{inner}]")
 }
 
+/// Statement head for a multi-row INSERT into `table`, up to `VALUES `.
+/// `extra_columns` are named vector fields written after `embedding`.
+fn insert_prefix(table: &str, on_conflict: OnConflict, extra_columns: &[&str]) -> String {
+    let verb = match on_conflict {
+        OnConflict::Error | OnConflict::Replace => "INSERT INTO",
+        OnConflict::Skip => "INSERT IGNORE INTO",
+    };
+    let extra: String = extra_columns.iter().map(|c| format!(", `{c}`")).collect();
+    format!("{verb} `{table}` (_id, document, metadata, embedding{extra}) VALUES ")
+}
+
+/// Fail `op` on a collection with named vector fields, which it would drop.
+fn reject_vector_fields<Ef: EmbeddingFunction + 'static>(
+    collection: &Collection<Ef>,
+    op: &str,
+) -> Result<()> {
+    match collection.vector_fields.first() {
+        Some(field) => Err(SeekDbError::InvalidInput(format!(
+            "{op} does not support named vector fields; collection {} has `{}`",
+            collection.name, field.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Tail of a multi-row INSERT: for [`OnConflict::Replace`], update only the
+/// columns the statement writes, so named vectors it does not carry are kept.
+fn insert_suffix(on_conflict: OnConflict, extra_columns: &[&str]) -> String {
+    if on_conflict != OnConflict::Replace {
+        return String::new();
+    }
+    let columns = ["document", "metadata", "embedding"]
+        .iter()
+        .map(|c| format!("{c} = VALUES({c})"))
+        .chain(
+            extra_columns
+                .iter()
+                .map(|c| format!("`{c}` = VALUES(`{c}`)")),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" ON DUPLICATE KEY UPDATE {columns}")
+}
+
+/// Placeholder tuple for one row of a collection INSERT.
+fn insert_row_placeholders(extra_columns: usize) -> String {
+    format!("(?, ?, ?, ?{})", ", ?".repeat(extra_columns))
+}
+
+/// One row queued for a multi-row INSERT, with every column already serialized.
+struct InsertRow<'a> {
+    id: &'a str,
+    document: String,
+    metadata: Option<String>,
+    vector: String,
+    /// Named vector field values, in the statement's extra column order.
+    extra_vectors: Vec<String>,
+}
+
+impl InsertRow<'_> {
+    fn param_lens(&self) -> Vec<usize> {
+        let mut lens = vec![
+            self.id.len(),
+            self.document.len(),
+            self.metadata.as_ref().map_or(0, String::len),
+            self.vector.len(),
+        ];
+        lens.extend(self.extra_vectors.iter().map(String::len));
+        lens
+    }
+}
+
+/// Split rows into consecutive ranges of at most `max_rows` whose estimated
+/// statement size (`base` plus each row's bytes) stays within `max_bytes`.
+/// A row is never split off on its own account, so every range is non-empty.
+fn plan_insert_chunks(
+    row_bytes: &[usize],
+    base: usize,
+    max_rows: usize,
+    max_bytes: usize,
+) -> Vec<std::ops::Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < row_bytes.len() {
+        let mut end = start;
+        let mut bytes = base;
+        while end < row_bytes.len() && end - start < max_rows {
+            if end > start && bytes + row_bytes[end] > max_bytes {
+                break;
+            }
+            bytes += row_bytes[end];
+            end += 1;
+        }
+        chunks.push(start..end);
+        start = end;
+    }
+    chunks
+}
+
+/// Differences in dimension and distance between `target` and `other`, each
+/// as `"dimension 3 != 4"` (target value first).
+pub(crate) fn dimension_distance_mismatch(
+    target_dimension: u32,
+    target_distance: DistanceMetric,
+    other_dimension: u32,
+    other_distance: DistanceMetric,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    if target_dimension != other_dimension {
+        problems.push(format!("dimension {target_dimension} != {other_dimension}"));
+    }
+    if target_distance != other_distance {
+        problems.push(format!(
+            "distance {} != {}",
+            target_distance.as_str(),
+            other_distance.as_str()
+        ));
+    }
+    problems
+}
+
+/// Why rows shaped like `other` do not fit `target`, or `None` if they do.
+pub(crate) fn schema_mismatch(
+    target: &CollectionSchema,
+    other: &CollectionSchema,
+) -> Option<String> {
+    let mut problems = dimension_distance_mismatch(
+        target.dimension,
+        target.distance,
+        other.dimension,
+        other.distance,
+    );
+    let missing: Vec<&str> = other
+        .columns
+        .iter()
+        .filter(|c| !target.columns.iter().any(|t| t.eq_ignore_ascii_case(c)))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        problems.push(format!("missing columns {}", missing.join(", ")));
+    }
+    (!problems.is_empty()).then(|| problems.join("; "))
+}
+
+/// Reject `meta` if its JSON encoding is longer than `max` bytes.
+fn check_metadata_size(id: &str, meta: &Metadata, max: usize) -> Result<()> {
+    let size = serde_json::to_vec(meta)?.len();
+    if size > max {
+        return Err(SeekDbError::InvalidInput(format!(
+            "metadata for id {id} exceeds {max} bytes ({size} bytes as JSON); \
+             shrink it or raise Collection::with_max_metadata_bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// Wrap `err` with the number of rows stored before it happened, if any.
+fn partial_write(written: usize, err: SeekDbError) -> SeekDbError {
+    if written == 0 {
+        err
+    } else {
+        SeekDbError::PartialWrite {
+            written,
+            source: Box::new(err),
+        }
+    }
+}
+
+/// Rough wire size of a prepared statement: the SQL text plus each bound
+/// parameter and its length-encoded header (up to 9 bytes).
+fn estimate_statement_bytes(sql: &str, param_lens: &[usize]) -> usize {
+    sql.len() + param_lens.iter().map(|len| len + 9).sum::<usize>()
+}
+
+/// Read the `embedding` column from its text vector literal.
+fn embedding_from_row<R: BackendRow>(row: &R) -> Option<Embedding> {
+    row.get_string("embedding")
+        .unwrap_or(None)
+        .map(parse_vector_string)
+}
+
 fn parse_vector_string(s: String) -> Embedding {
     s.trim_matches(&['[', ']'][..])
         .split(',')
@@ -1288,7 +3685,54 @@ fn distance_fn(distance: DistanceMetric) -> &'static str {
         DistanceMetric::L2 => "l2_distance",
         DistanceMetric::Cosine => "cosine_distance",
         DistanceMetric::InnerProduct => "inner_product",
+        DistanceMetric::Hamming => "hamming_distance",
+        DistanceMetric::Jaccard => "jaccard_distance",
+    }
+}
+
+/// Decode `SELECT {build_select_clause(include)}` rows into a [`GetResult`].
+fn get_result_from_rows<R: BackendRow>(
+    rows: Vec<R>,
+    include: Option<&[IncludeField]>,
+) -> GetResult {
+    let mut result = GetResult {
+        ids: Vec::new(),
+        documents: if include_documents(include) {
+            Some(Vec::new())
+        } else {
+            None
+        },
+        metadatas: if include_metadatas(include) {
+            Some(Vec::new())
+        } else {
+            None
+        },
+        embeddings: if include_embeddings(include) {
+            Some(Vec::new())
+        } else {
+            None
+        },
+        included: get_include_flags(include),
+    };
+
+    for row in rows {
+        result.ids.push(id_from_row(&row));
+        if let Some(docs) = result.documents.as_mut() {
+            let doc = row
+                .get_string("document")
+                .unwrap_or(None)
+                .unwrap_or_default();
+            docs.push(doc);
+        }
+        if let Some(metas) = result.metadatas.as_mut() {
+            metas.push(metadata_from_row(&row));
+        }
+        if let Some(embs) = result.embeddings.as_mut() {
+            embs.push(embedding_from_row(&row).unwrap_or_default());
+        }
     }
+
+    result
 }
 
 fn build_select_clause(include: Option<&[IncludeField]>) -> String {
@@ -1307,23 +3751,26 @@ fn build_select_clause(include: Option<&[IncludeField]>) -> String {
 }
 
 fn include_documents(include: Option<&[IncludeField]>) -> bool {
-    match include {
-        None => true,
-        Some(list) => list.iter().any(|f| matches!(f, IncludeField::Documents)),
-    }
+    IncludeFlags::from_include(include).documents
 }
 
 fn include_metadatas(include: Option<&[IncludeField]>) -> bool {
-    match include {
-        None => true,
-        Some(list) => list.iter().any(|f| matches!(f, IncludeField::Metadatas)),
-    }
+    IncludeFlags::from_include(include).metadatas
 }
 
 fn include_embeddings(include: Option<&[IncludeField]>) -> bool {
-    match include {
-        None => false,
-        Some(list) => list.iter().any(|f| matches!(f, IncludeField::Embeddings)),
+    IncludeFlags::from_include(include).embeddings
+}
+
+fn include_distances(include: Option<&[IncludeField]>) -> bool {
+    IncludeFlags::from_include(include).distances
+}
+
+/// [`IncludeFlags`] recorded on a `get` result, which never has distances.
+fn get_include_flags(include: Option<&[IncludeField]>) -> IncludeFlags {
+    IncludeFlags {
+        distances: false,
+        ..IncludeFlags::from_include(include)
     }
 }
 
@@ -1337,43 +3784,66 @@ fn id_from_row<R: BackendRow>(row: &R) -> String {
     }
 }
 
-fn bind_metadata<'q>(
-    query: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
-    value: &Value,
-) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+/// How a JSON filter value is bound as a statement parameter.
+#[derive(Debug, PartialEq)]
+enum MetadataParam {
+    Int(i64),
+    /// Integers above `i64::MAX` (e.g. snowflake ids), bound as
+    /// `BIGINT UNSIGNED` rather than wrapped into a negative `i64`.
+    UInt(u64),
+    Float(f64),
+    Text(String),
+    Bool(bool),
+    Null,
+}
+
+fn metadata_param(value: &Value) -> MetadataParam {
     match value {
-        Value::String(s) => query.bind(s.clone()),
+        Value::String(s) => MetadataParam::Text(s.clone()),
         Value::Number(n) => {
             if let Some(i) = n.as_i64() {
-                query.bind(i)
+                MetadataParam::Int(i)
             } else if let Some(u) = n.as_u64() {
-                query.bind(u as i64)
+                MetadataParam::UInt(u)
             } else if let Some(f) = n.as_f64() {
-                query.bind(f)
+                MetadataParam::Float(f)
             } else {
-                query.bind(n.to_string())
+                MetadataParam::Text(n.to_string())
             }
         }
-        Value::Bool(b) => query.bind(*b),
-        Value::Null => query.bind::<Option<i32>>(None),
-        other => query.bind(other.to_string()),
+        Value::Bool(b) => MetadataParam::Bool(*b),
+        Value::Null => MetadataParam::Null,
+        other => MetadataParam::Text(other.to_string()),
+    }
+}
+
+fn bind_metadata<'q>(
+    query: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+    value: &Value,
+) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+    match metadata_param(value) {
+        MetadataParam::Int(i) => query.bind(i),
+        MetadataParam::UInt(u) => query.bind(u),
+        MetadataParam::Float(f) => query.bind(f),
+        MetadataParam::Text(s) => query.bind(s),
+        MetadataParam::Bool(b) => query.bind(b),
+        MetadataParam::Null => query.bind::<Option<i32>>(None),
     }
 }
 
 fn metadata_from_row<R: BackendRow>(row: &R) -> Value {
     // Try read as string first
-    if let Ok(Some(s)) = row.get_string("metadata") {
-        if let Ok(v) = serde_json::from_str::<Value>(&s) {
-            return v;
-        }
+    if let Ok(Some(s)) = row.get_string("metadata")
+        && let Ok(v) = serde_json::from_str::<Value>(&s)
+    {
+        return v;
     }
     // Fallback: try bytes (for JSON-typed columns)
-    if let Ok(Some(bytes)) = row.get_bytes("metadata") {
-        if let Ok(s) = String::from_utf8(bytes) {
-            if let Ok(v) = serde_json::from_str::<Value>(&s) {
-                return v;
-            }
-        }
+    if let Ok(Some(bytes)) = row.get_bytes("metadata")
+        && let Ok(s) = String::from_utf8(bytes)
+        && let Ok(v) = serde_json::from_str::<Value>(&s)
+    {
+        return v;
     }
     Value::Null
 }
@@ -1396,6 +3866,160 @@ fn merge_values(
     (doc, meta, emb)
 }
 
+/// Entries at `keep` (ascending indices) of the first query's column.
+fn first_query_hits<T>(column: Vec<Vec<T>>, keep: &[usize]) -> Vec<T> {
+    let mut keep = keep.iter().peekable();
+    column
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, v)| keep.next_if_eq(&&i).map(|_| v))
+        .collect()
+}
+
+/// Distinct [`PARENT_ID_KEY`] values of the hits in `result`, ordered by
+/// each parent's best rank in any query; ties go to the earlier query.
+fn parent_ids(result: &QueryResult) -> Result<Vec<String>> {
+    let Some(metadatas) = &result.metadatas else {
+        if result.ids.iter().all(Vec::is_empty) {
+            return Ok(Vec::new());
+        }
+        return Err(SeekDbError::InvalidInput(
+            "query results carry no metadatas; include IncludeField::Metadatas to resolve parents"
+                .into(),
+        ));
+    };
+    // (rank, query) of each parent's best hit; every hit names one parent,
+    // so the keys are distinct.
+    let mut best: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (query, metas) in metadatas.iter().enumerate() {
+        for (rank, meta) in metas.iter().enumerate() {
+            if let Some(id) = meta.get(PARENT_ID_KEY).and_then(Value::as_str) {
+                let key = (rank, query);
+                best.entry(id)
+                    .and_modify(|b| *b = (*b).min(key))
+                    .or_insert(key);
+            }
+        }
+    }
+    let mut ids: Vec<(&str, (usize, usize))> = best.into_iter().collect();
+    ids.sort_by_key(|&(_, key)| key);
+    Ok(ids.into_iter().map(|(id, _)| id.to_string()).collect())
+}
+
+/// Reorder the rows of `result` to follow `order`; ids not in it go last.
+fn sort_get_result(result: GetResult, order: &[String]) -> GetResult {
+    let rank: HashMap<&str, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    let mut perm: Vec<usize> = (0..result.ids.len()).collect();
+    perm.sort_by_key(|&i| {
+        rank.get(result.ids[i].as_str())
+            .copied()
+            .unwrap_or(usize::MAX)
+    });
+    fn pick<T: Clone>(column: &[T], perm: &[usize]) -> Vec<T> {
+        perm.iter().map(|&i| column[i].clone()).collect()
+    }
+    GetResult {
+        ids: pick(&result.ids, &perm),
+        documents: result.documents.as_deref().map(|c| pick(c, &perm)),
+        metadatas: result.metadatas.as_deref().map(|c| pick(c, &perm)),
+        embeddings: result.embeddings.as_deref().map(|c| pick(c, &perm)),
+        included: result.included,
+    }
+}
+
+/// Splits the first query of `result` into groups keyed by
+/// `metadata[group_field]`, keeping at most `per_group` hits per group and
+/// `total` overall, in hit order.
+fn group_query_hits(
+    result: QueryResult,
+    group_field: &str,
+    per_group: usize,
+    total: usize,
+    keep_metadatas: bool,
+) -> Vec<QueryGroup> {
+    let mut groups: Vec<(Metadata, Vec<usize>)> = Vec::new();
+    let metas = result.metadatas.as_ref().and_then(|m| m.first());
+    let hits = result.ids.first().map_or(0, Vec::len);
+    let mut kept = 0;
+    for i in 0..hits {
+        if kept == total {
+            break;
+        }
+        let key = metas
+            .and_then(|m| m.get(i))
+            .and_then(|m| m.get(group_field))
+            .cloned()
+            .unwrap_or(Value::Null);
+        let members = match groups.iter().position(|(k, _)| *k == key) {
+            Some(pos) => &mut groups[pos].1,
+            None => {
+                groups.push((key, Vec::new()));
+                &mut groups.last_mut().expect("just pushed").1
+            }
+        };
+        if members.len() < per_group {
+            members.push(i);
+            kept += 1;
+        }
+    }
+
+    fn column<T>(col: Option<Vec<Vec<T>>>) -> Option<Vec<Option<T>>> {
+        col.map(|c| {
+            c.into_iter()
+                .next()
+                .unwrap_or_default()
+                .into_iter()
+                .map(Some)
+                .collect()
+        })
+    }
+    fn take<T>(col: &mut Option<Vec<Option<T>>>, keep: &[usize]) -> Option<Vec<Vec<T>>> {
+        col.as_mut()
+            .map(|c| vec![keep.iter().filter_map(|&i| c[i].take()).collect()])
+    }
+    let included = IncludeFlags {
+        metadatas: keep_metadatas,
+        ..result.included
+    };
+    let mut ids = column(Some(result.ids));
+    let mut documents = column(result.documents);
+    let mut metadatas = column(result.metadatas.filter(|_| keep_metadatas));
+    let mut embeddings = column(result.embeddings);
+    let mut distances = column(result.distances);
+    let mut distances_f64 = column(result.distances_f64);
+    groups
+        .into_iter()
+        .map(|(key, keep)| QueryGroup {
+            key,
+            hits: QueryResult {
+                ids: take(&mut ids, &keep).unwrap_or_default(),
+                documents: take(&mut documents, &keep),
+                metadatas: take(&mut metadatas, &keep),
+                embeddings: take(&mut embeddings, &keep),
+                distances: take(&mut distances, &keep),
+                distances_f64: take(&mut distances_f64, &keep),
+                included,
+            },
+        })
+        .collect()
+}
+
+/// Statement putting `ob_hnsw_ef_search` back to `previous`, or to the server
+/// default when it could not be read.
+fn restore_ef_search_sql(previous: Option<i64>) -> String {
+    match previous {
+        Some(value) => format!("SET ob_hnsw_ef_search = {value}"),
+        None => "SET ob_hnsw_ef_search = DEFAULT".to_string(),
+    }
+}
+
 fn empty_query_result(include: Option<&[IncludeField]>) -> QueryResult {
     QueryResult {
         ids: vec![Vec::new()],
@@ -1414,7 +4038,9 @@ fn empty_query_result(include: Option<&[IncludeField]>) -> QueryResult {
         } else {
             None
         },
-        distances: Some(vec![Vec::new()]),
+        distances: include_distances(include).then(|| vec![Vec::new()]),
+        distances_f64: None,
+        included: IncludeFlags::from_include(include),
     }
 }
 
@@ -1476,13 +4102,19 @@ fn transform_hybrid_rows<R: BackendRow>(
         } else {
             None
         },
-        distances: Some(vec![dists]),
+        distances: include_distances(include).then(|| vec![dists]),
+        distances_f64: None,
+        included: IncludeFlags::from_include(include),
     }
 }
 
 fn is_hybrid_invalid_argument(err: &SeekDbError) -> bool {
     match err {
-        SeekDbError::Sql(msg) => {
+        SeekDbError::Database {
+            code: Some(ER_INVALID_ARGUMENT),
+            ..
+        } => true,
+        SeekDbError::Database { message: msg, .. } | SeekDbError::Sql { message: msg, .. } => {
             let lower = msg.to_lowercase();
             lower.contains("invalid argument") || lower.contains("1210")
         }
@@ -1490,6 +4122,9 @@ fn is_hybrid_invalid_argument(err: &SeekDbError) -> bool {
     }
 }
 
+/// MySQL `ER_WRONG_ARGUMENTS`, returned for malformed hybrid search parameters.
+const ER_INVALID_ARGUMENT: u16 = 1210;
+
 #[derive(serde::Serialize)]
 struct HybridSearchParam {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1655,13 +4290,7 @@ async fn build_knn_expr_from_hybrid<Ef: EmbeddingFunction + 'static>(
         )
     })?;
 
-    let first = texts[0].clone();
-    let embs = ef.embed_documents(&[first]).await?;
-    let Some(query_vector) = embs.into_iter().next() else {
-        return Err(SeekDbError::InvalidInput(
-            "embedding_function returned empty embeddings for knn.query_texts".into(),
-        ));
-    };
+    let query_vector = ef.embed_query(&texts[0]).await?;
 
     if query_vector.len() as u32 != collection.dimension {
         return Err(SeekDbError::InvalidInput(format!(
@@ -1754,12 +4383,7 @@ async fn build_search_parm_json<Ef: EmbeddingFunction + 'static>(
                     .into(),
             )
         })?;
-        let embs = ef.embed_documents(&[queries[0].clone()]).await?;
-        let Some(first) = embs.first() else {
-            return Err(SeekDbError::InvalidInput(
-                "embedding_function returned empty embeddings".into(),
-            ));
-        };
+        let first = ef.embed_query(&queries[0]).await?;
         if first.len() as u32 != collection.dimension {
             return Err(SeekDbError::InvalidInput(format!(
                 "embedding dimension {} does not match collection dimension {}",
@@ -1775,7 +4399,7 @@ async fn build_search_parm_json<Ef: EmbeddingFunction + 'static>(
         knn_expr = Some(HybridKnnExpr {
             field: "embedding".into(),
             k: n_results,
-            query_vector: first.clone(),
+            query_vector: first,
             filter: knn_filter,
         });
     }
@@ -1812,6 +4436,23 @@ fn build_metadata_filter_for_search_parm(filter: &Filter) -> Vec<Value> {
         Filter::Nin { field, values } => {
             vec![json!({"bool": { "must_not": [ {"terms": { meta_path(field): values }} ]}})]
         }
+        // A term on an array field matches when any element equals the value.
+        Filter::ArrayContains { field, value } => {
+            vec![json!({"term": { meta_path(field): value }})]
+        }
+        Filter::Between { field, low, high } => {
+            vec![json!({"range": { meta_path(field): { "gte": low, "lte": high }}})]
+        }
+        Filter::Like { field, pattern } => {
+            vec![json!({"wildcard": { meta_path(field): like_to_wildcard(pattern) }})]
+        }
+        Filter::StartsWith { field, prefix } => {
+            vec![json!({"prefix": { meta_path(field): prefix }})]
+        }
+        Filter::Exists { field } => vec![json!({"exists": { "field": meta_path(field) }})],
+        Filter::NotExists { field } => {
+            vec![json!({"bool": { "must_not": [ {"exists": { "field": meta_path(field) }} ]}})]
+        }
         Filter::And(filters) => {
             let mut parts = Vec::new();
             for f in filters {
@@ -1855,12 +4496,63 @@ fn build_metadata_filter_for_search_parm(filter: &Filter) -> Vec<Value> {
     }
 }
 
+/// Translate a SQL `LIKE` pattern into the `*` / `?` syntax of a `wildcard`
+/// term. Characters that are literal in `LIKE` but special in a wildcard
+/// (`*`, `?` and `\`) are backslash-escaped so both paths match the same rows.
+fn like_to_wildcard(pattern: &str) -> String {
+    fn push_literal(out: &mut String, c: char) {
+        if matches!(c, '*' | '?' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => out.push('*'),
+            '_' => out.push('?'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    push_literal(&mut out, escaped);
+                }
+            }
+            other => push_literal(&mut out, other),
+        }
+    }
+    out
+}
+
+/// ORDER BY for a vector query: closest first (descending for metrics where
+/// higher is closer, such as inner product), then the optional metadata key,
+/// then `_id` so equal-distance rows come back in a stable order.
+fn query_order_by(
+    metric: DistanceMetric,
+    vector_column: &str,
+    then_by: Option<(&str, SortDir)>,
+    dialect: JsonDialect,
+) -> String {
+    let distance_func = distance_fn(metric);
+    let direction = if metric.higher_is_closer() {
+        "DESC"
+    } else {
+        "ASC"
+    };
+    let secondary = then_by
+        .map(|(field, dir)| format!("({}) {}, ", dialect.json_extract(field), dir.as_sql()))
+        .unwrap_or_default();
+    format!("ORDER BY {distance_func}({vector_column}, ?) {direction}, {secondary}_id ASC")
+}
+
+/// Metadata field key for a hybrid search parameter. The search DSL is parsed
+/// by the server itself, which takes the function form in every dialect.
 fn meta_path(field: &str) -> String {
-    format!("(JSON_EXTRACT(metadata, '$.{field}'))")
+    format!("({})", JsonDialect::Function.json_extract(field))
 }
 
 fn build_document_query_for_search_parm(where_doc: Option<&DocFilter>) -> Option<Value> {
-    let Some(filter) = where_doc else { return None };
+    let filter = where_doc?;
     match filter {
         DocFilter::Contains(text) => {
             Some(json!({"query_string": { "fields": ["document"], "query": text } }))
@@ -1902,12 +4594,361 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn metadata_param_keeps_large_unsigned_integers() {
+        assert_eq!(
+            metadata_param(&json!(u64::MAX)),
+            MetadataParam::UInt(u64::MAX)
+        );
+        assert_eq!(
+            metadata_param(&json!(i64::MAX as u64 + 1)),
+            MetadataParam::UInt(i64::MAX as u64 + 1)
+        );
+        assert_eq!(metadata_param(&json!(-7)), MetadataParam::Int(-7));
+        assert_eq!(metadata_param(&json!(42u64)), MetadataParam::Int(42));
+        assert_eq!(metadata_param(&json!(1.5)), MetadataParam::Float(1.5));
+        assert_eq!(metadata_param(&json!(null)), MetadataParam::Null);
+    }
+
+    #[test]
+    fn group_query_hits_caps_groups_and_total() {
+        let result = QueryResult {
+            ids: vec![["a", "b", "c", "d", "e", "f"].map(String::from).to_vec()],
+            documents: None,
+            metadatas: Some(vec![vec![
+                json!({"cat": "x"}),
+                json!({"cat": "x"}),
+                json!({"cat": "x"}),
+                json!({"cat": "y"}),
+                json!({}),
+                json!({"cat": "z"}),
+            ]]),
+            embeddings: None,
+            distances: Some(vec![vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6]]),
+            distances_f64: None,
+            included: IncludeFlags::default(),
+        };
+
+        let groups = group_query_hits(result.clone(), "cat", 2, 4, false);
+        let keys: Vec<_> = groups.iter().map(|g| g.key.clone()).collect();
+        assert_eq!(keys, vec![json!("x"), json!("y"), Value::Null]);
+        assert_eq!(groups[0].hits.ids, vec![vec!["a", "b"]]);
+        assert_eq!(groups[0].hits.distances, Some(vec![vec![0.1, 0.2]]));
+        assert!(groups[0].hits.metadatas.is_none());
+        assert_eq!(groups[2].hits.ids, vec![vec!["e"]]);
+
+        let groups = group_query_hits(result, "cat", 1, 10, true);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            groups[1].hits.metadatas,
+            Some(vec![vec![json!({"cat": "y"})]])
+        );
+    }
+
+    #[test]
+    fn parent_ids_dedups_in_best_rank_order() {
+        let mut result = QueryResult {
+            ids: vec![
+                ["c1", "c2", "c3"].map(String::from).to_vec(),
+                ["c4", "c5"].map(String::from).to_vec(),
+            ],
+            documents: None,
+            metadatas: Some(vec![
+                vec![
+                    json!({"parent_id": "p2"}),
+                    json!({"parent_id": "p1"}),
+                    json!({"parent_id": "p2"}),
+                ],
+                vec![json!({}), json!({"parent_id": "p3"})],
+            ]),
+            embeddings: None,
+            distances: None,
+            distances_f64: None,
+            included: IncludeFlags::default(),
+        };
+        assert_eq!(parent_ids(&result).unwrap(), vec!["p2", "p1", "p3"]);
+
+        // A top hit in a later query outranks a second-place hit in the first.
+        result.metadatas.as_mut().unwrap()[1] =
+            vec![json!({"parent_id": "p3"}), json!({"parent_id": "p1"})];
+        assert_eq!(parent_ids(&result).unwrap(), vec!["p2", "p3", "p1"]);
+
+        result.metadatas = None;
+        assert!(matches!(
+            parent_ids(&result),
+            Err(SeekDbError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn sort_get_result_follows_order() {
+        let result = GetResult {
+            ids: ["p1", "p3", "p2"].map(String::from).to_vec(),
+            documents: Some(["one", "three", "two"].map(String::from).to_vec()),
+            metadatas: None,
+            embeddings: None,
+            included: IncludeFlags::default(),
+        };
+        let order = ["p2", "p1", "p3"].map(String::from);
+        let sorted = sort_get_result(result, &order);
+        assert_eq!(sorted.ids, vec!["p2", "p1", "p3"]);
+        assert_eq!(
+            sorted.documents,
+            Some(["two", "one", "three"].map(String::from).to_vec())
+        );
+    }
+
+    #[test]
+    fn first_query_hits_picks_indices() {
+        let column = vec![vec!["a", "b", "c", "d"], vec!["x"]];
+        assert_eq!(first_query_hits(column, &[0, 2, 3]), vec!["a", "c", "d"]);
+        assert!(first_query_hits::<u8>(Vec::new(), &[0]).is_empty());
+    }
+
+    #[test]
+    fn validate_cursor_rejects_impossible_ids() {
+        assert!(validate_cursor(None).is_ok());
+        assert!(validate_cursor(Some("id-1")).is_ok());
+        assert!(validate_cursor(Some(&"x".repeat(512))).is_ok());
+        assert!(validate_cursor(Some("")).is_err());
+        assert!(validate_cursor(Some(&"x".repeat(513))).is_err());
+    }
+
+    #[test]
+    fn fit_dimension_pads_and_truncates() {
+        assert_eq!(fit_dimension(&[1.0, 2.0], 4), vec![1.0, 2.0, 0.0, 0.0]);
+        assert_eq!(fit_dimension(&[1.0, 2.0, 3.0], 2), vec![1.0, 2.0]);
+        assert_eq!(fit_dimension(&[1.0, 2.0], 2), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_check_metadata_size() {
+        let meta = json!({"k": "v"});
+        // `{"k":"v"}` is 9 bytes.
+        assert!(check_metadata_size("a", &meta, 9).is_ok());
+        let err = check_metadata_size("a", &meta, 8).expect_err("over the cap");
+        match err {
+            SeekDbError::InvalidInput(msg) => {
+                assert!(
+                    msg.starts_with("metadata for id a exceeds 8 bytes"),
+                    "{msg}"
+                )
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        let big = json!({"blob": "x".repeat(DEFAULT_MAX_METADATA_BYTES)});
+        assert!(check_metadata_size("b", &big, DEFAULT_MAX_METADATA_BYTES).is_err());
+    }
+
+    #[test]
+    fn test_insert_prefix() {
+        assert_eq!(
+            insert_prefix("t", OnConflict::Error, &[]),
+            "INSERT INTO `t` (_id, document, metadata, embedding) VALUES "
+        );
+        assert!(insert_prefix("t", OnConflict::Skip, &[]).starts_with("INSERT IGNORE INTO `t` "));
+        assert!(insert_prefix("t", OnConflict::Replace, &[]).starts_with("INSERT INTO `t` "));
+        assert_eq!(insert_suffix(OnConflict::Error, &["title_vec"]), "");
+        assert_eq!(insert_suffix(OnConflict::Skip, &[]), "");
+        assert_eq!(
+            insert_suffix(OnConflict::Replace, &["title_vec"]),
+            " ON DUPLICATE KEY UPDATE document = VALUES(document), \
+             metadata = VALUES(metadata), embedding = VALUES(embedding), \
+             `title_vec` = VALUES(`title_vec`)"
+        );
+        assert_eq!(
+            insert_prefix("t", OnConflict::Error, &["title_vec", "body_vec"]),
+            "INSERT INTO `t` (_id, document, metadata, embedding, `title_vec`, `body_vec`) VALUES "
+        );
+        assert_eq!(insert_row_placeholders(0), "(?, ?, ?, ?)");
+        assert_eq!(insert_row_placeholders(2), "(?, ?, ?, ?, ?, ?)");
+    }
+
+    #[test]
+    fn test_restore_ef_search_sql() {
+        assert_eq!(
+            restore_ef_search_sql(Some(64)),
+            "SET ob_hnsw_ef_search = 64"
+        );
+        assert_eq!(
+            restore_ef_search_sql(None),
+            "SET ob_hnsw_ef_search = DEFAULT"
+        );
+    }
+
+    #[test]
+    fn test_query_order_by() {
+        assert_eq!(
+            query_order_by(DistanceMetric::L2, "embedding", None, JsonDialect::Function),
+            "ORDER BY l2_distance(embedding, ?) ASC, _id ASC"
+        );
+        assert_eq!(
+            query_order_by(
+                DistanceMetric::Cosine,
+                "embedding",
+                Some(("meta.ts", SortDir::Desc)),
+                JsonDialect::Function
+            ),
+            "ORDER BY cosine_distance(embedding, ?) ASC, (JSON_EXTRACT(metadata, '$.meta.ts')) DESC, _id ASC"
+        );
+        assert_eq!(
+            query_order_by(
+                DistanceMetric::L2,
+                "`title_vec`",
+                None,
+                JsonDialect::Function
+            ),
+            "ORDER BY l2_distance(`title_vec`, ?) ASC, _id ASC"
+        );
+        // Larger inner products are closer, so they come first.
+        assert_eq!(
+            query_order_by(
+                DistanceMetric::Dot,
+                "embedding",
+                None,
+                JsonDialect::Function
+            ),
+            "ORDER BY inner_product(embedding, ?) DESC, _id ASC"
+        );
+        assert_eq!(
+            query_order_by(
+                DistanceMetric::L2,
+                "embedding",
+                Some(("rank", SortDir::Asc)),
+                JsonDialect::Operator
+            ),
+            "ORDER BY l2_distance(embedding, ?) ASC, (metadata->'$.rank') ASC, _id ASC"
+        );
+    }
+
     #[test]
     fn test_vector_roundtrip() {
         let v = vec![1.0, 2.5, 3.0];
-        let s = vector_to_string(&v);
+        let s = vector_to_string(&v, VectorLiteralFormat::Shortest);
         assert_eq!(s, "[1,2.5,3]");
         assert_eq!(parse_vector_string(s), v);
+        assert_eq!(
+            vector_to_string(&vec![0.1], VectorLiteralFormat::FullPrecision),
+            "[1.00000001e-1]"
+        );
+    }
+
+    #[test]
+    fn test_vector_roundtrip_tricky_values() {
+        let v = vec![
+            0.0,
+            -0.0,
+            f32::MIN_POSITIVE,
+            f32::MIN_POSITIVE / 3.0,
+            f32::from_bits(1),
+            -f32::from_bits(0x007f_ffff),
+            f32::MAX,
+            f32::MIN,
+            f32::EPSILON,
+            1.0 + f32::EPSILON,
+            0.1,
+            1.0 / 3.0,
+            16_777_217.0,
+            -123_456.79,
+        ];
+        let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        for format in [
+            VectorLiteralFormat::Shortest,
+            VectorLiteralFormat::FullPrecision,
+        ] {
+            let parsed = parse_vector_string(vector_to_string(&v, format));
+            assert_eq!(bits(&parsed), bits(&v), "{format:?}");
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_embeddings_from_array() {
+        let array = ndarray::array![[1.0_f32, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let embs = embeddings_from_array(array.view(), 3).unwrap();
+        assert_eq!(embs, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+
+        // Non-contiguous views (here: transposed) are read row by row too.
+        let embs = embeddings_from_array(array.t(), 2).unwrap();
+        assert_eq!(embs[0], vec![1.0, 4.0]);
+
+        let err = embeddings_from_array(array.view(), 4).unwrap_err();
+        assert!(matches!(err, SeekDbError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_validate_vector_rejects_non_finite_and_empty() {
+        assert!(validate_vector(&[0.1, -2.0, 3.5]).is_ok());
+        for bad in [
+            vec![0.1, f32::NAN, 0.3],
+            vec![f32::INFINITY, 0.0, 0.0],
+            vec![0.0, 0.0, f32::NEG_INFINITY],
+            vec![],
+        ] {
+            let err = validate_vector(&bad).unwrap_err();
+            assert!(matches!(err, SeekDbError::InvalidInput(_)), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_validate_lengths_rejects_empty_embeddings_slice() {
+        let ids = vec!["a".into()];
+        let err = validate_lengths(&ids, &[], None, None, 3).unwrap_err();
+        assert!(
+            matches!(err, SeekDbError::InvalidInput(ref msg) if msg == "empty embeddings slice; pass None to auto-generate"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_validate_lengths_rejects_nan_embedding() {
+        let ids = vec!["a".into()];
+        let embeddings = vec![vec![0.1_f32, f32::NAN, 0.2_f32]];
+        let err = validate_lengths(&ids, &embeddings, None, None, 3).unwrap_err();
+        assert!(matches!(err, SeekDbError::InvalidInput(msg) if msg.contains("not finite")));
+    }
+
+    #[test]
+    fn test_estimate_statement_bytes_counts_params() {
+        let sql = "INSERT INTO t VALUES (?, ?)";
+        assert_eq!(estimate_statement_bytes(sql, &[]), sql.len());
+        assert_eq!(
+            estimate_statement_bytes(sql, &[3, 10]),
+            sql.len() + 3 + 10 + 18
+        );
+
+        // A 4096-dim literal alone is tens of KB once serialized.
+        let big = vector_to_string(&vec![0.123_456_78_f32; 4096], VectorLiteralFormat::Shortest);
+        assert!(estimate_statement_bytes(sql, &[big.len()]) > 40_000);
+    }
+
+    #[test]
+    fn test_plan_insert_chunks_by_rows_and_bytes() {
+        assert!(plan_insert_chunks(&[], 10, 1000, 100).is_empty());
+        // Row limit only.
+        assert_eq!(
+            plan_insert_chunks(&[1; 5], 0, 2, usize::MAX),
+            vec![0..2, 2..4, 4..5]
+        );
+        // Byte budget shrinks chunks below the row limit.
+        assert_eq!(
+            plan_insert_chunks(&[40, 40, 40, 10], 20, 1000, 100),
+            vec![0..2, 2..4]
+        );
+        // An oversized row still gets its own chunk (callers reject it earlier).
+        assert_eq!(plan_insert_chunks(&[500, 1], 0, 10, 100), vec![0..1, 1..2]);
+    }
+
+    #[test]
+    fn test_partial_write_only_wraps_after_progress() {
+        let boom = || SeekDbError::Sql {
+            message: "boom".into(),
+            statement: None,
+        };
+        let err = partial_write(0, boom());
+        assert!(matches!(err, SeekDbError::Sql { .. }));
+        let err = partial_write(3, boom());
+        assert!(matches!(err, SeekDbError::PartialWrite { written: 3, .. }));
     }
 
     #[test]
@@ -1918,6 +4959,18 @@ mod tests {
         assert!(matches!(err, SeekDbError::InvalidInput(_)));
     }
 
+    #[test]
+    fn test_npy_header_layout() {
+        let header = npy_header(3, 384);
+        assert_eq!(header.len() % 64, 0);
+        assert_eq!(&header[..8], b"\x93NUMPY\x01\x00");
+        let dict_len = u16::from_le_bytes([header[8], header[9]]) as usize;
+        assert_eq!(dict_len, header.len() - 10);
+        let dict = std::str::from_utf8(&header[10..]).unwrap();
+        assert!(dict.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (3, 384), }"));
+        assert!(dict.ends_with(" \n"));
+    }
+
     #[test]
     fn test_merge_values() {
         let (doc, meta, emb) = merge_values(
@@ -1932,4 +4985,106 @@ mod tests {
         assert_eq!(meta["x"], 2);
         assert!(emb.is_some());
     }
+
+    #[test]
+    fn test_meta_path_nested_and_quoted() {
+        assert_eq!(meta_path("a.b.c"), "(JSON_EXTRACT(metadata, '$.a.b.c'))");
+        assert_eq!(
+            meta_path("tags[0]"),
+            "(JSON_EXTRACT(metadata, '$.tags[0]'))"
+        );
+        assert_eq!(
+            meta_path("doc's.x-y"),
+            "(JSON_EXTRACT(metadata, '$.\"doc''s\".\"x-y\"'))"
+        );
+    }
+
+    #[test]
+    fn test_search_parm_between_is_single_range() {
+        let parts = build_metadata_filter_for_search_parm(&Filter::Between {
+            field: "score".into(),
+            low: json!(1.5),
+            high: json!(3),
+        });
+        assert_eq!(
+            parts,
+            vec![json!({"range": {"(JSON_EXTRACT(metadata, '$.score'))": {"gte": 1.5, "lte": 3}}})]
+        );
+    }
+
+    #[test]
+    fn test_search_parm_like_and_prefix() {
+        let like = build_metadata_filter_for_search_parm(&Filter::Like {
+            field: "title".into(),
+            pattern: "ru_t%50\\%".into(),
+        });
+        assert_eq!(
+            like,
+            vec![json!({"wildcard": {"(JSON_EXTRACT(metadata, '$.title'))": "ru?t*50%"}})]
+        );
+        // `*`, `?` and `\` are literal in LIKE, so they stay literal here.
+        let literal = build_metadata_filter_for_search_parm(&Filter::Like {
+            field: "title".into(),
+            pattern: "a*b?c\\\\d%".into(),
+        });
+        assert_eq!(
+            literal,
+            vec![json!({"wildcard": {"(JSON_EXTRACT(metadata, '$.title'))": "a\\*b\\?c\\\\d*"}})]
+        );
+        let prefix = build_metadata_filter_for_search_parm(&Filter::StartsWith {
+            field: "path".into(),
+            prefix: "docs/".into(),
+        });
+        assert_eq!(
+            prefix,
+            vec![json!({"prefix": {"(JSON_EXTRACT(metadata, '$.path'))": "docs/"}})]
+        );
+    }
+
+    #[test]
+    fn test_schema_mismatch() {
+        let schema = |dimension, distance, columns: &[&str]| CollectionSchema {
+            name: "c".into(),
+            dimension,
+            distance,
+            index_type: None,
+            index_params: Default::default(),
+            has_fulltext: false,
+            fulltext_parser: None,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            row_count: 0,
+        };
+        let full = ["_id", "document", "embedding", "metadata"];
+        let target = schema(384, DistanceMetric::L2, &full);
+        assert_eq!(
+            schema_mismatch(&target, &schema(384, DistanceMetric::L2, &full[..3])),
+            None
+        );
+        assert_eq!(
+            schema_mismatch(&target, &schema(768, DistanceMetric::L2, &full)).as_deref(),
+            Some("dimension 384 != 768")
+        );
+        assert_eq!(
+            schema_mismatch(
+                &schema(384, DistanceMetric::Cosine, &full[..3]),
+                &schema(384, DistanceMetric::L2, &full)
+            )
+            .as_deref(),
+            Some("distance cosine != L2; missing columns metadata")
+        );
+    }
+
+    #[test]
+    fn test_include_helpers() {
+        assert!(include_distances(None));
+        assert!(!include_embeddings(None));
+        assert!(!include_distances(Some(IncludeField::none())));
+        assert_eq!(build_select_clause(Some(IncludeField::none())), "_id");
+        let all = Some(IncludeField::all());
+        assert!(include_distances(all) && include_embeddings(all));
+        assert_eq!(
+            build_select_clause(all),
+            "_id, document, CAST(metadata AS CHAR) AS metadata, embedding"
+        );
+    }
 }
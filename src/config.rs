@@ -1,4 +1,10 @@
 use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use url::Url;
 
 use crate::error::{Result, SeekDbError};
 
@@ -12,12 +18,34 @@ pub struct ServerConfig {
     pub user: String,
     pub password: String,
     pub max_connections: u32,
+    /// Connections the pool keeps open even when idle.
+    pub min_connections: u32,
+    /// Close connections idle for longer than this (above `min_connections`);
+    /// `None` keeps them indefinitely.
+    pub idle_timeout: Option<Duration>,
+    /// Recycle connections older than this; `None` never recycles.
+    pub max_lifetime: Option<Duration>,
+    /// How many times a transient connection failure (refused/reset
+    /// connection, pool timeout) is retried; `0` disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry; each further retry doubles it.
+    pub base_backoff: Duration,
+    /// TLS negotiation mode for connections to the server.
+    pub ssl_mode: SslMode,
+    /// PEM file with the CA certificate(s) used to verify the server when
+    /// `ssl_mode` is `VerifyCa` or `VerifyIdentity`.
+    pub ca_cert_path: Option<PathBuf>,
 }
 
 impl ServerConfig {
     /// Build configuration from environment variables:
     /// `SERVER_HOST`, `SERVER_PORT`, `SERVER_TENANT`, `SERVER_DATABASE`,
-    /// `SERVER_USER`, `SERVER_PASSWORD`, `SERVER_MAX_CONNECTIONS` (optional, default 5).
+    /// `SERVER_USER`, `SERVER_PASSWORD`, `SERVER_MAX_CONNECTIONS` (optional, default 5),
+    /// `SERVER_MIN_CONNECTIONS` (optional, default 0), `SERVER_IDLE_TIMEOUT_SECS`
+    /// (optional, default 600), `SERVER_MAX_LIFETIME_SECS` (optional, default 1800),
+    /// `SERVER_MAX_RETRIES` (optional, default 0), `SERVER_BASE_BACKOFF_MS`
+    /// (optional, default 100), `SERVER_SSL_MODE` (optional, default `preferred`)
+    /// and `SERVER_SSL_CA` (optional). A timeout of `0` disables it.
     pub fn from_env() -> Result<Self> {
         let host = require_env("SERVER_HOST")?;
         let port = parse_env("SERVER_PORT").unwrap_or(2881);
@@ -26,6 +54,19 @@ impl ServerConfig {
         let user = require_env("SERVER_USER")?;
         let password = require_env("SERVER_PASSWORD")?;
         let max_connections = parse_env("SERVER_MAX_CONNECTIONS").unwrap_or(5);
+        let min_connections = parse_env("SERVER_MIN_CONNECTIONS").unwrap_or(0);
+        let idle_timeout =
+            parse_env("SERVER_IDLE_TIMEOUT_SECS").map_or(DEFAULT_IDLE_TIMEOUT, timeout_from_secs);
+        let max_lifetime =
+            parse_env("SERVER_MAX_LIFETIME_SECS").map_or(DEFAULT_MAX_LIFETIME, timeout_from_secs);
+        let max_retries = parse_env("SERVER_MAX_RETRIES").unwrap_or(0);
+        let base_backoff =
+            parse_env("SERVER_BASE_BACKOFF_MS").map_or(DEFAULT_BASE_BACKOFF, Duration::from_millis);
+        let ssl_mode = match env::var("SERVER_SSL_MODE") {
+            Ok(v) => v.parse()?,
+            Err(_) => SslMode::default(),
+        };
+        let ca_cert_path = env::var_os("SERVER_SSL_CA").map(PathBuf::from);
 
         Ok(Self {
             host,
@@ -35,8 +76,218 @@ impl ServerConfig {
             user,
             password,
             max_connections,
+            min_connections,
+            idle_timeout,
+            max_lifetime,
+            max_retries,
+            base_backoff,
+            ssl_mode,
+            ca_cert_path,
         })
     }
+
+    /// Parse a connection URL of the form
+    /// `mysql://user@tenant:password@host:port/database?max_connections=N`.
+    ///
+    /// The tenant is taken from the last `@` in the user part and defaults to
+    /// `sys`; the port defaults to 2881. Credentials may be percent-encoded.
+    /// Supported query parameters are `max_connections`, `min_connections`,
+    /// `idle_timeout` and `max_lifetime` (seconds, `0` disables), `max_retries`,
+    /// `base_backoff_ms`, `ssl_mode` and `ssl_ca`.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let parsed =
+            Url::parse(url).map_err(|e| SeekDbError::Config(format!("invalid url: {e}")))?;
+        if parsed.scheme() != "mysql" {
+            return Err(SeekDbError::Config(format!(
+                "unsupported url scheme '{}'; expected mysql",
+                parsed.scheme()
+            )));
+        }
+        let host = match parsed.host_str() {
+            Some(h) if !h.is_empty() => h.to_string(),
+            _ => return Err(SeekDbError::Config("url is missing a host".into())),
+        };
+        let user_tenant = url_decode(parsed.username())?;
+        let (user, tenant) = match user_tenant.rsplit_once('@') {
+            Some((user, tenant)) => (user.to_string(), tenant.to_string()),
+            None => (user_tenant, "sys".to_string()),
+        };
+        if user.is_empty() {
+            return Err(SeekDbError::Config("url is missing a user".into()));
+        }
+        let password = url_decode(parsed.password().unwrap_or(""))?;
+        let database = url_decode(parsed.path().trim_start_matches('/'))?;
+        if database.is_empty() {
+            return Err(SeekDbError::Config("url is missing a database".into()));
+        }
+
+        let mut config = Self {
+            host,
+            port: parsed.port().unwrap_or(2881),
+            tenant,
+            database,
+            user,
+            password,
+            max_connections: 5,
+            min_connections: 0,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            max_lifetime: DEFAULT_MAX_LIFETIME,
+            max_retries: 0,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            ssl_mode: SslMode::default(),
+            ca_cert_path: None,
+        };
+        for (key, value) in parsed.query_pairs() {
+            match key.as_ref() {
+                "max_connections" => config.max_connections = parse_param(&key, &value)?,
+                "min_connections" => config.min_connections = parse_param(&key, &value)?,
+                "idle_timeout" => {
+                    config.idle_timeout = timeout_from_secs(parse_param(&key, &value)?);
+                }
+                "max_lifetime" => {
+                    config.max_lifetime = timeout_from_secs(parse_param(&key, &value)?);
+                }
+                "max_retries" => config.max_retries = parse_param(&key, &value)?,
+                "base_backoff_ms" => {
+                    config.base_backoff = Duration::from_millis(parse_param(&key, &value)?);
+                }
+                "ssl_mode" => config.ssl_mode = value.parse()?,
+                "ssl_ca" => config.ca_cert_path = Some(PathBuf::from(value.as_ref())),
+                other => {
+                    return Err(SeekDbError::Config(format!(
+                        "unknown url parameter: {other}"
+                    )));
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Connection URL for this configuration, the inverse of
+    /// [`from_url`](Self::from_url).
+    ///
+    /// User, tenant, password and database are percent-encoded, so values
+    /// containing `@`, `:`, `/`, `?` or `#` survive the round trip (except an
+    /// `@` in the tenant, which `from_url` cannot tell apart from the
+    /// user/tenant separator). Settings
+    /// equal to the `from_url` defaults are left out of the query string. The
+    /// URL contains the password; do not log it.
+    pub fn to_url(&self) -> String {
+        let mut url = format!(
+            "mysql://{}@{}",
+            url_encode(&self.user),
+            url_encode(&self.tenant)
+        );
+        if !self.password.is_empty() {
+            url.push(':');
+            url.push_str(&url_encode(&self.password));
+        }
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        url.push_str(&format!(
+            "@{host}:{}/{}",
+            self.port,
+            url_encode(&self.database)
+        ));
+
+        let secs = |d: Option<Duration>| d.map_or(0, |d| d.as_secs()).to_string();
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if self.max_connections != 5 {
+            query.append_pair("max_connections", &self.max_connections.to_string());
+        }
+        if self.min_connections != 0 {
+            query.append_pair("min_connections", &self.min_connections.to_string());
+        }
+        if self.idle_timeout != DEFAULT_IDLE_TIMEOUT {
+            query.append_pair("idle_timeout", &secs(self.idle_timeout));
+        }
+        if self.max_lifetime != DEFAULT_MAX_LIFETIME {
+            query.append_pair("max_lifetime", &secs(self.max_lifetime));
+        }
+        if self.max_retries != 0 {
+            query.append_pair("max_retries", &self.max_retries.to_string());
+        }
+        if self.base_backoff != DEFAULT_BASE_BACKOFF {
+            query.append_pair(
+                "base_backoff_ms",
+                &self.base_backoff.as_millis().to_string(),
+            );
+        }
+        if self.ssl_mode != SslMode::default() {
+            query.append_pair("ssl_mode", self.ssl_mode.as_str());
+        }
+        if let Some(ca) = &self.ca_cert_path {
+            query.append_pair("ssl_ca", &ca.to_string_lossy());
+        }
+        let query = query.finish();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+        url
+    }
+}
+
+/// TLS mode used when connecting to the server, mirroring the MySQL client's
+/// `--ssl-mode` option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SslMode {
+    /// Never use TLS.
+    Disabled,
+    /// Use TLS if the server supports it, otherwise fall back to plain text.
+    #[default]
+    Preferred,
+    /// Require TLS but do not verify the server certificate.
+    Required,
+    /// Require TLS and verify the server certificate against the CA.
+    VerifyCa,
+    /// Like `VerifyCa`, and also check the certificate matches the host name.
+    VerifyIdentity,
+}
+
+impl SslMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disabled => "disabled",
+            SslMode::Preferred => "preferred",
+            SslMode::Required => "required",
+            SslMode::VerifyCa => "verify_ca",
+            SslMode::VerifyIdentity => "verify_identity",
+        }
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = SeekDbError;
+
+    /// Parse a mode name case-insensitively; `-` and `_` are interchangeable
+    /// so both `verify-ca` and `VERIFY_CA` are accepted.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "disabled" => Ok(SslMode::Disabled),
+            "preferred" => Ok(SslMode::Preferred),
+            "required" => Ok(SslMode::Required),
+            "verify_ca" => Ok(SslMode::VerifyCa),
+            "verify_identity" => Ok(SslMode::VerifyIdentity),
+            other => Err(SeekDbError::Config(format!(
+                "invalid ssl mode '{other}'; expected one of disabled, preferred, required, verify_ca, verify_identity"
+            ))),
+        }
+    }
+}
+
+/// What a client with a `max_inflight` limit does with a statement issued
+/// while the limit is reached.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverloadPolicy {
+    /// Wait until another statement finishes.
+    #[default]
+    Wait,
+    /// Fail immediately with [`SeekDbError::Overloaded`](crate::SeekDbError::Overloaded).
+    Reject,
 }
 
 /// Supported vector distance metrics.
@@ -45,32 +296,279 @@ pub enum DistanceMetric {
     L2,
     Cosine,
     InnerProduct,
+    /// Number of differing components, for binary (0/1) embeddings.
+    Hamming,
+    /// `1 - |a ∩ b| / |a ∪ b|` over the set components, for binary embeddings.
+    Jaccard,
 }
 
 impl DistanceMetric {
+    /// Dot product; another name for [`DistanceMetric::InnerProduct`].
+    #[allow(non_upper_case_globals)]
+    pub const Dot: DistanceMetric = DistanceMetric::InnerProduct;
+
     pub fn as_str(&self) -> &'static str {
         match self {
             DistanceMetric::L2 => "L2",
             DistanceMetric::Cosine => "cosine",
             DistanceMetric::InnerProduct => "inner_product",
+            DistanceMetric::Hamming => "hamming",
+            DistanceMetric::Jaccard => "jaccard",
+        }
+    }
+
+    /// Whether larger values returned under this metric mean closer vectors.
+    ///
+    /// True for `InnerProduct` (the server reports the product itself);
+    /// false for the true distances `L2`, `Cosine`, `Hamming` and `Jaccard`.
+    pub fn higher_is_closer(&self) -> bool {
+        matches!(self, DistanceMetric::InnerProduct)
+    }
+
+    /// Convert a distance returned by a query under this metric into a
+    /// similarity score where larger means closer.
+    ///
+    /// - `Cosine`: `1 - d`, i.e. the cosine similarity.
+    /// - `L2`: `1 / (1 + d)`, mapping `[0, inf)` onto `(0, 1]`.
+    /// - `InnerProduct`: `d` unchanged; the server already reports the inner
+    ///   product itself, which grows with similarity.
+    /// - `Hamming`: `1 / (1 + d)`, like `L2`.
+    /// - `Jaccard`: `1 - d`, i.e. the Jaccard index.
+    pub fn similarity(&self, distance: f32) -> f32 {
+        match self {
+            DistanceMetric::L2 | DistanceMetric::Hamming => 1.0 / (1.0 + distance),
+            DistanceMetric::Cosine | DistanceMetric::Jaccard => 1.0 - distance,
+            DistanceMetric::InnerProduct => distance,
         }
     }
 }
 
 /// HNSW configuration used during collection creation.
+///
+/// The tuning fields are emitted into the vector index `WITH (...)` clause only
+/// when set; `None` leaves the server default in place.
 #[derive(Clone, Debug)]
 pub struct HnswConfig {
     pub dimension: u32,
     pub distance: DistanceMetric,
+    /// Maximum number of neighbours per graph node (`m`).
+    pub m: Option<u32>,
+    /// Candidate list size while building the graph (`ef_construction`).
+    pub ef_construction: Option<u32>,
+    /// Default candidate list size at query time (`ef_search`).
+    pub ef_search: Option<u32>,
+    /// Collation of the `document` column (e.g. `utf8mb4_unicode_ci`), which
+    /// governs string comparison for document filters and full-text matching;
+    /// `None` uses the table default.
+    pub document_collation: Option<String>,
+    /// Additional named vector columns, each with its own HNSW index, for
+    /// documents that carry more than one embedding (e.g. title and body).
+    pub vector_fields: Vec<VectorField>,
+}
+
+/// A named vector column stored next to the main `embedding` column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorField {
+    pub name: String,
+    pub dimension: u32,
+}
+
+impl VectorField {
+    pub fn new(name: impl Into<String>, dimension: u32) -> Self {
+        Self {
+            name: name.into(),
+            dimension,
+        }
+    }
+}
+
+impl HnswConfig {
+    /// Config with the given dimension and metric and server-default index tuning.
+    pub fn new(dimension: u32, distance: DistanceMetric) -> Self {
+        Self {
+            dimension,
+            distance,
+            m: None,
+            ef_construction: None,
+            ef_search: None,
+            document_collation: None,
+            vector_fields: Vec::new(),
+        }
+    }
+
+    pub fn with_m(mut self, m: u32) -> Self {
+        self.m = Some(m);
+        self
+    }
+
+    pub fn with_ef_construction(mut self, ef_construction: u32) -> Self {
+        self.ef_construction = Some(ef_construction);
+        self
+    }
+
+    pub fn with_ef_search(mut self, ef_search: u32) -> Self {
+        self.ef_search = Some(ef_search);
+        self
+    }
+
+    /// Collation name such as `utf8mb4_unicode_ci`; only `[A-Za-z0-9_]`
+    /// names of the form `charset_...` are accepted at creation time.
+    pub fn with_document_collation(mut self, collation: impl Into<String>) -> Self {
+        self.document_collation = Some(collation.into());
+        self
+    }
+
+    /// Add a named vector column of `dimension` dimensions, indexed with the
+    /// same metric and tuning as the main `embedding` column.
+    pub fn with_vector_field(mut self, name: impl Into<String>, dimension: u32) -> Self {
+        self.vector_fields.push(VectorField::new(name, dimension));
+        self
+    }
 }
 
 fn require_env(key: &str) -> Result<String> {
     env::var(key).map_err(|_| SeekDbError::Config(format!("missing env: {key}")))
 }
 
+/// Pool defaults, matching sqlx's own.
+pub(crate) const DEFAULT_IDLE_TIMEOUT: Option<Duration> = Some(Duration::from_secs(600));
+pub(crate) const DEFAULT_MAX_LIFETIME: Option<Duration> = Some(Duration::from_secs(1800));
+pub(crate) const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(100);
+
+fn timeout_from_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+fn parse_param<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| SeekDbError::Config(format!("invalid {key}: {value}")))
+}
+
+/// Everything except RFC 3986 unreserved characters, which is safe in any
+/// URL component.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn url_encode(s: &str) -> String {
+    utf8_percent_encode(s, URL_COMPONENT).to_string()
+}
+
+fn url_decode(s: &str) -> Result<String> {
+    percent_decode_str(s)
+        .decode_utf8()
+        .map(|v| v.into_owned())
+        .map_err(|e| SeekDbError::Config(format!("invalid url encoding: {e}")))
+}
+
 fn parse_env<T>(key: &str) -> Option<T>
 where
     T: std::str::FromStr,
 {
     env::var(key).ok().and_then(|v| v.parse::<T>().ok())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssl_mode_parses_names() {
+        assert_eq!("disabled".parse::<SslMode>().unwrap(), SslMode::Disabled);
+        assert_eq!("VERIFY_CA".parse::<SslMode>().unwrap(), SslMode::VerifyCa);
+        assert_eq!(
+            "verify-identity".parse::<SslMode>().unwrap(),
+            SslMode::VerifyIdentity
+        );
+        assert!("tls".parse::<SslMode>().is_err());
+        for mode in [
+            SslMode::Disabled,
+            SslMode::Preferred,
+            SslMode::Required,
+            SslMode::VerifyCa,
+            SslMode::VerifyIdentity,
+        ] {
+            assert_eq!(mode.as_str().parse::<SslMode>().unwrap(), mode);
+        }
+    }
+
+    #[test]
+    fn from_url_parses_tenant_and_params() {
+        let config = ServerConfig::from_url(
+            "mysql://root@mytenant:p%40ss@db.example:3306/app?max_connections=9&ssl_mode=required",
+        )
+        .unwrap();
+        assert_eq!(config.host, "db.example");
+        assert_eq!(config.port, 3306);
+        assert_eq!(config.user, "root");
+        assert_eq!(config.tenant, "mytenant");
+        assert_eq!(config.password, "p@ss");
+        assert_eq!(config.database, "app");
+        assert_eq!(config.max_connections, 9);
+        assert_eq!(config.ssl_mode, SslMode::Required);
+
+        let config = ServerConfig::from_url(
+            "mysql://root@h/test?min_connections=2&idle_timeout=600&max_lifetime=0",
+        )
+        .unwrap();
+        assert_eq!(config.min_connections, 2);
+        assert_eq!(config.idle_timeout, Some(Duration::from_secs(600)));
+        assert_eq!(config.max_lifetime, None);
+
+        let config =
+            ServerConfig::from_url("mysql://root@h/test?max_retries=4&base_backoff_ms=250")
+                .unwrap();
+        assert_eq!(config.max_retries, 4);
+        assert_eq!(config.base_backoff, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn from_url_defaults_and_errors() {
+        let config = ServerConfig::from_url("mysql://root@127.0.0.1/test").unwrap();
+        assert_eq!(config.tenant, "sys");
+        assert_eq!(config.port, 2881);
+        assert_eq!(config.password, "");
+        assert_eq!(config.max_connections, 5);
+        assert_eq!(config.min_connections, 0);
+        assert_eq!(config.idle_timeout, DEFAULT_IDLE_TIMEOUT);
+
+        assert!(ServerConfig::from_url("postgres://root@h/test").is_err());
+        assert!(ServerConfig::from_url("mysql://root@h").is_err());
+        assert!(ServerConfig::from_url("mysql://root@h/test?pool=3").is_err());
+        assert!(ServerConfig::from_url("mysql://root@h/test?max_connections=x").is_err());
+    }
+
+    #[test]
+    fn to_url_encodes_credentials_and_round_trips() {
+        let mut config = ServerConfig::from_url("mysql://root@127.0.0.1/test").unwrap();
+        assert_eq!(config.to_url(), "mysql://root@sys@127.0.0.1:2881/test");
+
+        config.user = "app@corp:x".into();
+        config.tenant = "t_1".into();
+        config.password = "p@ss:w/rd?#%& 1".into();
+        config.database = "my db".into();
+        config.max_connections = 9;
+        config.max_lifetime = None;
+        config.ssl_mode = SslMode::VerifyCa;
+        config.ca_cert_path = Some(PathBuf::from("/etc/ssl/ca cert.pem"));
+        let url = config.to_url();
+        assert!(url.starts_with(
+            "mysql://app%40corp%3Ax@t_1:p%40ss%3Aw%2Frd%3F%23%25%26%201@127.0.0.1:2881/my%20db?"
+        ));
+
+        let parsed = ServerConfig::from_url(&url).unwrap();
+        assert_eq!(parsed.user, config.user);
+        assert_eq!(parsed.tenant, config.tenant);
+        assert_eq!(parsed.password, config.password);
+        assert_eq!(parsed.database, config.database);
+        assert_eq!(parsed.host, config.host);
+        assert_eq!(parsed.max_connections, 9);
+        assert_eq!(parsed.max_lifetime, None);
+        assert_eq!(parsed.idle_timeout, DEFAULT_IDLE_TIMEOUT);
+        assert_eq!(parsed.ssl_mode, SslMode::VerifyCa);
+        assert_eq!(parsed.ca_cert_path, config.ca_cert_path);
+    }
+}
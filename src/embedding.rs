@@ -1,14 +1,42 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
-use crate::error::Result;
-#[cfg(feature = "embedding")]
-use crate::error::SeekDbError;
-use crate::types::Embeddings;
+use crate::error::{Result, SeekDbError};
+use crate::types::{Embedding, Embeddings};
 
 /// Embedding generation abstraction to allow custom models.
 #[async_trait]
 pub trait EmbeddingFunction: Send + Sync {
     async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings>;
+
+    /// Embed a search query (used by `query_texts` and text-based hybrid
+    /// search). Defaults to [`embed_documents`](Self::embed_documents) on the
+    /// single text; asymmetric models override it to apply their query
+    /// prompt or prefix.
+    async fn embed_query(&self, text: &str) -> Result<Embedding> {
+        let mut embeddings = self.embed_documents(&[text.to_string()]).await?;
+        if embeddings.len() != 1 {
+            return Err(SeekDbError::Embedding(format!(
+                "expected 1 embedding for query text, got {}",
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings.remove(0))
+    }
+
+    /// Embed several search queries in one call (used by `query_texts`).
+    /// Defaults to [`embed_query`](Self::embed_query) on each text in turn, so
+    /// a query prompt set there applies here too; models that can embed a
+    /// batch of queries in one request override this.
+    async fn embed_queries(&self, texts: &[String]) -> Result<Embeddings> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(self.embed_query(text).await?);
+        }
+        Ok(embeddings)
+    }
+
     fn dimension(&self) -> usize;
 }
 
@@ -20,11 +48,154 @@ impl EmbeddingFunction for Box<dyn EmbeddingFunction> {
         (**self).embed_documents(docs).await
     }
 
+    async fn embed_query(&self, text: &str) -> Result<Embedding> {
+        (**self).embed_query(text).await
+    }
+
+    async fn embed_queries(&self, texts: &[String]) -> Result<Embeddings> {
+        (**self).embed_queries(texts).await
+    }
+
     fn dimension(&self) -> usize {
         (**self).dimension()
     }
 }
 
+/// Constructor stored in an [`EmbeddingRegistry`].
+pub type EmbeddingFactory = Box<dyn Fn() -> Result<Box<dyn EmbeddingFunction>> + Send + Sync>;
+
+/// Maps names used in configuration (`"default"`, `"openai-small"`, ...) to
+/// embedding function constructors, so collections can be wired up without
+/// naming concrete types.
+///
+/// [`EmbeddingRegistry::new`] pre-registers `"default"` for
+/// [`DefaultEmbedding`] when the `embedding` feature is enabled.
+pub struct EmbeddingRegistry {
+    factories: HashMap<String, EmbeddingFactory>,
+}
+
+impl Default for EmbeddingRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmbeddingRegistry {
+    /// Registry with the built-in registrations.
+    pub fn new() -> Self {
+        #[cfg_attr(not(feature = "embedding"), allow(unused_mut))]
+        let mut registry = Self::empty();
+        #[cfg(feature = "embedding")]
+        registry.register("default", || {
+            Ok(Box::new(DefaultEmbedding::new()?) as Box<dyn EmbeddingFunction>)
+        });
+        registry
+    }
+
+    /// Registry without any registrations.
+    pub fn empty() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Register `factory` under `name`, replacing any previous registration.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn() -> Result<Box<dyn EmbeddingFunction>> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.into(), Box::new(factory));
+        self
+    }
+
+    /// Construct the embedding function registered under `name`.
+    pub fn get(&self, name: &str) -> Result<Box<dyn EmbeddingFunction>> {
+        let factory = self.factories.get(name).ok_or_else(|| {
+            SeekDbError::Config(format!(
+                "unknown embedding function '{name}'; registered: [{}]",
+                self.names().join(", ")
+            ))
+        })?;
+        factory()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Registered names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.factories.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+/// ONNX Runtime execution provider used by [`DefaultEmbedding`].
+///
+/// `Cuda` and `CoreMl` need the crate's `cuda` / `coreml` features; when a
+/// provider is not compiled in or not usable on this machine the model runs
+/// on the CPU instead.
+#[cfg(feature = "embedding")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutionProvider {
+    #[default]
+    Cpu,
+    Cuda,
+    CoreMl,
+}
+
+#[cfg(feature = "embedding")]
+impl ExecutionProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionProvider::Cpu => "cpu",
+            ExecutionProvider::Cuda => "cuda",
+            ExecutionProvider::CoreMl => "coreml",
+        }
+    }
+}
+
+#[cfg(feature = "embedding")]
+impl std::str::FromStr for ExecutionProvider {
+    type Err = SeekDbError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" => Ok(ExecutionProvider::Cpu),
+            "cuda" => Ok(ExecutionProvider::Cuda),
+            "coreml" => Ok(ExecutionProvider::CoreMl),
+            other => Err(SeekDbError::Config(format!(
+                "unknown execution provider '{other}' (expected cpu, cuda or coreml)"
+            ))),
+        }
+    }
+}
+
+/// Options for [`DefaultEmbedding::from_options`].
+#[cfg(feature = "embedding")]
+#[derive(Debug, Clone, Default)]
+pub struct DefaultEmbeddingOptions {
+    pub execution_provider: ExecutionProvider,
+}
+
+#[cfg(feature = "embedding")]
+impl DefaultEmbeddingOptions {
+    /// Read `SEEKDB_ONNX_EP` (`cpu`, `cuda` or `coreml`; default `cpu`).
+    pub fn from_env() -> Result<Self> {
+        let execution_provider = match std::env::var("SEEKDB_ONNX_EP") {
+            Ok(v) if !v.trim().is_empty() => v.parse()?,
+            _ => ExecutionProvider::Cpu,
+        };
+        Ok(Self { execution_provider })
+    }
+
+    pub fn with_execution_provider(mut self, execution_provider: ExecutionProvider) -> Self {
+        self.execution_provider = execution_provider;
+        self
+    }
+}
+
 /// Default ONNX-based embedding implementation (all-MiniLM-L6-v2).
 /// Compiled only when the `embedding` feature is enabled.
 #[cfg(feature = "embedding")]
@@ -32,11 +203,22 @@ pub struct DefaultEmbedding {
     tokenizer: tokenizers::Tokenizer,
     session: std::sync::Arc<std::sync::Mutex<ort::session::Session>>,
     max_length: usize,
+    normalize: bool,
+    batch_size: usize,
+    execution_provider: ExecutionProvider,
 }
 
 #[cfg(feature = "embedding")]
 impl DefaultEmbedding {
+    /// Load the model with options from the environment (see
+    /// [`DefaultEmbeddingOptions::from_env`]). Output vectors are
+    /// L2-normalized; see [`with_normalize`](Self::with_normalize).
     pub fn new() -> Result<Self> {
+        Self::from_options(DefaultEmbeddingOptions::from_env()?)
+    }
+
+    /// Load the model with explicit options.
+    pub fn from_options(options: DefaultEmbeddingOptions) -> Result<Self> {
         let (model_path, tokenizer_path) = resolve_model_paths()?;
 
         let mut tokenizer = tokenizers::Tokenizer::from_file(&tokenizer_path)
@@ -64,21 +246,88 @@ impl DefaultEmbedding {
         })?;
         let session = session
             .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level1)
-            .map_err(|e| SeekDbError::Embedding(format!("failed to set optimization level: {e}")))?
-            .commit_from_file(&model_path)
             .map_err(|e| {
-                SeekDbError::Embedding(format!(
-                    "failed to load onnx model from {}: {e}",
-                    model_path.display()
-                ))
+                SeekDbError::Embedding(format!("failed to set optimization level: {e}"))
             })?;
+        let (mut session, execution_provider) =
+            register_execution_provider(session, options.execution_provider);
+        let session = session.commit_from_file(&model_path).map_err(|e| {
+            SeekDbError::Embedding(format!(
+                "failed to load onnx model from {}: {e}",
+                model_path.display()
+            ))
+        })?;
 
         Ok(Self {
             tokenizer,
             session: std::sync::Arc::new(std::sync::Mutex::new(session)),
             max_length: DEFAULT_MAX_LENGTH,
+            normalize: true,
+            batch_size: DEFAULT_BATCH_SIZE,
+            execution_provider,
         })
     }
+
+    /// Whether to L2-normalize each pooled vector (default `true`), as
+    /// sentence-transformers does for this model. Unit vectors make cosine
+    /// and inner-product distances agree; disable only to get raw mean-pooled
+    /// outputs.
+    pub fn with_normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Maximum number of documents per ONNX run (default 32, at least 1).
+    /// Inputs are padded to the max sequence length, so tensor memory grows
+    /// linearly with this value.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The provider the session actually runs on; `Cpu` if the requested one
+    /// was unavailable.
+    pub fn execution_provider(&self) -> ExecutionProvider {
+        self.execution_provider
+    }
+}
+
+/// Register `requested` on the session builder when it is compiled in and
+/// available at runtime, otherwise leave the builder on the CPU. Returns the
+/// provider in effect.
+#[cfg(feature = "embedding")]
+fn register_execution_provider(
+    builder: ort::session::builder::SessionBuilder,
+    requested: ExecutionProvider,
+) -> (ort::session::builder::SessionBuilder, ExecutionProvider) {
+    #[cfg(any(feature = "cuda", feature = "coreml"))]
+    fn register(
+        builder: ort::session::builder::SessionBuilder,
+        provider: impl ort::ep::ExecutionProvider + Into<ort::ep::ExecutionProviderDispatch>,
+        kind: ExecutionProvider,
+    ) -> (ort::session::builder::SessionBuilder, ExecutionProvider) {
+        if !provider.is_available().unwrap_or(false) {
+            return (builder, ExecutionProvider::Cpu);
+        }
+        let dispatch: ort::ep::ExecutionProviderDispatch = provider.into();
+        let dispatch = dispatch.error_on_failure();
+        match builder.with_execution_providers([dispatch]) {
+            Ok(builder) => (builder, kind),
+            // Registration failed (e.g. missing driver libraries): the
+            // recovered builder still targets the CPU.
+            Err(e) => (e.recover(), ExecutionProvider::Cpu),
+        }
+    }
+
+    match requested {
+        ExecutionProvider::Cpu => (builder, ExecutionProvider::Cpu),
+        #[cfg(feature = "cuda")]
+        ExecutionProvider::Cuda => register(builder, ort::ep::CUDA::default(), requested),
+        #[cfg(feature = "coreml")]
+        ExecutionProvider::CoreMl => register(builder, ort::ep::CoreML::default(), requested),
+        #[allow(unreachable_patterns)]
+        _ => (builder, ExecutionProvider::Cpu),
+    }
 }
 
 #[cfg(feature = "embedding")]
@@ -89,7 +338,18 @@ impl EmbeddingFunction for DefaultEmbedding {
             return Ok(Vec::new());
         }
 
-        run_inference(&self.session, &self.tokenizer, docs, self.max_length)
+        let mut embeddings = embed_in_chunks(docs, self.batch_size, |chunk| {
+            run_inference(&self.session, &self.tokenizer, chunk, self.max_length)
+        })?;
+        if self.normalize {
+            embeddings.iter_mut().for_each(|v| l2_normalize(v));
+        }
+        Ok(embeddings)
+    }
+
+    /// The model is symmetric, so queries are batched like documents.
+    async fn embed_queries(&self, texts: &[String]) -> Result<Embeddings> {
+        self.embed_documents(texts).await
     }
 
     fn dimension(&self) -> usize {
@@ -97,11 +357,28 @@ impl EmbeddingFunction for DefaultEmbedding {
     }
 }
 
+/// Runs `embed` over `docs` in chunks of at most `batch_size`, concatenating
+/// the outputs so they stay aligned with the input order.
+#[cfg(feature = "embedding")]
+fn embed_in_chunks(
+    docs: &[String],
+    batch_size: usize,
+    mut embed: impl FnMut(&[String]) -> Result<Embeddings>,
+) -> Result<Embeddings> {
+    let mut embeddings = Vec::with_capacity(docs.len());
+    for chunk in docs.chunks(batch_size.max(1)) {
+        embeddings.extend(embed(chunk)?);
+    }
+    Ok(embeddings)
+}
+
 #[cfg(feature = "embedding")]
 const HF_MODEL_ID: &str = "sentence-transformers/all-MiniLM-L6-v2";
 #[cfg(feature = "embedding")]
 const DEFAULT_MAX_LENGTH: usize = 512;
 #[cfg(feature = "embedding")]
+const DEFAULT_BATCH_SIZE: usize = 32;
+#[cfg(feature = "embedding")]
 const EMBEDDING_DIM: usize = 384;
 
 #[cfg(feature = "embedding")]
@@ -226,7 +503,7 @@ fn run_inference(
         }
         input_ids.extend(enc.get_ids().iter().map(|id| *id as i64));
         attention_mask.extend(enc.get_attention_mask().iter().map(|m| *m as i64));
-        token_type_ids.extend(std::iter::repeat(0_i64).take(seq_len));
+        token_type_ids.extend(std::iter::repeat_n(0_i64, seq_len));
     }
 
     let shape: Vec<i64> = vec![batch as i64, seq_len as i64];
@@ -280,6 +557,14 @@ fn run_inference(
     mean_pool(out_data, &attention_mask, batch, seq_len, hidden)
 }
 
+/// Scale `v` to unit length; all-zero vectors are left unchanged.
+pub(crate) fn l2_normalize(v: &mut [f32]) {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
 #[cfg(feature = "embedding")]
 fn mean_pool(
     data: &[f32],
@@ -324,10 +609,403 @@ fn mean_pool(
     Ok(outputs)
 }
 
-#[cfg(all(test, feature = "embedding"))]
+/// 64-bit FNV-1a hash of `text`.
+#[cfg(feature = "testing")]
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Wraps an embedding function with an in-process LRU cache of document
+/// embeddings, so re-ingesting known texts skips inference.
+///
+/// Entries are keyed by the full text, so distinct texts never share an
+/// embedding; at most `capacity` are kept, evicting the least recently used.
+/// Only `embed_documents` is cached: `embed_query` and `embed_queries` go
+/// straight to the inner function, which may embed queries differently.
+pub struct CachingEmbedding<E> {
+    inner: E,
+    cache: std::sync::Mutex<LruCache>,
+}
+
+impl<E: EmbeddingFunction> CachingEmbedding<E> {
+    /// Cache up to `capacity` embeddings (at least 1).
+    pub fn new(inner: E, capacity: usize) -> Self {
+        Self {
+            inner,
+            cache: std::sync::Mutex::new(LruCache::new(capacity.max(1))),
+        }
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Number of embeddings currently cached.
+    pub fn cached(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache> {
+        // The cache holds no invariants a panicking holder could break.
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl<E: EmbeddingFunction> EmbeddingFunction for CachingEmbedding<E> {
+    async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+        let mut out: Vec<Option<Embedding>> = {
+            let mut cache = self.lock();
+            docs.iter().map(|d| cache.get(d)).collect()
+        };
+
+        // Embed each distinct missing text once.
+        let mut miss_docs = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (doc, hit) in docs.iter().zip(&out) {
+            if hit.is_none() && seen.insert(doc.as_str()) {
+                miss_docs.push(doc.clone());
+            }
+        }
+        if miss_docs.is_empty() {
+            return Ok(out.into_iter().flatten().collect());
+        }
+        let embedded = self.inner.embed_documents(&miss_docs).await?;
+        if embedded.len() != miss_docs.len() {
+            return Err(SeekDbError::Embedding(format!(
+                "expected {} embeddings, got {}",
+                miss_docs.len(),
+                embedded.len()
+            )));
+        }
+
+        let fresh: HashMap<String, Embedding> = miss_docs.into_iter().zip(embedded).collect();
+        for (doc, slot) in docs.iter().zip(out.iter_mut()) {
+            if slot.is_none() {
+                *slot = fresh.get(doc).cloned();
+            }
+        }
+        let mut cache = self.lock();
+        for (doc, embedding) in fresh {
+            cache.insert(doc, embedding);
+        }
+        Ok(out.into_iter().flatten().collect())
+    }
+
+    async fn embed_query(&self, text: &str) -> Result<Embedding> {
+        self.inner.embed_query(text).await
+    }
+
+    async fn embed_queries(&self, texts: &[String]) -> Result<Embeddings> {
+        self.inner.embed_queries(texts).await
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+}
+
+/// Bounded map from text to embedding with least-recently-used eviction.
+/// `order` indexes entries by the tick of their last use. Kept in-crate
+/// rather than pulling in an LRU dependency for this one cache.
+struct LruCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (Embedding, u64)>,
+    order: std::collections::BTreeMap<u64, String>,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: std::collections::BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Embedding> {
+        self.tick += 1;
+        let (embedding, used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(used)?;
+        *used = self.tick;
+        self.order.insert(self.tick, key);
+        Some(embedding.clone())
+    }
+
+    fn insert(&mut self, key: String, embedding: Embedding) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.clone(), (embedding, self.tick)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// Dependency-free embedding function for tests and benchmarks. Compiled only
+/// when the `testing` feature is enabled.
+///
+/// Each text maps to a deterministic pseudo-random unit vector of `dim`
+/// components, seeded from a stable hash of the text: identical inputs always
+/// give identical vectors (across runs and platforms), different inputs give
+/// unrelated ones.
+#[cfg(feature = "testing")]
+#[derive(Clone, Copy, Debug)]
+pub struct IdentityEmbedding {
+    pub dim: usize,
+}
+
+#[cfg(feature = "testing")]
+impl IdentityEmbedding {
+    pub fn new(dim: usize) -> Self {
+        Self { dim }
+    }
+
+    /// The vector for a single text.
+    pub fn embed_one(&self, text: &str) -> Embedding {
+        // FNV-1a for the seed, splitmix64 for the stream: both are fixed
+        // algorithms, unlike `DefaultHasher`, so vectors are reproducible.
+        let mut state = fnv1a(text);
+        let mut vector: Vec<f32> = (0..self.dim)
+            .map(|_| {
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^= z >> 31;
+                // Top 24 bits as a uniform value in [-1, 1).
+                (z >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+            })
+            .collect();
+        l2_normalize(&mut vector);
+        vector
+    }
+}
+
+#[cfg(feature = "testing")]
+#[async_trait]
+impl EmbeddingFunction for IdentityEmbedding {
+    async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+        Ok(docs.iter().map(|d| self.embed_one(d)).collect())
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+}
+
+/// Embedding function backed by an OpenAI-compatible `/embeddings` endpoint
+/// (OpenAI itself, vLLM, TEI, ...). Compiled only when the `openai` feature is
+/// enabled.
+///
+/// `base_url` is the API root including the version segment, e.g.
+/// `https://api.openai.com/v1` or `http://localhost:8000/v1`; requests go to
+/// `{base_url}/embeddings`. Documents are sent in batches of `batch_size`.
+#[cfg(feature = "openai")]
+pub struct OpenAiEmbedding {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    dimension: usize,
+    batch_size: usize,
+}
+
+#[cfg(feature = "openai")]
+impl OpenAiEmbedding {
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.openai.com/v1";
+
+    /// Client for `model`, whose vectors have `dimension` components.
+    /// The API key defaults to the `OPENAI_API_KEY` environment variable.
+    pub fn new(model: impl Into<String>, dimension: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: Self::DEFAULT_BASE_URL.to_string(),
+            model: model.into(),
+            api_key: std::env::var("OPENAI_API_KEY").ok(),
+            dimension,
+            batch_size: 128,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Maximum number of documents per request (default 128, at least 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}/embeddings", self.base_url.trim_end_matches('/'))
+    }
+
+    async fn embed_batch(&self, docs: &[String]) -> Result<Embeddings> {
+        let mut request = self.client.post(self.endpoint()).json(&serde_json::json!({
+            "model": self.model,
+            "input": docs,
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SeekDbError::Embedding(format!("embedding request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SeekDbError::Embedding(format!(
+                "embedding endpoint returned {status}: {body}"
+            )));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| SeekDbError::Embedding(format!("invalid embedding response: {e}")))?;
+        parse_openai_embeddings(&body, docs.len(), self.dimension)
+    }
+}
+
+#[cfg(feature = "openai")]
+#[async_trait]
+impl EmbeddingFunction for OpenAiEmbedding {
+    async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+        let mut out = Vec::with_capacity(docs.len());
+        for chunk in docs.chunks(self.batch_size) {
+            out.extend(self.embed_batch(chunk).await?);
+        }
+        Ok(out)
+    }
+
+    /// Queries are sent like documents, in batched requests.
+    async fn embed_queries(&self, texts: &[String]) -> Result<Embeddings> {
+        self.embed_documents(texts).await
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+/// Extract the vectors from an `/embeddings` response body, ordered by their
+/// `index`, checking the count and every vector's dimension.
+#[cfg(feature = "openai")]
+fn parse_openai_embeddings(
+    body: &serde_json::Value,
+    expected: usize,
+    dimension: usize,
+) -> Result<Embeddings> {
+    let invalid =
+        |what: &str| SeekDbError::Embedding(format!("invalid embedding response: {what}"));
+    let data = body
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| invalid("missing `data` array"))?;
+    let mut indexed = Vec::with_capacity(data.len());
+    for (pos, item) in data.iter().enumerate() {
+        let index = item
+            .get("index")
+            .and_then(|i| i.as_u64())
+            .map_or(pos, |i| i as usize);
+        let values = item
+            .get("embedding")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| invalid("missing `embedding` array"))?;
+        let vector = values
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or_else(|| invalid("non-numeric embedding value"))?;
+        if vector.len() != dimension {
+            return Err(SeekDbError::Embedding(format!(
+                "embedding dimension mismatch: expected {dimension}, got {}",
+                vector.len()
+            )));
+        }
+        indexed.push((index, vector));
+    }
+    if indexed.len() != expected {
+        return Err(SeekDbError::Embedding(format!(
+            "expected {expected} embeddings, got {}",
+            indexed.len()
+        )));
+    }
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok(indexed.into_iter().map(|(_, v)| v).collect())
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    struct Fixed(usize);
+
+    #[async_trait]
+    impl EmbeddingFunction for Fixed {
+        async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+            Ok(vec![vec![0.0; self.0]; docs.len()])
+        }
+
+        fn dimension(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn registry_resolves_registered_names() {
+        let mut registry = EmbeddingRegistry::empty();
+        registry
+            .register("small", || {
+                Ok(Box::new(Fixed(4)) as Box<dyn EmbeddingFunction>)
+            })
+            .register("large", || {
+                Ok(Box::new(Fixed(8)) as Box<dyn EmbeddingFunction>)
+            });
+
+        assert_eq!(registry.names(), vec!["large", "small"]);
+        assert_eq!(registry.get("small").unwrap().dimension(), 4);
+        assert_eq!(registry.get("large").unwrap().dimension(), 8);
+
+        match registry.get("missing") {
+            Err(SeekDbError::Config(msg)) => {
+                assert!(
+                    msg.contains("'missing'") && msg.contains("large, small"),
+                    "{msg}"
+                );
+            }
+            Err(e) => panic!("expected config error, got {e:?}"),
+            Ok(_) => panic!("expected config error, got Ok"),
+        }
+    }
+
+    #[test]
+    fn registry_builtins_follow_features() {
+        assert_eq!(
+            EmbeddingRegistry::new().contains("default"),
+            cfg!(feature = "embedding")
+        );
+        assert!(EmbeddingRegistry::empty().names().is_empty());
+    }
+
+    #[cfg(feature = "embedding")]
     #[test]
     fn test_mean_pool_basic() {
         let data = vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0];
@@ -337,6 +1015,7 @@ mod tests {
         assert_eq!(pooled[0], vec![2.5, 4.0, 5.5]);
     }
 
+    #[cfg(feature = "embedding")]
     #[test]
     fn test_mean_pool_ignores_masked() {
         let data = vec![1.0, 1.0, 1.0, 5.0, 5.0, 5.0];
@@ -345,7 +1024,100 @@ mod tests {
         assert_eq!(pooled[0], vec![1.0, 1.0, 1.0]);
     }
 
+    #[test]
+    fn test_l2_normalize() {
+        let mut v = vec![3.0, 4.0];
+        l2_normalize(&mut v);
+        assert_eq!(v, vec![0.6, 0.8]);
+
+        let mut v = vec![2.5, 4.0, 5.5];
+        l2_normalize(&mut v);
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+
+        let mut zeros = vec![0.0; 3];
+        l2_normalize(&mut zeros);
+        assert_eq!(zeros, vec![0.0; 3]);
+    }
+
+    #[cfg(feature = "embedding")]
+    #[test]
+    fn test_embed_in_chunks_keeps_input_order() {
+        let docs: Vec<String> = (0..7).map(|i| format!("doc{i}")).collect();
+        let mut chunk_sizes = Vec::new();
+        let embeddings = embed_in_chunks(&docs, 3, |chunk| {
+            chunk_sizes.push(chunk.len());
+            Ok(chunk
+                .iter()
+                .map(|d| vec![d[3..].parse::<f32>().unwrap()])
+                .collect())
+        })
+        .unwrap();
+        assert_eq!(chunk_sizes, vec![3, 3, 1]);
+        let expected: Embeddings = (0..7).map(|i| vec![i as f32]).collect();
+        assert_eq!(embeddings, expected);
+    }
+
+    /// Embeds each text as `[len]` and counts how many texts it was asked for.
+    struct Counting(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl EmbeddingFunction for Counting {
+        async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+            self.0
+                .fetch_add(docs.len(), std::sync::atomic::Ordering::SeqCst);
+            Ok(docs.iter().map(|d| vec![d.len() as f32]).collect())
+        }
+
+        fn dimension(&self) -> usize {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caching_embedding_skips_known_texts() {
+        let ef = CachingEmbedding::new(Counting(Default::default()), 2);
+        let calls = |ef: &CachingEmbedding<Counting>| {
+            ef.inner().0.load(std::sync::atomic::Ordering::SeqCst)
+        };
+        let docs = |texts: &[&str]| texts.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        let out = ef.embed_documents(&docs(&["a", "bb", "a"])).await.unwrap();
+        assert_eq!(out, vec![vec![1.0], vec![2.0], vec![1.0]]);
+        assert_eq!(calls(&ef), 2);
+
+        let out = ef.embed_documents(&docs(&["bb", "a"])).await.unwrap();
+        assert_eq!(out, vec![vec![2.0], vec![1.0]]);
+        assert_eq!(calls(&ef), 2);
+
+        // "bb" is now least recently used and gets evicted by "ccc".
+        ef.embed_documents(&docs(&["ccc"])).await.unwrap();
+        assert_eq!(ef.cached(), 2);
+        ef.embed_documents(&docs(&["a", "bb"])).await.unwrap();
+        assert_eq!(calls(&ef), 4);
+        assert_eq!(ef.dimension(), 1);
+    }
+
+    #[cfg(feature = "embedding")]
+    #[test]
+    fn test_execution_provider_from_str() {
+        assert_eq!(
+            "CUDA".parse::<ExecutionProvider>().unwrap(),
+            ExecutionProvider::Cuda
+        );
+        assert_eq!(
+            " coreml ".parse::<ExecutionProvider>().unwrap(),
+            ExecutionProvider::CoreMl
+        );
+        assert_eq!("cpu".parse::<ExecutionProvider>().unwrap().as_str(), "cpu");
+        assert!(matches!(
+            "tpu".parse::<ExecutionProvider>(),
+            Err(SeekDbError::Config(_))
+        ));
+    }
+
     /// Basic smoke test for DefaultEmbedding end-to-end ONNX inference.
+    #[cfg(feature = "embedding")]
     #[test]
     fn default_embedding_infers_shape() {
         let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
@@ -361,4 +1133,79 @@ mod tests {
             assert_eq!(embs[1].len(), EMBEDDING_DIM);
         });
     }
+
+    #[cfg(feature = "openai")]
+    #[test]
+    fn openai_response_is_ordered_and_checked() {
+        let body = serde_json::json!({
+            "data": [
+                {"index": 1, "embedding": [0.0, 1.0]},
+                {"index": 0, "embedding": [1.0, 0.0]},
+            ]
+        });
+        let embs = parse_openai_embeddings(&body, 2, 2).unwrap();
+        assert_eq!(embs, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        assert!(parse_openai_embeddings(&body, 2, 3).is_err());
+        assert!(parse_openai_embeddings(&body, 3, 2).is_err());
+        assert!(parse_openai_embeddings(&serde_json::json!({"error": "x"}), 1, 2).is_err());
+    }
+
+    struct Prefixed;
+
+    #[async_trait]
+    impl EmbeddingFunction for Prefixed {
+        async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings> {
+            Ok(docs.iter().map(|d| vec![d.len() as f32]).collect())
+        }
+
+        async fn embed_query(&self, text: &str) -> Result<Embedding> {
+            Ok(vec![format!("query: {text}").len() as f32])
+        }
+
+        fn dimension(&self) -> usize {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn embed_query_defaults_and_forwards() {
+        assert_eq!(Fixed(3).embed_query("hi").await.unwrap(), vec![0.0; 3]);
+        let boxed: Box<dyn EmbeddingFunction> = Box::new(Prefixed);
+        assert_eq!(boxed.embed_query("ab").await.unwrap(), vec![9.0]);
+        assert_eq!(
+            boxed
+                .embed_queries(&["ab".into(), "c".into()])
+                .await
+                .unwrap(),
+            vec![vec![9.0], vec![8.0]]
+        );
+        assert_eq!(
+            Fixed(2)
+                .embed_queries(&["a".into(), "b".into()])
+                .await
+                .unwrap(),
+            vec![vec![0.0; 2]; 2]
+        );
+        assert_eq!(
+            boxed.embed_documents(&["ab".into()]).await.unwrap(),
+            vec![vec![2.0]]
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn identity_embedding_is_deterministic() {
+        let ef = IdentityEmbedding::new(16);
+        let docs = vec!["alpha".to_string(), "beta".to_string(), "alpha".to_string()];
+        let embs = ef.embed_documents(&docs).await.unwrap();
+        assert_eq!(embs[0], embs[2]);
+        assert_ne!(embs[0], embs[1]);
+        assert_eq!(embs[0], IdentityEmbedding::new(16).embed_one("alpha"));
+        for emb in &embs {
+            assert_eq!(emb.len(), 16);
+            let norm = emb.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5);
+        }
+    }
 }
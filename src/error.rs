@@ -8,8 +8,23 @@ pub type Result<T> = std::result::Result<T, SeekDbError>;
 pub enum SeekDbError {
     #[error("connection error: {0}")]
     Connection(String),
-    #[error("sql error: {0}")]
-    Sql(String),
+    /// Client-side SQL failures that did not come from the server: protocol,
+    /// decoding or driver errors. `statement` is the failing SQL, filled only
+    /// with `ServerClient::with_verbose_errors`.
+    #[error("sql error: {message}{}", statement_suffix(.statement))]
+    Sql {
+        message: String,
+        statement: Option<String>,
+    },
+    /// An error reported by the server, with its MySQL error number when
+    /// known (e.g. 1062 for a duplicate key, 1146 for a missing table) and,
+    /// with `ServerClient::with_verbose_errors`, the statement it rejected.
+    #[error("database error{}: {message}{}", code_suffix(.code), statement_suffix(.statement))]
+    Database {
+        code: Option<u16>,
+        message: String,
+        statement: Option<String>,
+    },
     #[error("not found: {0}")]
     NotFound(String),
     #[error("config error: {0}")]
@@ -18,17 +33,216 @@ pub enum SeekDbError {
     Embedding(String),
     #[error("invalid input: {0}")]
     InvalidInput(String),
+    /// A multi-statement write failed after `written` rows had already been stored.
+    #[error("write failed after {written} rows were stored: {source}")]
+    PartialWrite {
+        written: usize,
+        #[source]
+        source: Box<SeekDbError>,
+    },
+    /// A client configured with `max_inflight` and [`OverloadPolicy::Reject`]
+    /// already had `max_inflight` statements running.
+    ///
+    /// [`OverloadPolicy::Reject`]: crate::OverloadPolicy::Reject
+    #[error("overloaded: {max_inflight} statements already in flight")]
+    Overloaded { max_inflight: usize },
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+impl SeekDbError {
+    /// The server's MySQL error number, looking through
+    /// [`SeekDbError::PartialWrite`] to its cause.
+    pub fn database_code(&self) -> Option<u16> {
+        match self {
+            SeekDbError::Database { code, .. } => *code,
+            SeekDbError::PartialWrite { source, .. } => source.database_code(),
+            _ => None,
+        }
+    }
+
+    /// The SQL statement that failed, looking through
+    /// [`SeekDbError::PartialWrite`]; only recorded with verbose errors.
+    pub fn statement(&self) -> Option<&str> {
+        match self {
+            SeekDbError::Sql { statement, .. } | SeekDbError::Database { statement, .. } => {
+                statement.as_deref()
+            }
+            SeekDbError::PartialWrite { source, .. } => source.statement(),
+            _ => None,
+        }
+    }
+
+    /// Attach `sql` (redacted, see [`redact_statement`]) to a `Sql` or
+    /// `Database` error; other errors are returned unchanged.
+    pub(crate) fn with_statement(mut self, sql: &str) -> Self {
+        if let SeekDbError::Sql { statement, .. } | SeekDbError::Database { statement, .. } =
+            &mut self
+        {
+            *statement = Some(redact_statement(sql));
+        }
+        self
+    }
+}
+
+fn code_suffix(code: &Option<u16>) -> String {
+    code.map(|c| format!(" {c}")).unwrap_or_default()
+}
+
+fn statement_suffix(statement: &Option<String>) -> String {
+    statement
+        .as_deref()
+        .map(|s| format!(" (statement: {s})"))
+        .unwrap_or_default()
+}
+
+/// Longest statement text kept in an error, in characters.
+const MAX_STATEMENT_IN_ERROR: usize = 1024;
+
+/// Statement text safe to put in an error: values are normally bound as `?`
+/// already, and any quoted string literal that was interpolated (comments,
+/// JSON search parameters) is replaced with `'?'`. Long statements, such as
+/// multi-row INSERTs, are cut to [`MAX_STATEMENT_IN_ERROR`] characters.
+fn redact_statement(sql: &str) -> String {
+    let mut out = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(q) if c == q => {
+                // A doubled quote is an escaped quote inside the literal.
+                if chars.peek() == Some(&q) {
+                    chars.next();
+                } else {
+                    quote = None;
+                    out.push(q);
+                }
+            }
+            Some(_) => {}
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                out.push(c);
+                out.push('?');
+            }
+            None => out.push(c),
+        }
+    }
+    if let Some(q) = quote {
+        out.push(q);
+    }
+    match out.char_indices().nth(MAX_STATEMENT_IN_ERROR) {
+        Some((cut, _)) => format!("{}...", &out[..cut]),
+        None => out,
+    }
+}
+
 impl From<sqlx::Error> for SeekDbError {
     fn from(value: sqlx::Error) -> Self {
         match value {
             sqlx::Error::RowNotFound => SeekDbError::NotFound("row not found".into()),
-            _ => SeekDbError::Sql(value.to_string()),
+            sqlx::Error::Database(db_err) => SeekDbError::Database {
+                code: db_err
+                    .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+                    .map(|e| e.number()),
+                message: db_err.message().to_string(),
+                statement: None,
+            },
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed => SeekDbError::Connection(value.to_string()),
+            _ => SeekDbError::Sql {
+                message: value.to_string(),
+                statement: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn database_error_code_and_display() {
+        let err = SeekDbError::Database {
+            code: Some(1062),
+            message: "Duplicate entry 'a' for key 'PRIMARY'".into(),
+            statement: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            "database error 1062: Duplicate entry 'a' for key 'PRIMARY'"
+        );
+        assert_eq!(err.database_code(), Some(1062));
+
+        let partial = SeekDbError::PartialWrite {
+            written: 2,
+            source: Box::new(err),
+        };
+        assert_eq!(partial.database_code(), Some(1062));
+
+        let bare = SeekDbError::Database {
+            code: None,
+            message: "boom".into(),
+            statement: None,
+        };
+        assert_eq!(bare.to_string(), "database error: boom");
+        let sql = SeekDbError::from(sqlx::Error::Protocol("bad packet".into()));
+        assert!(matches!(
+            sql,
+            SeekDbError::Sql {
+                statement: None,
+                ..
+            }
+        ));
+        assert_eq!(sql.database_code(), None);
+    }
+
+    #[test]
+    fn statement_is_attached_redacted() {
+        let err = SeekDbError::Database {
+            code: Some(1064),
+            message: "syntax error".into(),
+            statement: None,
         }
+        .with_statement("CREATE TABLE `t` (x int) COMMENT = '{\"owner\":\"o''brien\"}'");
+        assert_eq!(
+            err.statement(),
+            Some("CREATE TABLE `t` (x int) COMMENT = '?'")
+        );
+        assert_eq!(
+            err.to_string(),
+            "database error 1064: syntax error (statement: CREATE TABLE `t` (x int) COMMENT = '?')"
+        );
+
+        let partial = SeekDbError::PartialWrite {
+            written: 1,
+            source: Box::new(err),
+        };
+        assert!(partial.statement().is_some());
+        assert!(
+            SeekDbError::InvalidInput("x".into())
+                .with_statement("SELECT 1")
+                .statement()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn redact_statement_keeps_placeholders_and_truncates() {
+        assert_eq!(
+            redact_statement("SELECT * FROM t WHERE a = ? AND b = 'x\\'y' AND c = \"z\""),
+            "SELECT * FROM t WHERE a = ? AND b = '?' AND c = \"?\""
+        );
+        let long = format!("INSERT INTO t VALUES {}", vec!["(?, ?)"; 1000].join(", "));
+        let redacted = redact_statement(&long);
+        assert!(redacted.ends_with("..."));
+        assert_eq!(redacted.chars().count(), MAX_STATEMENT_IN_ERROR + 3);
     }
 }
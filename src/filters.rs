@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SeekDbError};
 use crate::types::Metadata;
 
 /// Metadata filter expressions (mirrors Python SDK semantics).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Filter {
     Eq {
         field: String,
@@ -27,6 +32,10 @@ pub enum Filter {
         field: String,
         value: Metadata,
     },
+    /// Scalar `field` equals one of `values`.
+    ///
+    /// For an array-valued field use [`Filter::ArrayContains`] instead; `In`
+    /// compares the whole array against each value and does not look inside it.
     In {
         field: String,
         values: Vec<Metadata>,
@@ -35,13 +44,502 @@ pub enum Filter {
         field: String,
         values: Vec<Metadata>,
     },
+    /// Array `field` has `value` as one of its elements, e.g.
+    /// `{"tags": ["a", "b"]}` matches `value: "a"`. This is the reverse of
+    /// [`Filter::In`], which matches a scalar field against a list of values.
+    ArrayContains {
+        field: String,
+        value: Metadata,
+    },
+    /// Inclusive range `low <= field <= high`.
+    Between {
+        field: String,
+        low: Metadata,
+        high: Metadata,
+    },
+    /// SQL `LIKE` on a string field; `pattern` uses `%` and `_` wildcards.
+    Like {
+        field: String,
+        pattern: String,
+    },
+    /// String field starts with `prefix`, taken literally.
+    StartsWith {
+        field: String,
+        prefix: String,
+    },
+    /// Metadata has the key `field` (any value, including JSON `null`).
+    Exists {
+        field: String,
+    },
+    /// Metadata lacks the key `field`; rows with NULL metadata match too.
+    NotExists {
+        field: String,
+    },
     And(Vec<Filter>),
     Or(Vec<Filter>),
     Not(Box<Filter>),
 }
 
+/// Fluent alternative to building nested [`Filter`] values by hand.
+///
+/// Conditions added to one builder must all hold; [`or`](Self::or) and
+/// [`not`](Self::not) take a nested builder for alternatives and negation.
+///
+/// ```
+/// use seekdb_rs::{Filter, FilterBuilder};
+/// use serde_json::json;
+///
+/// let built = FilterBuilder::new()
+///     .gte("score", 10)
+///     .eq("tag", "x")
+///     .build();
+///
+/// let by_hand = Filter::And(vec![
+///     Filter::Gte { field: "score".into(), value: json!(10) },
+///     Filter::Eq { field: "tag".into(), value: json!("x") },
+/// ]);
+/// assert_eq!(built, Some(by_hand));
+/// ```
+///
+/// A single condition is returned as-is, and alternatives nest:
+///
+/// ```
+/// use seekdb_rs::{Filter, FilterBuilder};
+/// use serde_json::json;
+///
+/// let built = FilterBuilder::new()
+///     .eq("lang", "en")
+///     .or(FilterBuilder::new().lt("year", 2000).exists("classic"))
+///     .build();
+///
+/// assert_eq!(
+///     built,
+///     Some(Filter::And(vec![
+///         Filter::Eq { field: "lang".into(), value: json!("en") },
+///         Filter::Or(vec![
+///             Filter::Lt { field: "year".into(), value: json!(2000) },
+///             Filter::Exists { field: "classic".into() },
+///         ]),
+///     ]))
+/// );
+/// assert_eq!(
+///     FilterBuilder::new().ne("tag", "x").build(),
+///     Some(Filter::Ne { field: "tag".into(), value: json!("x") })
+/// );
+/// assert_eq!(FilterBuilder::new().build(), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FilterBuilder {
+    conditions: Vec<Filter>,
+}
+
+impl FilterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an arbitrary, already built condition.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.conditions.push(filter);
+        self
+    }
+
+    pub fn eq(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Eq {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn ne(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Ne {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn gt(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Gt {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn gte(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Gte {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn lt(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Lt {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn lte(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::Lte {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn between(
+        self,
+        field: impl Into<String>,
+        low: impl Into<Metadata>,
+        high: impl Into<Metadata>,
+    ) -> Self {
+        self.filter(Filter::Between {
+            field: field.into(),
+            low: low.into(),
+            high: high.into(),
+        })
+    }
+
+    pub fn is_in<V: Into<Metadata>>(
+        self,
+        field: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        self.filter(Filter::In {
+            field: field.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    pub fn not_in<V: Into<Metadata>>(
+        self,
+        field: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        self.filter(Filter::Nin {
+            field: field.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    pub fn array_contains(self, field: impl Into<String>, value: impl Into<Metadata>) -> Self {
+        self.filter(Filter::ArrayContains {
+            field: field.into(),
+            value: value.into(),
+        })
+    }
+
+    pub fn like(self, field: impl Into<String>, pattern: impl Into<String>) -> Self {
+        self.filter(Filter::Like {
+            field: field.into(),
+            pattern: pattern.into(),
+        })
+    }
+
+    pub fn starts_with(self, field: impl Into<String>, prefix: impl Into<String>) -> Self {
+        self.filter(Filter::StartsWith {
+            field: field.into(),
+            prefix: prefix.into(),
+        })
+    }
+
+    pub fn exists(self, field: impl Into<String>) -> Self {
+        self.filter(Filter::Exists {
+            field: field.into(),
+        })
+    }
+
+    pub fn not_exists(self, field: impl Into<String>) -> Self {
+        self.filter(Filter::NotExists {
+            field: field.into(),
+        })
+    }
+
+    /// Add all conditions of `group` (kept as one nested group).
+    pub fn and(self, group: FilterBuilder) -> Self {
+        match group.build() {
+            Some(filter) => self.filter(filter),
+            None => self,
+        }
+    }
+
+    /// Require at least one of `group`'s conditions to hold.
+    pub fn or(self, group: FilterBuilder) -> Self {
+        match group.conditions.len() {
+            0 => self,
+            1 => self.and(group),
+            _ => self.filter(Filter::Or(group.conditions)),
+        }
+    }
+
+    /// Require `group` (all of its conditions) not to hold.
+    pub fn not(self, group: FilterBuilder) -> Self {
+        match group.build() {
+            Some(filter) => self.filter(Filter::Not(Box::new(filter))),
+            None => self,
+        }
+    }
+
+    /// The combined filter: `None` without conditions, the condition itself
+    /// when there is one, otherwise [`Filter::And`] of all of them.
+    pub fn build(mut self) -> Option<Filter> {
+        match self.conditions.len() {
+            0 => None,
+            1 => self.conditions.pop(),
+            _ => Some(Filter::And(self.conditions)),
+        }
+    }
+}
+
+/// Type of a metadata field, as declared in a [`MetadataSchema`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    /// No type constraint; only the field name is checked.
+    Any,
+}
+
+impl MetadataType {
+    fn accepts(self, value: &Metadata) -> bool {
+        match self {
+            MetadataType::Any => true,
+            _ if value.is_null() => true,
+            MetadataType::String => value.is_string(),
+            MetadataType::Number => value.is_number(),
+            MetadataType::Bool => value.is_boolean(),
+            MetadataType::Array => value.is_array(),
+            MetadataType::Object => value.is_object(),
+        }
+    }
+}
+
+/// Known metadata fields of a collection, used by [`Filter::validate_against`]
+/// to reject filters on misspelled fields or with mismatched value types.
+#[derive(Clone, Debug, Default)]
+pub struct MetadataSchema {
+    fields: HashMap<String, MetadataType>,
+}
+
+impl MetadataSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_field(mut self, name: impl Into<String>, ty: MetadataType) -> Self {
+        self.fields.insert(name.into(), ty);
+        self
+    }
+
+    pub fn field(&self, name: &str) -> Option<MetadataType> {
+        self.fields.get(name).copied()
+    }
+}
+
+impl Filter {
+    /// Check, without a server round-trip, that every field this filter
+    /// references is declared in `schema` and that each operator and value
+    /// fits the field's type (e.g. no `Gt` on a string field).
+    pub fn validate_against(&self, schema: &MetadataSchema) -> Result<()> {
+        let field_type = |field: &str| {
+            schema.field(field).ok_or_else(|| {
+                SeekDbError::InvalidInput(format!("unknown metadata field '{field}' in filter"))
+            })
+        };
+        let check_value = |field: &str, op: &str, value: &Metadata| {
+            let ty = field_type(field)?;
+            if ty.accepts(value) {
+                Ok(())
+            } else {
+                Err(SeekDbError::InvalidInput(format!(
+                    "{op} on metadata field '{field}' of type {ty:?} with incompatible value {value}"
+                )))
+            }
+        };
+        let require_type = |field: &str, op: &str, allowed: &[MetadataType]| {
+            let ty = field_type(field)?;
+            if ty == MetadataType::Any || allowed.contains(&ty) {
+                Ok(())
+            } else {
+                Err(SeekDbError::InvalidInput(format!(
+                    "{op} is not supported on metadata field '{field}' of type {ty:?}"
+                )))
+            }
+        };
+
+        match self {
+            Filter::Eq { field, value } => check_value(field, "Eq", value),
+            Filter::Ne { field, value } => check_value(field, "Ne", value),
+            Filter::Lt { field, value } => {
+                require_type(field, "Lt", &[MetadataType::Number])?;
+                check_value(field, "Lt", value)
+            }
+            Filter::Gt { field, value } => {
+                require_type(field, "Gt", &[MetadataType::Number])?;
+                check_value(field, "Gt", value)
+            }
+            Filter::Lte { field, value } => {
+                require_type(field, "Lte", &[MetadataType::Number])?;
+                check_value(field, "Lte", value)
+            }
+            Filter::Gte { field, value } => {
+                require_type(field, "Gte", &[MetadataType::Number])?;
+                check_value(field, "Gte", value)
+            }
+            Filter::Between { field, low, high } => {
+                require_type(field, "Between", &[MetadataType::Number])?;
+                check_value(field, "Between", low)?;
+                check_value(field, "Between", high)
+            }
+            Filter::In { field, values } => values
+                .iter()
+                .try_for_each(|value| check_value(field, "In", value)),
+            Filter::Nin { field, values } => values
+                .iter()
+                .try_for_each(|value| check_value(field, "Nin", value)),
+            Filter::ArrayContains { field, .. } => {
+                require_type(field, "ArrayContains", &[MetadataType::Array])
+            }
+            Filter::Like { field, .. } => require_type(field, "Like", &[MetadataType::String]),
+            Filter::StartsWith { field, .. } => {
+                require_type(field, "StartsWith", &[MetadataType::String])
+            }
+            Filter::Exists { field } | Filter::NotExists { field } => field_type(field).map(|_| ()),
+            Filter::And(filters) | Filter::Or(filters) => {
+                filters.iter().try_for_each(|f| f.validate_against(schema))
+            }
+            Filter::Not(f) => f.validate_against(schema),
+        }
+    }
+}
+
+/// Parse a Chroma/Python-client `where` dict, e.g.
+/// `{"$and": [{"score": {"$gte": 10}}, {"tag": "x"}]}`.
+///
+/// Supported operators are `$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`, `$in`,
+/// `$nin`, `$and` and `$or`; a bare value means `$eq`. Several keys in one
+/// object are combined with AND. Anything else is `SeekDbError::InvalidInput`.
+impl TryFrom<&Metadata> for Filter {
+    type Error = SeekDbError;
+
+    fn try_from(value: &Metadata) -> Result<Self> {
+        let obj = value.as_object().ok_or_else(|| {
+            SeekDbError::InvalidInput(format!("where filter must be a JSON object, got {value}"))
+        })?;
+        let mut filters = Vec::with_capacity(obj.len());
+        for (key, operand) in obj {
+            let filter = match key.as_str() {
+                "$and" | "$or" => {
+                    let items = operand.as_array().ok_or_else(|| {
+                        SeekDbError::InvalidInput(format!("{key} expects an array of filters"))
+                    })?;
+                    let parsed = items
+                        .iter()
+                        .map(Filter::try_from)
+                        .collect::<Result<Vec<_>>>()?;
+                    if key == "$and" {
+                        Filter::And(parsed)
+                    } else {
+                        Filter::Or(parsed)
+                    }
+                }
+                op if op.starts_with('$') => {
+                    return Err(SeekDbError::InvalidInput(format!(
+                        "unsupported where operator {op} at top level"
+                    )));
+                }
+                field => parse_field_condition(field, operand)?,
+            };
+            filters.push(filter);
+        }
+        match filters.len() {
+            0 => Err(SeekDbError::InvalidInput(
+                "where filter must not be an empty object".into(),
+            )),
+            1 => Ok(filters.remove(0)),
+            _ => Ok(Filter::And(filters)),
+        }
+    }
+}
+
+impl TryFrom<Metadata> for Filter {
+    type Error = SeekDbError;
+
+    fn try_from(value: Metadata) -> Result<Self> {
+        Filter::try_from(&value)
+    }
+}
+
+/// `{"field": value}` or `{"field": {"$op": value, ...}}`.
+fn parse_field_condition(field: &str, operand: &Metadata) -> Result<Filter> {
+    let Some(ops) = operand.as_object() else {
+        return Ok(Filter::Eq {
+            field: field.to_string(),
+            value: operand.clone(),
+        });
+    };
+    let mut filters = Vec::with_capacity(ops.len());
+    for (op, value) in ops {
+        let field = field.to_string();
+        let list = |op: &str| {
+            value.as_array().cloned().ok_or_else(|| {
+                SeekDbError::InvalidInput(format!("{op} on field '{field}' expects an array"))
+            })
+        };
+        let filter = match op.as_str() {
+            "$eq" => Filter::Eq {
+                value: value.clone(),
+                field,
+            },
+            "$ne" => Filter::Ne {
+                value: value.clone(),
+                field,
+            },
+            "$gt" => Filter::Gt {
+                value: value.clone(),
+                field,
+            },
+            "$gte" => Filter::Gte {
+                value: value.clone(),
+                field,
+            },
+            "$lt" => Filter::Lt {
+                value: value.clone(),
+                field,
+            },
+            "$lte" => Filter::Lte {
+                value: value.clone(),
+                field,
+            },
+            "$in" => Filter::In {
+                values: list("$in")?,
+                field,
+            },
+            "$nin" => Filter::Nin {
+                values: list("$nin")?,
+                field,
+            },
+            other => {
+                return Err(SeekDbError::InvalidInput(format!(
+                    "unsupported where operator {other} on field '{field}'"
+                )));
+            }
+        };
+        filters.push(filter);
+    }
+    match filters.len() {
+        0 => Err(SeekDbError::InvalidInput(format!(
+            "no operator given for field '{field}'"
+        ))),
+        1 => Ok(filters.remove(0)),
+        _ => Ok(Filter::And(filters)),
+    }
+}
+
 /// Document filter expressions.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DocFilter {
     Contains(String),
     Regex(String),
@@ -62,27 +560,36 @@ pub fn build_where_clause(
     filter: Option<&Filter>,
     doc_filter: Option<&DocFilter>,
     ids: Option<&[String]>,
+) -> SqlWhere {
+    build_where_clause_with_dialect(filter, doc_filter, ids, JsonDialect::default())
+}
+
+/// [`build_where_clause`] with metadata paths read using `dialect`.
+pub fn build_where_clause_with_dialect(
+    filter: Option<&Filter>,
+    doc_filter: Option<&DocFilter>,
+    ids: Option<&[String]>,
+    dialect: JsonDialect,
 ) -> SqlWhere {
     let mut clauses: Vec<String> = Vec::new();
     let mut params: Vec<Metadata> = Vec::new();
 
     // IDs filter: generate `_id IN (?, ?, ...)`
-    if let Some(ids) = ids {
-        if !ids.is_empty() {
-            let placeholders = std::iter::repeat("?")
-                .take(ids.len())
-                .collect::<Vec<_>>()
-                .join(", ");
-            clauses.push(format!("_id IN ({placeholders})"));
-            for id in ids {
-                params.push(Metadata::String(id.clone()));
-            }
+    if let Some(ids) = ids
+        && !ids.is_empty()
+    {
+        let placeholders = std::iter::repeat_n("?", ids.len())
+            .collect::<Vec<_>>()
+            .join(", ");
+        clauses.push(format!("_id IN ({placeholders})"));
+        for id in ids {
+            params.push(Metadata::String(id.clone()));
         }
     }
 
     // Metadata filter
     if let Some(filter) = filter {
-        let (clause, mut p) = build_meta_clause(filter);
+        let (clause, mut p) = build_meta_clause(filter, dialect);
         if !clause.is_empty() {
             clauses.push(clause);
             params.append(&mut p);
@@ -107,73 +614,54 @@ pub fn build_where_clause(
     SqlWhere { clause, params }
 }
 
-fn build_meta_clause(filter: &Filter) -> (String, Vec<Metadata>) {
+fn build_meta_clause(filter: &Filter, dialect: JsonDialect) -> (String, Vec<Metadata>) {
     let mut params = Vec::new();
     let clause = match filter {
-        Filter::Eq { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') = ?")
-        }
-        Filter::Lt { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') < ?")
-        }
-        Filter::Gt { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') > ?")
-        }
-        Filter::Lte { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') <= ?")
-        }
-        Filter::Gte { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') >= ?")
-        }
-        Filter::Ne { field, value } => {
-            params.push(value.clone());
-            format!("JSON_EXTRACT(metadata, '$.{field}') != ?")
-        }
-        Filter::In { field, values } => {
-            let placeholders = std::iter::repeat("?")
-                .take(values.len())
-                .collect::<Vec<_>>()
-                .join(", ");
-            params.extend(values.iter().cloned());
-            format!("JSON_EXTRACT(metadata, '$.{field}') IN ({placeholders})")
-        }
-        Filter::Nin { field, values } => {
-            let placeholders = std::iter::repeat("?")
-                .take(values.len())
-                .collect::<Vec<_>>()
-                .join(", ");
-            params.extend(values.iter().cloned());
-            format!("JSON_EXTRACT(metadata, '$.{field}') NOT IN ({placeholders})")
-        }
-        Filter::And(filters) => {
-            let mut clauses = Vec::new();
-            for f in filters {
-                let (c, mut p) = build_meta_clause(f);
-                if !c.is_empty() {
-                    clauses.push(c);
-                    params.append(&mut p);
-                }
-            }
-            format!("({})", clauses.join(" AND "))
+        Filter::Eq { field, value } => compare(&mut params, dialect, field, "=", value),
+        Filter::Lt { field, value } => compare(&mut params, dialect, field, "<", value),
+        Filter::Gt { field, value } => compare(&mut params, dialect, field, ">", value),
+        Filter::Lte { field, value } => compare(&mut params, dialect, field, "<=", value),
+        Filter::Gte { field, value } => compare(&mut params, dialect, field, ">=", value),
+        Filter::Ne { field, value } => compare(&mut params, dialect, field, "!=", value),
+        Filter::In { field, values } => in_list(&mut params, dialect, field, "IN", values),
+        Filter::Nin { field, values } => in_list(&mut params, dialect, field, "NOT IN", values),
+        Filter::Between { field, low, high } => {
+            params.push(low.clone());
+            params.push(high.clone());
+            format!("{} BETWEEN ? AND ?", dialect.json_extract(field))
         }
-        Filter::Or(filters) => {
-            let mut clauses = Vec::new();
-            for f in filters {
-                let (c, mut p) = build_meta_clause(f);
-                if !c.is_empty() {
-                    clauses.push(c);
-                    params.append(&mut p);
-                }
-            }
-            format!("({})", clauses.join(" OR "))
+        Filter::ArrayContains { field, value } => {
+            // Bound as JSON text so CAST(? AS JSON) rebuilds the typed value.
+            params.push(Metadata::String(value.to_string()));
+            format!(
+                "JSON_CONTAINS({}, CAST(? AS JSON))",
+                dialect.json_extract(field)
+            )
         }
+        Filter::Like { field, pattern } => {
+            params.push(Metadata::String(pattern.clone()));
+            format!("{} LIKE ?", dialect.json_extract_unquoted(field))
+        }
+        Filter::StartsWith { field, prefix } => {
+            params.push(Metadata::String(format!("{}%", escape_like(prefix))));
+            format!("{} LIKE ?", dialect.json_extract_unquoted(field))
+        }
+        Filter::Exists { field } => {
+            format!(
+                "JSON_CONTAINS_PATH(metadata, 'one', {path})",
+                path = json_path_literal(field)
+            )
+        }
+        Filter::NotExists { field } => {
+            format!(
+                "COALESCE(JSON_CONTAINS_PATH(metadata, 'one', {path}), 0) = 0",
+                path = json_path_literal(field)
+            )
+        }
+        Filter::And(filters) => join_clauses(&mut params, dialect, filters, " AND "),
+        Filter::Or(filters) => join_clauses(&mut params, dialect, filters, " OR "),
         Filter::Not(f) => {
-            let (c, mut p) = build_meta_clause(f);
+            let (c, mut p) = build_meta_clause(f, dialect);
             if !c.is_empty() {
                 params.append(&mut p);
                 format!("NOT ({c})")
@@ -186,6 +674,158 @@ fn build_meta_clause(filter: &Filter) -> (String, Vec<Metadata>) {
     (clause, params)
 }
 
+/// `field <op> ?`, binding `value`.
+fn compare(
+    params: &mut Vec<Metadata>,
+    dialect: JsonDialect,
+    field: &str,
+    op: &str,
+    value: &Metadata,
+) -> String {
+    params.push(value.clone());
+    format!("{} {op} ?", dialect.json_extract(field))
+}
+
+/// `field <op> (?, ...)`, binding each of `values`.
+fn in_list(
+    params: &mut Vec<Metadata>,
+    dialect: JsonDialect,
+    field: &str,
+    op: &str,
+    values: &[Metadata],
+) -> String {
+    let placeholders = std::iter::repeat_n("?", values.len())
+        .collect::<Vec<_>>()
+        .join(", ");
+    params.extend(values.iter().cloned());
+    format!("{} {op} ({placeholders})", dialect.json_extract(field))
+}
+
+/// The non-empty clauses of `filters` joined by `sep`, in parentheses.
+fn join_clauses(
+    params: &mut Vec<Metadata>,
+    dialect: JsonDialect,
+    filters: &[Filter],
+    sep: &str,
+) -> String {
+    let mut clauses = Vec::new();
+    for f in filters {
+        let (c, mut p) = build_meta_clause(f, dialect);
+        if !c.is_empty() {
+            clauses.push(c);
+            params.append(&mut p);
+        }
+    }
+    format!("({})", clauses.join(sep))
+}
+
+/// How metadata values are read out of the JSON `metadata` column.
+///
+/// All supported servers accept the `JSON_EXTRACT` function form, which is the
+/// default. Use [`ServerClient::capabilities`](crate::ServerClient::capabilities)
+/// to detect the form a server handles and
+/// [`ServerClient::with_json_dialect`](crate::ServerClient::with_json_dialect)
+/// to select it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JsonDialect {
+    /// `JSON_EXTRACT(metadata, path)` and `JSON_UNQUOTE(JSON_EXTRACT(...))`.
+    #[default]
+    Function,
+    /// `metadata->path` and `metadata->>path`.
+    Operator,
+}
+
+impl JsonDialect {
+    /// SQL expression for the JSON value at metadata `field`.
+    pub fn json_extract(self, field: &str) -> String {
+        let path = json_path_literal(field);
+        match self {
+            JsonDialect::Function => format!("JSON_EXTRACT(metadata, {path})"),
+            JsonDialect::Operator => format!("metadata->{path}"),
+        }
+    }
+
+    /// SQL expression for the value at metadata `field` with JSON strings
+    /// unquoted, for text comparisons such as `LIKE`.
+    pub fn json_extract_unquoted(self, field: &str) -> String {
+        let path = json_path_literal(field);
+        match self {
+            JsonDialect::Function => format!("JSON_UNQUOTE(JSON_EXTRACT(metadata, {path}))"),
+            JsonDialect::Operator => format!("metadata->>{path}"),
+        }
+    }
+}
+
+/// SQL string literal holding the JSON path for a metadata `field`.
+///
+/// Dots separate nested keys and `[N]` indexes into arrays, so `"a.b[0].c"`
+/// becomes `'$.a.b[0].c'`. Keys that are not identifiers (ASCII letters,
+/// digits and `_`, not starting with a digit) are emitted as quoted path
+/// members, and the result is escaped for use inside a single-quoted SQL
+/// literal.
+pub(crate) fn json_path_literal(field: &str) -> String {
+    let mut path = String::from("$");
+    for segment in field.split('.') {
+        let (key, indexes) = split_array_indexes(segment);
+        if !key.is_empty() || indexes.is_empty() {
+            path.push('.');
+            let identifier = key.starts_with(|c: char| !c.is_ascii_digit())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if identifier {
+                path.push_str(key);
+            } else {
+                path.push('"');
+                for c in key.chars() {
+                    if matches!(c, '"' | '\\') {
+                        path.push('\\');
+                    }
+                    path.push(c);
+                }
+                path.push('"');
+            }
+        }
+        for index in indexes {
+            path.push('[');
+            path.push_str(index);
+            path.push(']');
+        }
+    }
+    format!("'{}'", path.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+/// Split trailing `[N]` array indexes off a path segment: `"tags[0][1]"`
+/// gives `("tags", ["0", "1"])`. Brackets that are not all-digit indexes
+/// stay part of the key.
+fn split_array_indexes(segment: &str) -> (&str, Vec<&str>) {
+    let mut key = segment;
+    let mut indexes = Vec::new();
+    while let Some(rest) = key.strip_suffix(']') {
+        let Some(open) = rest.rfind('[') else {
+            break;
+        };
+        let index = &rest[open + 1..];
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            break;
+        }
+        indexes.push(index);
+        key = &rest[..open];
+    }
+    indexes.reverse();
+    (key, indexes)
+}
+
+/// Escape `LIKE` wildcards (and the `\\` escape character) so `text` matches literally.
+fn escape_like(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
 fn build_doc_clause(filter: &DocFilter) -> (String, Vec<Metadata>) {
     let mut params = Vec::new();
     let clause = match filter {
@@ -255,6 +895,269 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_like_and_starts_with() {
+        let filter = Filter::And(vec![
+            Filter::Like {
+                field: "title".into(),
+                pattern: "%rust%".into(),
+            },
+            Filter::StartsWith {
+                field: "path".into(),
+                prefix: "50%_off\\".into(),
+            },
+        ]);
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_UNQUOTE(JSON_EXTRACT(metadata, '$.title')) LIKE ? AND JSON_UNQUOTE(JSON_EXTRACT(metadata, '$.path')) LIKE ?)"
+        );
+        assert_eq!(sql.params, vec![json!("%rust%"), json!("50\\%\\_off\\\\%")]);
+    }
+
+    #[test]
+    fn test_exists_and_not_exists() {
+        let filter = Filter::Or(vec![
+            Filter::Exists {
+                field: "source".into(),
+            },
+            Filter::NotExists {
+                field: "version".into(),
+            },
+        ]);
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_CONTAINS_PATH(metadata, 'one', '$.source') OR COALESCE(JSON_CONTAINS_PATH(metadata, 'one', '$.version'), 0) = 0)"
+        );
+        assert!(sql.params.is_empty());
+    }
+
+    fn sample_schema() -> MetadataSchema {
+        MetadataSchema::new()
+            .with_field("category", MetadataType::String)
+            .with_field("score", MetadataType::Number)
+            .with_field("extra", MetadataType::Any)
+    }
+
+    #[test]
+    fn test_validate_against_nested_filters() {
+        let schema = sample_schema();
+        let ok = Filter::And(vec![
+            Filter::Eq {
+                field: "category".into(),
+                value: json!("news"),
+            },
+            Filter::Or(vec![
+                Filter::Gte {
+                    field: "score".into(),
+                    value: json!(3),
+                },
+                Filter::Not(Box::new(Filter::In {
+                    field: "extra".into(),
+                    values: vec![json!(1), json!("x")],
+                })),
+            ]),
+            Filter::StartsWith {
+                field: "category".into(),
+                prefix: "ne".into(),
+            },
+        ]);
+        ok.validate_against(&schema).unwrap();
+
+        let typo = Filter::Or(vec![Filter::Not(Box::new(Filter::Eq {
+            field: "catgory".into(),
+            value: json!("news"),
+        }))]);
+        let err = typo.validate_against(&schema).unwrap_err();
+        assert!(err.to_string().contains("unknown metadata field 'catgory'"));
+    }
+
+    #[test]
+    fn test_validate_against_rejects_type_mismatch() {
+        let schema = sample_schema();
+        let range_on_string = Filter::And(vec![Filter::Gt {
+            field: "category".into(),
+            value: json!("a"),
+        }]);
+        assert!(range_on_string.validate_against(&schema).is_err());
+
+        let wrong_value = Filter::In {
+            field: "score".into(),
+            values: vec![json!(1), json!("two")],
+        };
+        assert!(wrong_value.validate_against(&schema).is_err());
+
+        let like_on_number = Filter::Like {
+            field: "score".into(),
+            pattern: "1%".into(),
+        };
+        assert!(like_on_number.validate_against(&schema).is_err());
+
+        let null_is_fine = Filter::Ne {
+            field: "score".into(),
+            value: json!(null),
+        };
+        null_is_fine.validate_against(&schema).unwrap();
+    }
+
+    #[test]
+    fn test_json_path_literal() {
+        assert_eq!(json_path_literal("age"), "'$.age'");
+        assert_eq!(json_path_literal("a.b.c"), "'$.a.b.c'");
+        assert_eq!(json_path_literal("tags[0]"), "'$.tags[0]'");
+        assert_eq!(json_path_literal("a.b[2][10].c"), "'$.a.b[2][10].c'");
+        assert_eq!(json_path_literal("my-key.x y"), "'$.\"my-key\".\"x y\"'");
+        assert_eq!(json_path_literal("it's"), "'$.\"it''s\"'");
+        assert_eq!(json_path_literal("q\"k"), "'$.\"q\\\\\"k\"'");
+        assert_eq!(json_path_literal("a[x]"), "'$.\"a[x]\"'");
+        assert_eq!(json_path_literal("2024.q1"), "'$.\"2024\".q1'");
+        assert_eq!(json_path_literal("_a1"), "'$._a1'");
+    }
+
+    #[test]
+    fn test_nested_path_in_where_clause() {
+        let filter = Filter::Eq {
+            field: "author.names[1]".into(),
+            value: json!("bob"),
+        };
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE JSON_EXTRACT(metadata, '$.author.names[1]') = ?"
+        );
+    }
+
+    #[test]
+    fn test_operator_dialect_where_clause() {
+        let filter = Filter::And(vec![
+            Filter::Gte {
+                field: "a.n".into(),
+                value: json!(2),
+            },
+            Filter::StartsWith {
+                field: "path".into(),
+                prefix: "docs/".into(),
+            },
+            Filter::Exists {
+                field: "tag".into(),
+            },
+        ]);
+        let sql = build_where_clause_with_dialect(Some(&filter), None, None, JsonDialect::Operator);
+        assert_eq!(
+            sql.clause,
+            "WHERE (metadata->'$.a.n' >= ? AND metadata->>'$.path' LIKE ? AND JSON_CONTAINS_PATH(metadata, 'one', '$.tag'))"
+        );
+        assert_eq!(
+            JsonDialect::Function.json_extract_unquoted("path"),
+            "JSON_UNQUOTE(JSON_EXTRACT(metadata, '$.path'))"
+        );
+    }
+
+    #[test]
+    fn test_between_binds_low_then_high() {
+        let filter = Filter::And(vec![
+            Filter::Eq {
+                field: "kind".into(),
+                value: json!("a"),
+            },
+            Filter::Between {
+                field: "score".into(),
+                low: json!(10),
+                high: json!(20),
+            },
+        ]);
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_EXTRACT(metadata, '$.kind') = ? AND JSON_EXTRACT(metadata, '$.score') BETWEEN ? AND ?)"
+        );
+        assert_eq!(sql.params, vec![json!("a"), json!(10), json!(20)]);
+    }
+
+    #[test]
+    fn test_array_contains_binds_json_text() {
+        let filter = Filter::Or(vec![
+            Filter::ArrayContains {
+                field: "tags".into(),
+                value: json!("a"),
+            },
+            Filter::ArrayContains {
+                field: "ranks".into(),
+                value: json!(3),
+            },
+        ]);
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_CONTAINS(JSON_EXTRACT(metadata, '$.tags'), CAST(? AS JSON)) OR JSON_CONTAINS(JSON_EXTRACT(metadata, '$.ranks'), CAST(? AS JSON)))"
+        );
+        assert_eq!(sql.params, vec![json!("\"a\""), json!("3")]);
+
+        let schema = MetadataSchema::new()
+            .with_field("tags", MetadataType::Array)
+            .with_field("name", MetadataType::String);
+        filter.validate_against(&schema).unwrap_err();
+        Filter::ArrayContains {
+            field: "tags".into(),
+            value: json!("a"),
+        }
+        .validate_against(&schema)
+        .unwrap();
+        Filter::ArrayContains {
+            field: "name".into(),
+            value: json!("a"),
+        }
+        .validate_against(&schema)
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_filter_from_chroma_where() {
+        let filter = Filter::try_from(&json!({
+            "$and": [
+                {"score": {"$gte": 10}},
+                {"tag": "x"},
+                {"$or": [{"lang": {"$in": ["en", "de"]}}, {"draft": {"$ne": true}}]}
+            ]
+        }))
+        .unwrap();
+        let sql = build_where_clause(Some(&filter), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_EXTRACT(metadata, '$.score') >= ? AND JSON_EXTRACT(metadata, '$.tag') = ? AND (JSON_EXTRACT(metadata, '$.lang') IN (?, ?) OR JSON_EXTRACT(metadata, '$.draft') != ?))"
+        );
+        assert_eq!(
+            sql.params,
+            vec![json!(10), json!("x"), json!("en"), json!("de"), json!(true)]
+        );
+
+        // Several operators on one field are ANDed.
+        let range = Filter::try_from(json!({"score": {"$gt": 1, "$lte": 5}})).unwrap();
+        let sql = build_where_clause(Some(&range), None, None);
+        assert_eq!(
+            sql.clause,
+            "WHERE (JSON_EXTRACT(metadata, '$.score') > ? AND JSON_EXTRACT(metadata, '$.score') <= ?)"
+        );
+    }
+
+    #[test]
+    fn test_filter_from_chroma_where_rejects_bad_input() {
+        for bad in [
+            json!({"score": {"$regex": "a"}}),
+            json!({"$not": {"a": 1}}),
+            json!({"tag": {"$in": "x"}}),
+            json!({"$and": {"a": 1}}),
+            json!({}),
+            json!(["a"]),
+        ] {
+            match Filter::try_from(&bad) {
+                Err(SeekDbError::InvalidInput(_)) => {}
+                other => panic!("expected InvalidInput for {bad}, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_doc_regex_or() {
         let doc = DocFilter::Or(vec![
@@ -0,0 +1,146 @@
+//! Record id generation for `Collection::add_auto`.
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// How [`Collection::add_auto`](crate::Collection::add_auto) generates ids.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// Random UUID v4 in hyphenated lower-case form (36 chars).
+    #[default]
+    UuidV4,
+    /// ULID (26 chars, Crockford base32): sortable by creation time, and
+    /// strictly increasing within this process.
+    Ulid,
+    /// Hex SHA-256 of the document and embedding (64 chars), so identical
+    /// content always gets the same id and re-adding it collides instead of
+    /// duplicating.
+    ContentHash,
+}
+
+impl IdStrategy {
+    /// Id for one record with the given content.
+    pub fn generate(&self, document: Option<&str>, embedding: &[f32]) -> String {
+        match self {
+            IdStrategy::UuidV4 => uuid_v4(),
+            IdStrategy::Ulid => ulid(),
+            IdStrategy::ContentHash => content_hash(document, embedding),
+        }
+    }
+}
+
+fn uuid_v4() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Last ULID handed out: (milliseconds, 80 random bits).
+static LAST_ULID: Mutex<(u64, u128)> = Mutex::new((0, 0));
+
+const RANDOM_MASK: u128 = (1 << 80) - 1;
+
+fn ulid() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut last = LAST_ULID.lock().unwrap_or_else(|e| e.into_inner());
+    // Within the same millisecond (or if the clock went back), increment the
+    // random part instead of drawing a new one, as the ULID spec's monotonic
+    // mode does; on overflow borrow the next millisecond.
+    let (ms, random) = if now > last.0 {
+        let mut bytes = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        (now, u128::from_le_bytes(bytes) & RANDOM_MASK)
+    } else if last.1 < RANDOM_MASK {
+        (last.0, last.1 + 1)
+    } else {
+        (last.0 + 1, 0)
+    };
+    *last = (ms, random);
+    encode_ulid((u128::from(ms) << 80) | random)
+}
+
+/// Crockford base32 of a 128-bit ULID value, most significant digit first.
+fn encode_ulid(value: u128) -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    (0..26)
+        .rev()
+        .map(|i| ALPHABET[((value >> (i * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+fn content_hash(document: Option<&str>, embedding: &[f32]) -> String {
+    let mut hasher = Sha256::new();
+    // Length-prefix the document so different splits never hash alike.
+    let document = document.unwrap_or_default();
+    hasher.update((document.len() as u64).to_le_bytes());
+    hasher.update(document.as_bytes());
+    for v in embedding {
+        hasher.update(v.to_le_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuid_v4_has_version_and_variant() {
+        let id = uuid_v4();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"), "{id}");
+        assert_ne!(id, uuid_v4());
+    }
+
+    #[test]
+    fn ulids_are_monotonic() {
+        let ids: Vec<String> = (0..1000).map(|_| ulid()).collect();
+        assert!(ids.iter().all(|id| id.len() == 26));
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn encode_ulid_uses_crockford_alphabet() {
+        assert_eq!(encode_ulid(0), "0".repeat(26));
+        assert_eq!(encode_ulid(u128::MAX), format!("7{}", "Z".repeat(25)));
+    }
+
+    #[test]
+    fn content_hash_is_stable() {
+        let a = IdStrategy::ContentHash.generate(Some("doc"), &[1.0, 2.0]);
+        assert_eq!(a.len(), 64);
+        assert_eq!(
+            a,
+            IdStrategy::ContentHash.generate(Some("doc"), &[1.0, 2.0])
+        );
+        assert_ne!(
+            a,
+            IdStrategy::ContentHash.generate(Some("doc"), &[1.0, 2.5])
+        );
+        assert_ne!(a, IdStrategy::ContentHash.generate(Some("do"), &[1.0, 2.0]));
+        assert_eq!(
+            IdStrategy::ContentHash.generate(None, &[]),
+            "af5570f5a1810b7af78caf4bc70a660f0df51e42baf91d4de5b2328de0e83dfc"
+        );
+    }
+}
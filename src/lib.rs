@@ -8,29 +8,48 @@ pub mod config;
 pub mod embedding;
 pub mod error;
 pub mod filters;
+pub mod ids;
 pub mod meta;
 pub mod server;
+#[cfg(feature = "stats")]
+pub mod stats;
 #[cfg(feature = "sync")]
 pub mod sync;
 pub mod types;
 
 pub use crate::admin::{AdminApi, AdminClient};
 pub use crate::collection::{
-    AddBatch, Collection, DeleteQuery, GetQuery, UpdateBatch, UpsertBatch,
+    AddBatch, Collection, DeleteQuery, DimensionFit, GetQuery, OnConflict, PARENT_ID_KEY,
+    QueryOptions, SortDir, UpdateBatch, UpsertBatch,
+};
+pub use crate::config::{
+    DistanceMetric, HnswConfig, OverloadPolicy, ServerConfig, SslMode, VectorField,
+};
+pub use crate::embedding::{
+    CachingEmbedding, EmbeddingFactory, EmbeddingFunction, EmbeddingRegistry,
 };
-pub use crate::config::{DistanceMetric, HnswConfig, ServerConfig};
-pub use crate::embedding::EmbeddingFunction;
 pub use crate::error::SeekDbError;
-pub use crate::filters::{DocFilter, Filter, SqlWhere};
+pub use crate::filters::{
+    DocFilter, Filter, FilterBuilder, JsonDialect, MetadataSchema, MetadataType, SqlWhere,
+};
+pub use crate::ids::IdStrategy;
 pub use crate::meta::{CollectionFieldNames, CollectionNames};
 pub use crate::server::ServerClient;
+#[cfg(feature = "stats")]
+pub use crate::stats::{LatencyPercentiles, LatencySnapshot};
 pub use crate::types::Database;
 pub use crate::types::{
-    Document, Documents, Embedding, Embeddings, GetResult, IncludeField, Metadata, QueryResult,
+    CollectionInfo, CollectionSchema, Document, Documents, Embedding, Embeddings, GetResult,
+    IncludeField, IncludeFlags, Metadata, PageToken, QueryGroup, QueryHit, QueryResult, Record,
+    ServerCapabilities, UpsertReport, VectorLiteralFormat, merge_query_results,
 };
 
+#[cfg(feature = "testing")]
+pub use crate::embedding::IdentityEmbedding;
+#[cfg(feature = "openai")]
+pub use crate::embedding::OpenAiEmbedding;
 #[cfg(feature = "embedding")]
-pub use crate::embedding::DefaultEmbedding;
+pub use crate::embedding::{DefaultEmbedding, DefaultEmbeddingOptions, ExecutionProvider};
 
 #[cfg(feature = "sync")]
 pub use crate::sync::{SyncCollection, SyncServerClient};
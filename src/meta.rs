@@ -33,10 +33,7 @@ impl CollectionNames {
             )));
         }
 
-        if !name
-            .bytes()
-            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
-        {
+        if !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
             return Err(SeekDbError::InvalidInput(
                 "collection name must match [a-zA-Z0-9_]".into(),
             ));
@@ -86,8 +83,7 @@ mod tests {
 
     #[test]
     fn too_long_collection_name_fails() {
-        let allowed_len =
-            CollectionNames::MAX_TABLE_NAME_LEN - CollectionNames::TABLE_PREFIX.len();
+        let allowed_len = CollectionNames::MAX_TABLE_NAME_LEN - CollectionNames::TABLE_PREFIX.len();
         let long_name = "a".repeat(allowed_len + 1);
         let err = CollectionNames::validate(&long_name).unwrap_err();
         assert!(matches!(err, SeekDbError::InvalidInput(_)));
@@ -1,15 +1,33 @@
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
 use async_trait::async_trait;
-use sqlx::mysql::MySqlPoolOptions;
-use sqlx::{MySqlPool, Row};
+use sqlx::mysql::{
+    MySqlArguments, MySqlConnectOptions, MySqlConnection, MySqlDatabaseError, MySqlPoolOptions,
+    MySqlQueryResult, MySqlRow, MySqlSslMode,
+};
+use sqlx::pool::PoolConnection;
+use sqlx::query::Query;
+use sqlx::{Execute, MySql, MySqlPool, Row, Transaction};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 use crate::admin::AdminApi;
-use crate::backend::SqlBackend;
-use crate::collection::Collection;
-use crate::config::{DistanceMetric, HnswConfig, ServerConfig};
+use crate::collection::{Collection, dimension_distance_mismatch};
+use crate::config::{
+    DEFAULT_BASE_BACKOFF, DEFAULT_IDLE_TIMEOUT, DEFAULT_MAX_LIFETIME, DistanceMetric, HnswConfig,
+    OverloadPolicy, ServerConfig, SslMode, VectorField,
+};
 use crate::embedding::EmbeddingFunction;
 use crate::error::{Result, SeekDbError};
+use crate::filters::JsonDialect;
 use crate::meta::CollectionNames;
-use crate::types::Database;
+#[cfg(feature = "stats")]
+use crate::stats::{LatencySnapshot, LatencyStats};
+use crate::types::{CollectionInfo, CollectionSchema, Database, ServerCapabilities};
 
 /// Builder for configuring and constructing a [`ServerClient`].
 ///
@@ -24,6 +42,14 @@ pub struct ServerClientBuilder {
     user: String,
     password: String,
     max_connections: u32,
+    min_connections: u32,
+    idle_timeout: Option<Duration>,
+    max_lifetime: Option<Duration>,
+    max_retries: u32,
+    base_backoff: Duration,
+    ssl_mode: SslMode,
+    ca_cert_path: Option<PathBuf>,
+    max_inflight: Option<(usize, OverloadPolicy)>,
 }
 
 /// Server-side client that talks to seekdb/OceanBase over MySQL protocol.
@@ -32,21 +58,246 @@ pub struct ServerClient {
     pool: MySqlPool,
     tenant: String,
     database: String,
+    /// Settings the pool was opened with; reused to reach other tenants.
+    config: Arc<ServerConfig>,
+    /// Clients for other tenants opened by [`ServerClient::tenant_client`].
+    tenant_clients: Arc<Mutex<HashMap<String, ServerClient>>>,
+    /// Reject `UPDATE`/`DELETE` without `WHERE` in [`ServerClient::execute`].
+    safe_updates: bool,
+    /// Attach the failing statement to SQL errors; see
+    /// [`ServerClient::with_verbose_errors`].
+    verbose_errors: bool,
+    /// Row limit standing in for "no limit" when only an offset is given;
+    /// see [`ServerClient::with_max_rows`].
+    max_rows: u64,
+    /// How filters read metadata; see [`ServerClient::with_json_dialect`].
+    json_dialect: JsonDialect,
+    /// Statements currently running, shared by clones; see
+    /// [`ServerClient::with_max_inflight`].
+    inflight: Arc<Inflight>,
+    #[cfg(feature = "stats")]
+    stats: Arc<LatencyStats>,
+}
+
+/// In-flight statement count and optional limit of a [`ServerClient`].
+#[derive(Default)]
+struct Inflight {
+    current: AtomicUsize,
+    limit: Option<InflightLimit>,
+}
+
+impl Inflight {
+    fn limited(max: usize, policy: OverloadPolicy) -> Self {
+        Inflight {
+            current: AtomicUsize::new(0),
+            limit: Some(InflightLimit {
+                max,
+                policy,
+                semaphore: Semaphore::new(max),
+            }),
+        }
+    }
+
+    async fn admit(&self) -> Result<InflightGuard<'_>> {
+        let permit = match &self.limit {
+            None => None,
+            Some(limit) => Some(match limit.policy {
+                OverloadPolicy::Wait => limit
+                    .semaphore
+                    .acquire()
+                    .await
+                    .expect("in-flight semaphore is never closed"),
+                OverloadPolicy::Reject => {
+                    limit
+                        .semaphore
+                        .try_acquire()
+                        .map_err(|_| SeekDbError::Overloaded {
+                            max_inflight: limit.max,
+                        })?
+                }
+            }),
+        };
+        self.current.fetch_add(1, Ordering::Relaxed);
+        Ok(InflightGuard {
+            inflight: self,
+            _permit: permit,
+        })
+    }
+}
+
+struct InflightLimit {
+    max: usize,
+    policy: OverloadPolicy,
+    semaphore: Semaphore,
+}
+
+/// Admission of one statement; releases its slot when dropped.
+pub(crate) struct InflightGuard<'a> {
+    inflight: &'a Inflight,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        self.inflight.current.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Classifies a statement for latency accounting (reads vs. writes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OpKind {
+    Read,
+    Write,
+}
+
+/// A single pooled connection held across several statements.
+///
+/// Statements issued through the pool may each land on a different connection,
+/// so anything relying on session state must go through one of these instead.
+/// Call [`PinnedSession::release`] with the statement that undoes that state;
+/// if release never runs (the owning future was dropped mid-flight) or the reset
+/// fails, the connection is detached from the pool rather than handed back dirty.
+pub(crate) struct PinnedSession<'a> {
+    client: &'a ServerClient,
+    conn: Option<PoolConnection<MySql>>,
+}
+
+impl PinnedSession<'_> {
+    fn conn(&mut self) -> &mut PoolConnection<MySql> {
+        self.conn
+            .as_mut()
+            .expect("connection is only taken by release/drop")
+    }
+
+    /// Execute a statement that does not return rows on the pinned connection.
+    pub(crate) async fn execute(&mut self, sql: &str) -> Result<MySqlQueryResult> {
+        let client = self.client;
+        let conn = self.conn();
+        client
+            .timed_sql(OpKind::Write, sql, sqlx::query(sql).execute(&mut **conn))
+            .await
+    }
+
+    /// Fetch all rows for `sql` on the pinned connection.
+    pub(crate) async fn fetch_all(&mut self, sql: &str) -> Result<Vec<MySqlRow>> {
+        let client = self.client;
+        let conn = self.conn();
+        client
+            .timed_sql(OpKind::Read, sql, sqlx::query(sql).fetch_all(&mut **conn))
+            .await
+    }
+
+    /// Fetch all rows for a query with bound parameters on the pinned connection.
+    pub(crate) async fn fetch_query(
+        &mut self,
+        query: Query<'_, MySql, MySqlArguments>,
+    ) -> Result<Vec<MySqlRow>> {
+        let client = self.client;
+        let sql = query.sql();
+        let conn = self.conn();
+        client
+            .timed_sql(OpKind::Read, sql, query.fetch_all(&mut **conn))
+            .await
+    }
+
+    /// Run `reset_sql` and return the connection to the pool, or detach it if
+    /// the reset fails.
+    pub(crate) async fn release(mut self, reset_sql: &str) {
+        if let Some(mut conn) = self.conn.take()
+            && sqlx::query(reset_sql).execute(&mut *conn).await.is_err()
+        {
+            drop(conn.detach());
+        }
+    }
+}
+
+impl Drop for PinnedSession<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+/// Connection used by one collection write call (`add`/`update`/`upsert`).
+///
+/// By default the statements run inside a transaction that
+/// [`WriteSession::finish`] commits on success and rolls back on error (or on
+/// drop, if the call is cancelled). Without a transaction they run on a single
+/// pooled connection in autocommit mode.
+pub(crate) struct WriteSession<'a> {
+    client: &'a ServerClient,
+    conn: WriteConn,
+    /// In-flight slot taken when the session starts and held to its end, so
+    /// statements and the COMMIT never queue while holding row locks.
+    _slot: InflightGuard<'a>,
+}
+
+enum WriteConn {
+    Transaction(Transaction<'static, MySql>),
+    Autocommit(PoolConnection<MySql>),
+}
+
+impl WriteSession<'_> {
+    fn conn(&mut self) -> &mut MySqlConnection {
+        match &mut self.conn {
+            WriteConn::Transaction(tx) => tx,
+            WriteConn::Autocommit(conn) => conn,
+        }
+    }
+
+    pub(crate) async fn execute(
+        &mut self,
+        query: Query<'_, MySql, MySqlArguments>,
+    ) -> Result<MySqlQueryResult> {
+        let client = self.client;
+        let sql = query.sql();
+        client
+            .admitted_sql(OpKind::Write, sql, query.execute(self.conn()))
+            .await
+    }
+
+    pub(crate) async fn fetch_all(
+        &mut self,
+        query: Query<'_, MySql, MySqlArguments>,
+    ) -> Result<Vec<MySqlRow>> {
+        let client = self.client;
+        let sql = query.sql();
+        client
+            .admitted_sql(OpKind::Read, sql, query.fetch_all(self.conn()))
+            .await
+    }
+
+    /// Commit (on `Ok`) or roll back (on `Err`) and pass `result` through.
+    /// After a rollback nothing was stored, so a [`SeekDbError::PartialWrite`]
+    /// is unwrapped to its cause.
+    pub(crate) async fn finish<T>(self, result: Result<T>) -> Result<T> {
+        let WriteConn::Transaction(tx) = self.conn else {
+            return result;
+        };
+        match result {
+            Ok(value) => {
+                self.client
+                    .timed(OpKind::Write, tx.commit())
+                    .await
+                    .map_err(SeekDbError::from)?;
+                Ok(value)
+            }
+            Err(err) => {
+                let _ = tx.rollback().await;
+                match err {
+                    SeekDbError::PartialWrite { source, .. } => Err(*source),
+                    other => Err(other),
+                }
+            }
+        }
+    }
 }
 
 impl ServerClient {
     /// Build a client from a `ServerConfig`.
     pub async fn from_config(config: ServerConfig) -> Result<Self> {
-        Self::connect_internal(
-            &config.host,
-            config.port,
-            &config.tenant,
-            &config.database,
-            &config.user,
-            &config.password,
-            config.max_connections,
-        )
-        .await
+        Self::connect_internal(config).await
     }
 
     pub async fn from_env() -> Result<Self> {
@@ -54,6 +305,11 @@ impl ServerClient {
         Self::from_config(config).await
     }
 
+    /// Connect using a URL; see [`ServerConfig::from_url`] for the format.
+    pub async fn from_url(url: &str) -> Result<Self> {
+        Self::from_config(ServerConfig::from_url(url)?).await
+    }
+
     pub fn pool(&self) -> &MySqlPool {
         &self.pool
     }
@@ -66,24 +322,324 @@ impl ServerClient {
         &self.database
     }
 
+    /// Client for `tenant`, using this client's host, credentials and database.
+    ///
+    /// OceanBase binds a connection to one tenant (`user@tenant`), so other
+    /// tenants get their own pool, opened on first use and cached on this
+    /// client. The connecting user needs access to that tenant; otherwise a
+    /// [`SeekDbError::Connection`] naming the tenant is returned.
+    pub async fn tenant_client(&self, tenant: &str) -> Result<ServerClient> {
+        if tenant == self.tenant {
+            return Ok(self.clone());
+        }
+        if let Some(client) = self.tenant_clients_lock().get(tenant) {
+            return Ok(client.clone().with_settings_of(self));
+        }
+
+        let mut config = (*self.config).clone();
+        config.tenant = tenant.to_string();
+        #[cfg_attr(not(feature = "stats"), allow(unused_mut))]
+        let mut client = Self::connect_internal(config).await.map_err(|e| match e {
+            SeekDbError::Connection(msg) => SeekDbError::Connection(format!(
+                "tenant '{tenant}' is not accessible as user '{}': {msg}",
+                self.config.user
+            )),
+            other => other,
+        })?;
+        #[cfg(feature = "stats")]
+        {
+            client.stats = Arc::clone(&self.stats);
+        }
+
+        Ok(self
+            .tenant_clients_lock()
+            .entry(tenant.to_string())
+            .or_insert(client)
+            .clone()
+            .with_settings_of(self))
+    }
+
+    /// Apply `other`'s client-side settings, sharing its in-flight limit so
+    /// tenant clients count against the client they were opened from.
+    fn with_settings_of(mut self, other: &ServerClient) -> Self {
+        self.inflight = Arc::clone(&other.inflight);
+        self.with_safe_updates(other.safe_updates)
+            .with_verbose_errors(other.verbose_errors)
+            .with_max_rows(other.max_rows)
+            .with_json_dialect(other.json_dialect)
+    }
+
+    fn tenant_clients_lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ServerClient>> {
+        self.tenant_clients
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
     pub fn builder() -> ServerClientBuilder {
         ServerClientBuilder::new()
     }
 
+    /// Check that the server is reachable by running `SELECT 1`, e.g. for a
+    /// readiness probe. Any failure is reported as [`SeekDbError::Connection`].
+    ///
+    /// The probe bypasses [`with_max_inflight`](Self::with_max_inflight): a
+    /// client at its limit is busy, not down, so `ping` neither waits for a
+    /// slot nor fails with [`SeekDbError::Overloaded`].
+    pub async fn ping(&self) -> Result<()> {
+        self.timed(OpKind::Read, sqlx::query("SELECT 1").execute(&self.pool))
+            .await
+            .map(|_| ())
+            .map_err(|e| SeekDbError::Connection(e.to_string()))
+    }
+
+    /// Like MySQL's `--safe-updates`: when enabled, [`execute`](Self::execute)
+    /// and [`fetch_all`](Self::fetch_all) reject any `UPDATE` or `DELETE`
+    /// statement without a `WHERE` clause instead of sending it. Collection
+    /// methods are unaffected; they always scope their writes.
+    pub fn with_safe_updates(mut self, enabled: bool) -> Self {
+        self.safe_updates = enabled;
+        self
+    }
+
+    pub fn safe_updates(&self) -> bool {
+        self.safe_updates
+    }
+
+    /// When enabled, [`SeekDbError::Sql`] and [`SeekDbError::Database`]
+    /// errors carry the statement that failed, for debugging. Bound values
+    /// never appear in it and inline string literals are masked, but table
+    /// and column names do, so keep this off where errors reach end users.
+    pub fn with_verbose_errors(mut self, enabled: bool) -> Self {
+        self.verbose_errors = enabled;
+        self
+    }
+
+    pub fn verbose_errors(&self) -> bool {
+        self.verbose_errors
+    }
+
+    /// MySQL only accepts `OFFSET` after a `LIMIT`, so reads given an offset
+    /// but no limit (`Collection::get`, `list_collections`, `list_databases`)
+    /// send `LIMIT max_rows` (default [`DEFAULT_MAX_ROWS`]). Lower it for
+    /// servers that reject large limits; rows past it are not returned.
+    pub fn with_max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    pub fn max_rows(&self) -> u64 {
+        self.max_rows
+    }
+
+    /// Select how collection filters and `then_by` ordering read metadata
+    /// values (default [`JsonDialect::Function`]). The dialect reported by
+    /// [`capabilities`](Self::capabilities) is one the server accepts.
+    pub fn with_json_dialect(mut self, dialect: JsonDialect) -> Self {
+        self.json_dialect = dialect;
+        self
+    }
+
+    pub fn json_dialect(&self) -> JsonDialect {
+        self.json_dialect
+    }
+
+    /// Bound the statements this client and its clones run at once to `max`
+    /// (at least 1), independently of the pool's `max_connections`. Once
+    /// `max` are running, further statements wait or fail with
+    /// [`SeekDbError::Overloaded`] according to `policy`. Collection writes
+    /// (`add`, `update`, `upsert`) take one slot when they start and keep it
+    /// through their COMMIT or ROLLBACK, so a transaction is never rejected
+    /// or queued halfway. Other calls take one slot per statement.
+    pub fn with_max_inflight(mut self, max: usize, policy: OverloadPolicy) -> Self {
+        self.inflight = Arc::new(Inflight::limited(max.max(1), policy));
+        self
+    }
+
+    /// The limit set by [`with_max_inflight`](Self::with_max_inflight), if any.
+    pub fn max_inflight(&self) -> Option<usize> {
+        self.inflight.limit.as_ref().map(|limit| limit.max)
+    }
+
+    /// How many independent statements one call should run at once: the
+    /// pool's `max_connections`, capped by [`max_inflight`](Self::max_inflight).
+    pub(crate) fn default_concurrency(&self) -> usize {
+        let connections = self.pool.options().get_max_connections() as usize;
+        self.max_inflight()
+            .map_or(connections, |max| connections.min(max))
+            .max(1)
+    }
+
+    /// Number of statements this client and its clones are running right now.
+    pub fn inflight(&self) -> usize {
+        self.inflight.current.load(Ordering::Relaxed)
+    }
+
+    /// Take an in-flight slot for one statement, waiting or failing with
+    /// [`SeekDbError::Overloaded`] when the limit is reached.
+    pub(crate) async fn admit(&self) -> Result<InflightGuard<'_>> {
+        self.inflight.admit().await
+    }
+
+    /// Query the server's version and probe which [`JsonDialect`] it
+    /// evaluates correctly, preferring [`JsonDialect::Function`]. Probing
+    /// does not change this client; pass the result to
+    /// [`with_json_dialect`](Self::with_json_dialect) to use it.
+    pub async fn capabilities(&self) -> Result<ServerCapabilities> {
+        let sql = "SELECT VERSION()";
+        let row = self
+            .timed_sql(OpKind::Read, sql, sqlx::query(sql).fetch_one(&self.pool))
+            .await?;
+        let version: String = row.try_get(0)?;
+
+        let mut json_dialect = JsonDialect::default();
+        for dialect in [JsonDialect::Function, JsonDialect::Operator] {
+            let sql = json_dialect_probe(dialect);
+            let _slot = self.admit().await?;
+            let passed = self
+                .timed(OpKind::Read, sqlx::query(&sql).fetch_one(&self.pool))
+                .await
+                .and_then(|row| row.try_get::<Option<i64>, _>(0))
+                .is_ok_and(|v| v == Some(1));
+            if passed {
+                json_dialect = dialect;
+                break;
+            }
+        }
+
+        Ok(ServerCapabilities {
+            version,
+            json_dialect,
+        })
+    }
+
+    /// Convert a driver error from running `sql`, attaching the statement
+    /// when verbose errors are enabled.
+    pub(crate) fn sql_error(&self, err: sqlx::Error, sql: &str) -> SeekDbError {
+        let err = SeekDbError::from(err);
+        if self.verbose_errors {
+            err.with_statement(sql)
+        } else {
+            err
+        }
+    }
+
+    fn check_safe_update(&self, sql: &str) -> Result<()> {
+        if self.safe_updates && lacks_where_clause(sql) {
+            return Err(SeekDbError::InvalidInput(format!(
+                "safe updates: UPDATE/DELETE without WHERE rejected: {sql}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Execute a SQL statement that does not return rows.
+    ///
+    /// With retries configured, a failure to obtain a connection is retried;
+    /// a connection lost mid-statement is only retried for read-only
+    /// statements, since a write may already have been applied.
     pub async fn execute(&self, sql: &str) -> Result<sqlx::mysql::MySqlQueryResult> {
-        sqlx::query(sql)
-            .execute(&self.pool)
-            .await
-            .map_err(Into::into)
+        self.check_safe_update(sql)?;
+        let _slot = self.admit().await?;
+        with_retry(
+            self.config.max_retries,
+            self.config.base_backoff,
+            is_read_only(sql),
+            || self.timed(OpKind::Write, sqlx::query(sql).execute(&self.pool)),
+        )
+        .await
+        .map_err(|e| self.sql_error(e, sql))
     }
 
-    /// Fetch all rows for the given SQL query.
+    /// Fetch all rows for the given SQL query. Retries as for
+    /// [`execute`](Self::execute).
     pub async fn fetch_all(&self, sql: &str) -> Result<Vec<sqlx::mysql::MySqlRow>> {
-        sqlx::query(sql)
-            .fetch_all(&self.pool)
+        self.check_safe_update(sql)?;
+        let _slot = self.admit().await?;
+        with_retry(
+            self.config.max_retries,
+            self.config.base_backoff,
+            is_read_only(sql),
+            || self.timed(OpKind::Read, sqlx::query(sql).fetch_all(&self.pool)),
+        )
+        .await
+        .map_err(|e| self.sql_error(e, sql))
+    }
+
+    /// Percentile summary (p50/p95/p99) of the read and write latencies observed
+    /// by this client and every clone/collection derived from it.
+    #[cfg(feature = "stats")]
+    pub fn latency_snapshot(&self) -> LatencySnapshot {
+        self.stats.snapshot()
+    }
+
+    /// The same latencies rendered in the Prometheus text exposition format,
+    /// ready to be served from the application's own `/metrics` handler.
+    #[cfg(feature = "stats")]
+    pub fn metrics_text(&self) -> String {
+        self.stats.render_prometheus()
+    }
+
+    /// Check out one pooled connection for a statement sequence that depends on
+    /// session state (user variables such as `@search_parm`, session settings).
+    pub(crate) async fn pinned_session(&self) -> Result<PinnedSession<'_>> {
+        let conn = self.pool.acquire().await?;
+        Ok(PinnedSession {
+            client: self,
+            conn: Some(conn),
+        })
+    }
+
+    /// Start a [`WriteSession`], inside a transaction when `transactional` is set.
+    pub(crate) async fn write_session(&self, transactional: bool) -> Result<WriteSession<'_>> {
+        let slot = self.admit().await?;
+        let conn = if transactional {
+            WriteConn::Transaction(self.pool.begin().await?)
+        } else {
+            WriteConn::Autocommit(self.pool.acquire().await?)
+        };
+        Ok(WriteSession {
+            client: self,
+            conn,
+            _slot: slot,
+        })
+    }
+
+    /// [`timed`](Self::timed) for a driver call running `sql` once
+    /// [`admit`](Self::admit)ted, converting its error with
+    /// [`sql_error`](Self::sql_error).
+    pub(crate) async fn timed_sql<T, F>(&self, kind: OpKind, sql: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = std::result::Result<T, sqlx::Error>>,
+    {
+        let _slot = self.admit().await?;
+        self.admitted_sql(kind, sql, fut).await
+    }
+
+    /// [`timed_sql`](Self::timed_sql) for a caller that already holds an
+    /// in-flight slot, such as a [`WriteSession`].
+    pub(crate) async fn admitted_sql<T, F>(&self, kind: OpKind, sql: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = std::result::Result<T, sqlx::Error>>,
+    {
+        self.timed(kind, fut)
             .await
-            .map_err(Into::into)
+            .map_err(|e| self.sql_error(e, sql))
+    }
+
+    /// Await `fut`, recording its latency under `kind` when the `stats` feature is enabled.
+    pub(crate) async fn timed<F: Future>(&self, kind: OpKind, fut: F) -> F::Output {
+        #[cfg(feature = "stats")]
+        {
+            let start = std::time::Instant::now();
+            let out = fut.await;
+            self.stats.record(kind, start.elapsed());
+            out
+        }
+        #[cfg(not(feature = "stats"))]
+        {
+            let _ = kind;
+            fut.await
+        }
     }
 
     pub async fn create_collection<Ef: EmbeddingFunction + 'static>(
@@ -91,16 +647,56 @@ impl ServerClient {
         name: &str,
         config: Option<HnswConfig>,
         embedding_function: Option<Ef>,
+    ) -> Result<Collection<Ef>> {
+        self.create_collection_with_metadata(name, config, embedding_function, None)
+            .await
+    }
+
+    /// Like [`create_collection`](Self::create_collection), additionally storing
+    /// `metadata` (as JSON in the table comment) so that `get_collection` can
+    /// return it from [`Collection::metadata`] later.
+    pub async fn create_collection_with_metadata<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        config: Option<HnswConfig>,
+        embedding_function: Option<Ef>,
+        metadata: Option<serde_json::Value>,
     ) -> Result<Collection<Ef>> {
         CollectionNames::validate(name)?;
 
         let cfg = config.ok_or_else(|| {
             SeekDbError::Config("HnswConfig must be provided when creating a collection".into())
         })?;
+        if let Some(collation) = &cfg.document_collation {
+            validate_collation(collation)?;
+        }
+        validate_dimension("embedding", cfg.dimension)?;
+        validate_vector_fields(&cfg.vector_fields)?;
+
+        let comment = metadata.as_ref().map(serde_json::to_string).transpose()?;
+        if let Some(comment) = &comment
+            && comment.len() > MAX_TABLE_COMMENT_LEN
+        {
+            return Err(SeekDbError::InvalidInput(format!(
+                "collection metadata is {} bytes as JSON; at most {MAX_TABLE_COMMENT_LEN} are allowed",
+                comment.len()
+            )));
+        }
 
         let table_name = CollectionNames::table_name(name);
-        let sql = build_create_table_sql(&table_name, cfg.dimension, cfg.distance);
-        self.execute(&sql).await?;
+        let sql = build_create_table_sql(&table_name, &cfg, comment.as_deref());
+        let _slot = self.admit().await?;
+        self.timed(OpKind::Write, sqlx::query(&sql).execute(&self.pool))
+            .await
+            .map_err(|e| {
+                if is_table_exists_error(&e) {
+                    SeekDbError::InvalidInput(format!(
+                        "collection already exists: {name}; use get_collection or get_or_create_collection"
+                    ))
+                } else {
+                    self.sql_error(e, &sql)
+                }
+            })?;
 
         Ok(Collection::new(
             std::sync::Arc::new(self.clone()),
@@ -109,59 +705,51 @@ impl ServerClient {
             cfg.dimension,
             cfg.distance,
             embedding_function,
-            None,
-        ))
+            metadata,
+        )
+        .with_vector_fields(cfg.vector_fields))
     }
 
-    pub async fn get_collection<Ef: EmbeddingFunction + 'static>(
+    /// [`create_collection`](Self::create_collection) under `tenant` (the
+    /// client's own tenant when `None`); see [`tenant_client`](Self::tenant_client).
+    pub async fn create_collection_in_tenant<Ef: EmbeddingFunction + 'static>(
         &self,
         name: &str,
+        config: Option<HnswConfig>,
         embedding_function: Option<Ef>,
+        tenant: Option<&str>,
     ) -> Result<Collection<Ef>> {
-        CollectionNames::validate(name)?;
-
-        let table_name = CollectionNames::table_name(name);
-
-        // Check existence by describing the table
-        let describe_sql = format!("DESCRIBE `{table_name}`");
-        let describe = self.fetch_all(&describe_sql).await?;
-        if describe.is_empty() {
-            return Err(SeekDbError::NotFound(format!(
-                "collection not found: {name}"
-            )));
-        }
-
-        // Extract dimension from embedding column type
-        let mut dimension: Option<u32> = None;
-        for row in describe {
-            let field: String = row.try_get("Field").unwrap_or_default();
-            if field == "embedding" {
-                let type_str: String = row.try_get("Type").unwrap_or_default();
-                if let Some(dim) = parse_dimension(&type_str) {
-                    dimension = Some(dim);
-                }
-                break;
-            }
-        }
+        let client = self.tenant_client(self.effective_tenant(tenant)).await?;
+        client
+            .create_collection(name, config, embedding_function)
+            .await
+    }
 
-        // Extract distance from SHOW CREATE TABLE
-        let create_sql = format!("SHOW CREATE TABLE `{table_name}`");
-        let create_rows = self.fetch_all(&create_sql).await?;
-        let mut distance: DistanceMetric = DistanceMetric::L2;
-        if let Some(row) = create_rows.first() {
-            let create_stmt: String = row
-                .try_get("Create Table")
-                .or_else(|_| row.try_get(1))
-                .unwrap_or_default();
-            if let Some(d) = parse_distance(&create_stmt) {
-                distance = d;
-            }
-        }
+    /// [`get_collection`](Self::get_collection) under `tenant` (the client's
+    /// own tenant when `None`); see [`tenant_client`](Self::tenant_client).
+    pub async fn get_collection_in_tenant<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        embedding_function: Option<Ef>,
+        tenant: Option<&str>,
+    ) -> Result<Collection<Ef>> {
+        let client = self.tenant_client(self.effective_tenant(tenant)).await?;
+        client.get_collection(name, embedding_function).await
+    }
 
-        let dimension = dimension.ok_or_else(|| {
+    pub async fn get_collection<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        embedding_function: Option<Ef>,
+    ) -> Result<Collection<Ef>> {
+        let layout = self.table_layout(name).await?;
+        let distance = parse_distance(&layout.create_stmt).unwrap_or(DistanceMetric::L2);
+        let dimension = layout.dimension().ok_or_else(|| {
             SeekDbError::Config("cannot detect dimension from collection schema".into())
         })?;
 
+        let metadata = self.collection_metadata(name).await?;
+
         Ok(Collection::new(
             std::sync::Arc::new(self.clone()),
             name.to_string(),
@@ -169,8 +757,102 @@ impl ServerClient {
             dimension,
             distance,
             embedding_function,
-            None,
-        ))
+            metadata,
+        )
+        .with_vector_fields(layout.vector_fields()))
+    }
+
+    /// Read the schema of collection `name`, including its current row count.
+    pub(crate) async fn describe_collection(&self, name: &str) -> Result<CollectionSchema> {
+        let layout = self.table_layout(name).await?;
+        let dimension = layout.dimension().ok_or_else(|| {
+            SeekDbError::Config("cannot detect dimension from collection schema".into())
+        })?;
+
+        let table_name = CollectionNames::table_name(name);
+        let count_sql = format!("SELECT COUNT(*) AS cnt FROM `{table_name}`");
+        let row = self
+            .timed_sql(
+                OpKind::Read,
+                &count_sql,
+                sqlx::query(&count_sql).fetch_one(&self.pool),
+            )
+            .await?;
+        let row_count: i64 = row.try_get("cnt").unwrap_or(0);
+
+        let index_params = parse_vector_index_options(&layout.create_stmt).unwrap_or_default();
+        let fulltext_parser = parse_fulltext_parser(&layout.create_stmt);
+        Ok(CollectionSchema {
+            name: name.to_string(),
+            dimension,
+            distance: parse_distance(&layout.create_stmt).unwrap_or(DistanceMetric::L2),
+            index_type: index_params.get("type").cloned(),
+            index_params,
+            has_fulltext: layout.create_stmt.to_lowercase().contains("fulltext"),
+            fulltext_parser,
+            columns: layout.columns.into_iter().map(|(name, _)| name).collect(),
+            row_count: row_count.max(0) as u64,
+        })
+    }
+
+    /// Collection metadata stored as JSON in the table comment, if any.
+    async fn collection_metadata(&self, name: &str) -> Result<Option<serde_json::Value>> {
+        let sql = "SELECT TABLE_COMMENT FROM information_schema.TABLES \
+                   WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? LIMIT 1";
+        let row = self
+            .timed_sql(
+                OpKind::Read,
+                sql,
+                sqlx::query(sql)
+                    .bind(&self.database)
+                    .bind(CollectionNames::table_name(name))
+                    .fetch_optional(&self.pool),
+            )
+            .await?;
+        let comment: Option<String> = row.and_then(|r| r.try_get(0).ok());
+        // Tables created without metadata (or by other tools) may carry any
+        // comment; only a JSON value is treated as collection metadata.
+        Ok(comment
+            .filter(|c| !c.is_empty())
+            .and_then(|c| serde_json::from_str(&c).ok()))
+    }
+
+    /// DESCRIBE + SHOW CREATE TABLE for collection `name`; `NotFound` if it is missing.
+    async fn table_layout(&self, name: &str) -> Result<TableLayout> {
+        CollectionNames::validate(name)?;
+        let table_name = CollectionNames::table_name(name);
+
+        let describe = self.fetch_all(&format!("DESCRIBE `{table_name}`")).await?;
+        if describe.is_empty() {
+            return Err(SeekDbError::NotFound(format!(
+                "collection not found: {name}"
+            )));
+        }
+        let columns = describe
+            .iter()
+            .map(|row| {
+                let field: String = row.try_get("Field").unwrap_or_default();
+                let type_str: String = row.try_get("Type").unwrap_or_default();
+                (field, type_str)
+            })
+            .collect();
+
+        let create_rows = self
+            .fetch_all(&format!("SHOW CREATE TABLE `{table_name}`"))
+            .await?;
+        let create_stmt = create_rows
+            .first()
+            .map(|row| {
+                row.try_get("Create Table")
+                    .or_else(|_| row.try_get(1))
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        Ok(TableLayout {
+            columns,
+            create_stmt,
+        })
     }
 
     pub async fn delete_collection(&self, name: &str) -> Result<()> {
@@ -182,53 +864,193 @@ impl ServerClient {
         Ok(())
     }
 
-    pub async fn list_collections(&self) -> Result<Vec<String>> {
-        let prefix = CollectionNames::TABLE_PREFIX;
-        let like_pattern = format!("{prefix}%");
-        let show_sql = format!("SHOW TABLES LIKE '{like_pattern}'");
+    /// Rename collection `old` to `new` with a single `RENAME TABLE`, which
+    /// the server applies atomically: readers see either the old name or the
+    /// new one, never neither.
+    ///
+    /// Fails with [`SeekDbError::NotFound`] if `old` does not exist and with
+    /// [`SeekDbError::InvalidInput`] if `new` already does. `Collection`
+    /// handles opened under the old name stop working; reopen them with
+    /// [`get_collection`](Self::get_collection).
+    pub async fn rename_collection(&self, old: &str, new: &str) -> Result<()> {
+        CollectionNames::validate(old)?;
+        CollectionNames::validate(new)?;
+
+        if !self.has_collection(old).await? {
+            return Err(SeekDbError::NotFound(format!(
+                "collection not found: {old}"
+            )));
+        }
+        if self.has_collection(new).await? {
+            return Err(SeekDbError::InvalidInput(format!(
+                "collection already exists: {new}"
+            )));
+        }
 
-        let rows = match self.fetch_all(&show_sql).await {
+        let sql = format!(
+            "RENAME TABLE `{}` TO `{}`",
+            CollectionNames::table_name(old),
+            CollectionNames::table_name(new)
+        );
+        let _slot = self.admit().await?;
+        self.timed(OpKind::Write, sqlx::query(&sql).execute(&self.pool))
+            .await
+            .map_err(|e| {
+                // Created concurrently after the check above.
+                if is_table_exists_error(&e) {
+                    SeekDbError::InvalidInput(format!("collection already exists: {new}"))
+                } else {
+                    self.sql_error(e, &sql)
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Collection names in the current database, sorted by name so that
+    /// `limit`/`offset` pages are stable.
+    ///
+    /// Paging reads `information_schema`; only an unpaged call falls back to
+    /// `SHOW TABLES` on servers where it is not readable.
+    pub async fn list_collections(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<String>> {
+        self.list_collections_like("%", limit, offset).await
+    }
+
+    /// Collection names matching the SQL `LIKE` pattern `pattern` (e.g.
+    /// `docs_%`), sorted by name. `%` matches any run of characters and `_`
+    /// any single one; escape them as `\%` and `\_` to match literally.
+    pub async fn list_collections_matching(&self, pattern: &str) -> Result<Vec<String>> {
+        self.list_collections_like(pattern, None, None).await
+    }
+
+    /// Names of collections whose name matches `pattern`; the table prefix is
+    /// prepended for the lookup and stripped from the results.
+    async fn list_collections_like(
+        &self,
+        pattern: &str,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<String>> {
+        let mut sql = String::from(
+            "SELECT TABLE_NAME FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME LIKE ? ORDER BY TABLE_NAME",
+        );
+        append_limit_offset(&mut sql, limit, offset, self.max_rows);
+        let like_pattern = format!("{}{pattern}", CollectionNames::TABLE_PREFIX);
+        let rows = self
+            .timed_sql(
+                OpKind::Read,
+                &sql,
+                sqlx::query(&sql)
+                    .bind(&self.database)
+                    .bind(&like_pattern)
+                    .fetch_all(&self.pool),
+            )
+            .await;
+
+        let rows = match rows {
             Ok(rows) => rows,
-            Err(_) => {
-                // Fallback to information_schema if SHOW TABLES is not supported
-                let sql = format!(
-                    "SELECT TABLE_NAME FROM information_schema.TABLES \
-                     WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME LIKE '{}'",
-                    self.database, like_pattern
+            // Fall back to SHOW TABLES only when information_schema is denied
+            // or unsupported. SHOW TABLES takes no LIMIT/OFFSET, so a paged
+            // call reports the error instead of listing every table.
+            Err(e) if limit.is_none() && offset.is_none() && is_schema_unavailable(&e) => {
+                let mut names = collection_names(
+                    self.fetch_all(&format!(
+                        "SHOW TABLES LIKE '{}'",
+                        like_pattern.replace('\\', "\\\\").replace('\'', "''")
+                    ))
+                    .await?,
                 );
-                self.fetch_all(&sql).await?
+                names.sort();
+                return Ok(names);
             }
+            Err(e) => return Err(e),
         };
+        Ok(collection_names(rows))
+    }
 
-        let mut names = Vec::new();
+    /// Name, dimension, distance and estimated row count of every collection
+    /// in the current database, ordered by name.
+    ///
+    /// Names, dimensions and row counts come from one `information_schema`
+    /// query; the distance metric is only recorded in the vector index
+    /// definition, so each collection costs one `SHOW CREATE TABLE` on top.
+    pub async fn list_collections_detailed(&self) -> Result<Vec<CollectionInfo>> {
+        let sql = "SELECT t.TABLE_NAME AS table_name, c.COLUMN_TYPE AS column_type, \
+                   t.TABLE_ROWS AS table_rows \
+                   FROM information_schema.TABLES t \
+                   JOIN information_schema.COLUMNS c \
+                   ON c.TABLE_SCHEMA = t.TABLE_SCHEMA AND c.TABLE_NAME = t.TABLE_NAME \
+                   AND c.COLUMN_NAME = 'embedding' \
+                   WHERE t.TABLE_SCHEMA = ? AND t.TABLE_NAME LIKE ? \
+                   ORDER BY t.TABLE_NAME";
+        let rows = self
+            .timed_sql(
+                OpKind::Read,
+                sql,
+                sqlx::query(sql)
+                    .bind(&self.database)
+                    .bind(format!("{}%", CollectionNames::TABLE_PREFIX))
+                    .fetch_all(&self.pool),
+            )
+            .await?;
+
+        let mut infos = Vec::with_capacity(rows.len());
         for row in rows {
-            // SHOW TABLES column name varies; take first column
-            if let Ok(table_name) = row.try_get::<String, _>(0) {
-                if let Some(name) = table_name.strip_prefix(CollectionNames::TABLE_PREFIX) {
-                    names.push(name.to_string());
-                }
-            }
+            let table_name: String = row.try_get("table_name")?;
+            let Some(name) = table_name.strip_prefix(CollectionNames::TABLE_PREFIX) else {
+                continue;
+            };
+            let column_type: String = row.try_get("column_type").unwrap_or_default();
+            let Some(dimension) = parse_dimension(&column_type) else {
+                continue;
+            };
+            let row_count = row.try_get::<Option<u64>, _>("table_rows").ok().flatten();
+
+            let create_rows = self
+                .fetch_all(&format!("SHOW CREATE TABLE `{table_name}`"))
+                .await?;
+            let create_stmt: String = create_rows
+                .first()
+                .and_then(|row| row.try_get(1).ok())
+                .unwrap_or_default();
+            infos.push(CollectionInfo {
+                name: name.to_string(),
+                dimension,
+                distance: parse_distance(&create_stmt).unwrap_or(DistanceMetric::L2),
+                row_count,
+            });
         }
-        Ok(names)
+        Ok(infos)
     }
 
     pub async fn has_collection(&self, name: &str) -> Result<bool> {
         CollectionNames::validate(name)?;
 
         let table_name = CollectionNames::table_name(name);
-        let sql = format!(
-            "SELECT 1 FROM information_schema.TABLES \
-             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? LIMIT 1"
-        );
-        let exists = sqlx::query(&sql)
-            .bind(&self.database)
-            .bind(&table_name)
-            .fetch_optional(&self.pool)
+        let sql = "SELECT 1 FROM information_schema.TABLES \
+                   WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? LIMIT 1";
+        let exists = self
+            .timed_sql(
+                OpKind::Read,
+                sql,
+                sqlx::query(sql)
+                    .bind(&self.database)
+                    .bind(&table_name)
+                    .fetch_optional(&self.pool),
+            )
             .await?;
         Ok(exists.is_some())
     }
 
     /// Convenience: get if exists, else create.
+    ///
+    /// When the collection exists and `config` is given, its dimension and
+    /// distance must match the existing collection's, or the call fails with
+    /// [`SeekDbError::InvalidInput`] naming the difference.
     pub async fn get_or_create_collection<Ef: EmbeddingFunction + 'static>(
         &self,
         name: &str,
@@ -238,7 +1060,16 @@ impl ServerClient {
         CollectionNames::validate(name)?;
 
         if self.has_collection(name).await? {
-            self.get_collection(name, embedding_function).await
+            let collection = self.get_collection(name, embedding_function).await?;
+            if let Some(cfg) = &config
+                && let Some(problems) =
+                    config_mismatch(collection.dimension(), collection.distance(), cfg)
+            {
+                return Err(SeekDbError::InvalidInput(format!(
+                    "collection {name} exists with a different config: {problems}"
+                )));
+            }
+            Ok(collection)
         } else {
             self.create_collection(name, config, embedding_function)
                 .await
@@ -246,7 +1077,7 @@ impl ServerClient {
     }
 
     pub async fn count_collection(&self) -> Result<usize> {
-        let collections = self.list_collections().await?;
+        let collections = self.list_collections(None, None).await?;
         Ok(collections.len())
     }
 
@@ -259,13 +1090,15 @@ impl ServerClient {
 
     async fn get_database_impl(&self, _name: &str, _tenant: Option<&str>) -> Result<Database> {
         let tenant = self.effective_tenant(_tenant).to_string();
-        let row = sqlx::query(
+        let query = sqlx::query(
             "SELECT SCHEMA_NAME, DEFAULT_CHARACTER_SET_NAME, DEFAULT_COLLATION_NAME \
              FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?",
         )
-        .bind(_name)
-        .fetch_optional(&self.pool)
-        .await?;
+        .bind(_name);
+        let sql = query.sql();
+        let row = self
+            .timed_sql(OpKind::Read, sql, query.fetch_optional(&self.pool))
+            .await?;
 
         let Some(row) = row else {
             return Err(SeekDbError::NotFound(format!(
@@ -298,16 +1131,7 @@ impl ServerClient {
              FROM information_schema.SCHEMATA",
         );
 
-        if let Some(limit) = limit {
-            sql.push_str(&format!(" LIMIT {limit}"));
-        }
-        if let Some(offset) = offset {
-            // MySQL allows OFFSET only when LIMIT exists; use a large limit when missing.
-            if limit.is_none() {
-                sql.push_str(" LIMIT 18446744073709551615");
-            }
-            sql.push_str(&format!(" OFFSET {offset}"));
-        }
+        append_limit_offset(&mut sql, limit, offset, self.max_rows);
 
         let tenant = self.effective_tenant(_tenant).to_string();
         let rows = self.fetch_all(&sql).await?;
@@ -372,51 +1196,230 @@ impl AdminApi for ServerClient {
     }
 }
 
-// Implement the generic SqlBackend abstraction for ServerClient so that
-// higher-level code can depend on SqlBackend instead of this concrete type.
-#[async_trait]
-impl SqlBackend for ServerClient {
-    type Row = sqlx::mysql::MySqlRow;
-
-    async fn execute(&self, sql: &str) -> crate::error::Result<()> {
-        // Delegate to the inherent execute and discard the driver-specific result.
-        ServerClient::execute(self, sql).await.map(|_| ())
+impl ServerClient {
+    fn effective_tenant<'a>(&'a self, tenant: Option<&'a str>) -> &'a str {
+        tenant.unwrap_or(&self.tenant)
     }
+}
 
-    async fn fetch_all(&self, sql: &str) -> crate::error::Result<Vec<Self::Row>> {
-        ServerClient::fetch_all(self, sql).await
-    }
+/// MySQL `ER_TABLE_EXISTS_ERROR`, also used by OceanBase.
+const ER_TABLE_EXISTS: u16 = 1050;
+
+/// Errors meaning `information_schema` cannot be read on this server: access
+/// denied to the database, table or privilege (1044, 1142, 1227), a missing
+/// table (1146) or an unsupported feature (1235).
+const SCHEMA_UNAVAILABLE_CODES: [u16; 5] = [1044, 1142, 1146, 1227, 1235];
 
-    fn mode(&self) -> &'static str {
-        "server"
+/// Whether `err` is an `information_schema` query failing for lack of access
+/// or support, rather than for load or connectivity.
+fn is_schema_unavailable(err: &SeekDbError) -> bool {
+    err.database_code()
+        .is_some_and(|code| SCHEMA_UNAVAILABLE_CODES.contains(&code))
+}
+
+/// Whether `err` is the server rejecting `CREATE TABLE` for an existing table.
+fn is_table_exists_error(err: &sqlx::Error) -> bool {
+    let Some(db_err) = err.as_database_error() else {
+        return false;
+    };
+    match db_err.try_downcast_ref::<MySqlDatabaseError>() {
+        Some(mysql) => mysql.number() == ER_TABLE_EXISTS,
+        None => db_err.code().as_deref() == Some("42S01"),
     }
 }
 
-impl ServerClient {
-    fn effective_tenant<'a>(&'a self, tenant: Option<&'a str>) -> &'a str {
-        tenant.unwrap_or(&self.tenant)
+/// Collection names from rows whose first column is a table name; tables
+/// without the collection prefix are skipped.
+fn collection_names(rows: Vec<MySqlRow>) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| row.try_get::<String, _>(0).ok())
+        .filter_map(|table| {
+            table
+                .strip_prefix(CollectionNames::TABLE_PREFIX)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// Statement checking that `dialect` reads a nested number and unquotes a
+/// string from a `metadata` JSON value; it selects 1 when both work.
+fn json_dialect_probe(dialect: JsonDialect) -> String {
+    format!(
+        "SELECT ({} = 2 AND {} = 'x') FROM (SELECT CAST('{{\"a\": {{\"n\": 2}}, \"s\": \"x\"}}' AS JSON) AS metadata) AS probe",
+        dialect.json_extract("a.n"),
+        dialect.json_extract_unquoted("s"),
+    )
+}
+
+/// Default for [`ServerClient::with_max_rows`]: `u32::MAX`, large enough to
+/// mean "all rows" while staying within the range every server accepts.
+pub const DEFAULT_MAX_ROWS: u64 = u32::MAX as u64;
+
+/// Append `LIMIT`/`OFFSET` for an optional page to `sql`. An offset without a
+/// limit gets `LIMIT max_rows`, since MySQL has no bare `OFFSET`.
+pub(crate) fn append_limit_offset(
+    sql: &mut String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    max_rows: u64,
+) {
+    match (limit, offset) {
+        (Some(limit), _) => sql.push_str(&format!(" LIMIT {limit}")),
+        (None, Some(_)) => sql.push_str(&format!(" LIMIT {max_rows}")),
+        (None, None) => {}
+    }
+    if let Some(offset) = offset {
+        sql.push_str(&format!(" OFFSET {offset}"));
     }
 }
 
-fn build_create_table_sql(table_name: &str, dimension: u32, distance: DistanceMetric) -> String {
-    let distance = distance_str(distance);
+/// Longest table comment accepted by the server, in bytes.
+const MAX_TABLE_COMMENT_LEN: usize = 2048;
+
+/// Largest `vector(N)` column the server accepts.
+const MAX_VECTOR_DIMENSION: u32 = 16_000;
+
+/// Differences between an existing collection's dimension/distance and the
+/// requested `config`, as `"dimension 3 != 4; distance cosine != L2"`
+/// (existing value first).
+fn config_mismatch(
+    dimension: u32,
+    distance: DistanceMetric,
+    config: &HnswConfig,
+) -> Option<String> {
+    let problems =
+        dimension_distance_mismatch(dimension, distance, config.dimension, config.distance);
+    (!problems.is_empty()).then(|| problems.join("; "))
+}
+
+fn build_create_table_sql(table_name: &str, config: &HnswConfig, comment: Option<&str>) -> String {
+    let dimension = config.dimension;
+    let mut index_options = format!(
+        "distance={}, type=hnsw, lib=vsag",
+        distance_str(config.distance)
+    );
+    for (name, value) in [
+        ("m", config.m),
+        ("ef_construction", config.ef_construction),
+        ("ef_search", config.ef_search),
+    ] {
+        if let Some(value) = value {
+            index_options.push_str(&format!(", {name}={value}"));
+        }
+    }
     format!(
         "CREATE TABLE `{table_name}` (
             _id varbinary(512) PRIMARY KEY NOT NULL,
-            document text,
-            embedding vector({dimension}),
+            document text{collation},
+            embedding vector({dimension}),{extra_columns}
             metadata json,
             FULLTEXT INDEX idx_fts(document) WITH PARSER ik,
-            VECTOR INDEX idx_vec (embedding) with(distance={distance}, type=hnsw, lib=vsag)
-        ) ORGANIZATION = HEAP;"
+            VECTOR INDEX idx_vec (embedding) with({index_options}){extra_indexes}
+        ) ORGANIZATION = HEAP{comment};",
+        extra_columns = config
+            .vector_fields
+            .iter()
+            .map(|f| format!("\n            `{}` vector({}),", f.name, f.dimension))
+            .collect::<String>(),
+        extra_indexes = config
+            .vector_fields
+            .iter()
+            .map(|f| format!(
+                ",\n            VECTOR INDEX `idx_vec_{0}` (`{0}`) with({index_options})",
+                f.name
+            ))
+            .collect::<String>(),
+        collation = config
+            .document_collation
+            .as_deref()
+            .map(|c| format!(" COLLATE {c}"))
+            .unwrap_or_default(),
+        comment = comment
+            .map(|c| format!(
+                " COMMENT = '{}'",
+                c.replace('\\', "\\\\").replace('\'', "''")
+            ))
+            .unwrap_or_default()
     )
 }
 
+/// Collation names are interpolated into DDL, so only plain identifiers of the
+/// form `charset_name[_suffix...]` (e.g. `utf8mb4_0900_ai_ci`) are allowed.
+fn validate_collation(collation: &str) -> Result<()> {
+    let valid = collation.len() <= 64
+        && collation.contains('_')
+        && collation
+            .split('_')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if valid {
+        Ok(())
+    } else {
+        Err(SeekDbError::InvalidInput(format!(
+            "invalid document collation '{collation}'; expected a name like utf8mb4_unicode_ci"
+        )))
+    }
+}
+
+/// `vector(0)` and oversized columns only fail on the server with an opaque
+/// DDL error, so catch them before any DDL is sent.
+fn validate_dimension(column: &str, dimension: u32) -> Result<()> {
+    if dimension == 0 || dimension > MAX_VECTOR_DIMENSION {
+        return Err(SeekDbError::InvalidInput(format!(
+            "{column} dimension must be between 1 and {MAX_VECTOR_DIMENSION}, got {dimension}"
+        )));
+    }
+    Ok(())
+}
+
+/// Columns a named vector field may not shadow.
+const RESERVED_COLUMNS: [&str; 4] = ["_id", "document", "embedding", "metadata"];
+
+/// Longest vector field name: its index is named `idx_vec_<name>`, and index
+/// identifiers are limited to 64 characters.
+const MAX_VECTOR_FIELD_NAME_LEN: usize = 64 - "idx_vec_".len();
+
+/// Vector field names are interpolated into DDL and query SQL, so only plain
+/// identifiers are accepted; each must be unique and have a dimension.
+fn validate_vector_fields(fields: &[VectorField]) -> Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        let name = &field.name;
+        let valid = !name.is_empty()
+            && name.len() <= MAX_VECTOR_FIELD_NAME_LEN
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(SeekDbError::InvalidInput(format!(
+                "invalid vector field name '{name}'; use up to \
+                 {MAX_VECTOR_FIELD_NAME_LEN} ASCII letters, digits or '_'"
+            )));
+        }
+        if RESERVED_COLUMNS
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(name))
+        {
+            return Err(SeekDbError::InvalidInput(format!(
+                "vector field name '{name}' clashes with a built-in column"
+            )));
+        }
+        if fields[..i]
+            .iter()
+            .any(|f| f.name.eq_ignore_ascii_case(name))
+        {
+            return Err(SeekDbError::InvalidInput(format!(
+                "duplicate vector field '{name}'"
+            )));
+        }
+        validate_dimension(name, field.dimension)?;
+    }
+    Ok(())
+}
+
 fn distance_str(distance: DistanceMetric) -> &'static str {
     match distance {
         DistanceMetric::L2 => "l2",
         DistanceMetric::Cosine => "cosine",
         DistanceMetric::InnerProduct => "inner_product",
+        DistanceMetric::Hamming => "hamming",
+        DistanceMetric::Jaccard => "jaccard",
     }
 }
 
@@ -424,39 +1427,56 @@ fn escape_identifier(name: &str) -> String {
     format!("`{}`", name.replace('`', "``"))
 }
 
-fn connect_url(
-    host: &str,
-    port: u16,
-    tenant: &str,
-    database: &str,
-    user: &str,
-    password: &str,
-) -> String {
-    let user_tenant = format!("{user}@{tenant}");
-    format!("mysql://{user_tenant}:{password}@{host}:{port}/{database}")
+fn connect_options(config: &ServerConfig) -> MySqlConnectOptions {
+    let ssl_mode = match config.ssl_mode {
+        SslMode::Disabled => MySqlSslMode::Disabled,
+        SslMode::Preferred => MySqlSslMode::Preferred,
+        SslMode::Required => MySqlSslMode::Required,
+        SslMode::VerifyCa => MySqlSslMode::VerifyCa,
+        SslMode::VerifyIdentity => MySqlSslMode::VerifyIdentity,
+    };
+    let mut options = MySqlConnectOptions::new()
+        .host(&config.host)
+        .port(config.port)
+        .username(&format!("{}@{}", config.user, config.tenant))
+        .password(&config.password)
+        .database(&config.database)
+        .ssl_mode(ssl_mode);
+    if let Some(ca) = &config.ca_cert_path {
+        options = options.ssl_ca(ca);
+    }
+    options
 }
 
 impl ServerClient {
-    async fn connect_internal(
-        host: &str,
-        port: u16,
-        tenant: &str,
-        database: &str,
-        user: &str,
-        password: &str,
-        max_connections: u32,
-    ) -> Result<Self> {
-        let url = connect_url(host, port, tenant, database, user, password);
-        let pool = MySqlPoolOptions::new()
-            .max_connections(max_connections)
-            .connect(&url)
-            .await
-            .map_err(|e| SeekDbError::Connection(e.to_string()))?;
+    async fn connect_internal(config: ServerConfig) -> Result<Self> {
+        let pool_options = MySqlPoolOptions::new()
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .idle_timeout(config.idle_timeout)
+            .max_lifetime(config.max_lifetime);
+        let connect_options = connect_options(&config);
+        // Connecting sends no statements, so every transient failure is safe
+        // to retry; this lets startup ride out a server that is still booting.
+        let pool = with_retry(config.max_retries, config.base_backoff, true, || {
+            pool_options.clone().connect_with(connect_options.clone())
+        })
+        .await
+        .map_err(|e| SeekDbError::Connection(e.to_string()))?;
 
         Ok(Self {
             pool,
-            tenant: tenant.to_string(),
-            database: database.to_string(),
+            tenant: config.tenant.clone(),
+            database: config.database.clone(),
+            config: Arc::new(config),
+            tenant_clients: Arc::default(),
+            safe_updates: false,
+            verbose_errors: false,
+            max_rows: DEFAULT_MAX_ROWS,
+            json_dialect: JsonDialect::default(),
+            inflight: Arc::default(),
+            #[cfg(feature = "stats")]
+            stats: Arc::new(LatencyStats::new()),
         })
     }
 }
@@ -471,6 +1491,14 @@ impl ServerClientBuilder {
             user: "root".to_string(),
             password: String::new(),
             max_connections: 5,
+            min_connections: 0,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            max_lifetime: DEFAULT_MAX_LIFETIME,
+            max_retries: 0,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            ssl_mode: SslMode::default(),
+            ca_cert_path: None,
+            max_inflight: None,
         }
     }
 
@@ -487,6 +1515,14 @@ impl ServerClientBuilder {
             user: config.user,
             password: config.password,
             max_connections: config.max_connections,
+            min_connections: config.min_connections,
+            idle_timeout: config.idle_timeout,
+            max_lifetime: config.max_lifetime,
+            max_retries: config.max_retries,
+            base_backoff: config.base_backoff,
+            ssl_mode: config.ssl_mode,
+            ca_cert_path: config.ca_cert_path,
+            max_inflight: None,
         })
     }
 
@@ -525,18 +1561,249 @@ impl ServerClientBuilder {
         self
     }
 
+    /// Connections kept open even when idle (default 0).
+    pub fn min_connections(mut self, min_connections: u32) -> Self {
+        self.min_connections = min_connections;
+        self
+    }
+
+    /// Close connections idle for longer than this (default 10 minutes);
+    /// `None` keeps them indefinitely.
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Recycle connections older than this (default 30 minutes); `None`
+    /// never recycles.
+    pub fn max_lifetime(mut self, max_lifetime: Option<Duration>) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Retry transient connection failures up to `max_retries` times with
+    /// exponential backoff starting at `base_backoff` (default: no retries,
+    /// 100ms).
+    pub fn retry(mut self, max_retries: u32, base_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_backoff = base_backoff;
+        self
+    }
+
+    /// TLS mode for connections; defaults to [`SslMode::Preferred`].
+    pub fn ssl_mode(mut self, ssl_mode: SslMode) -> Self {
+        self.ssl_mode = ssl_mode;
+        self
+    }
+
+    /// CA certificate (PEM) used to verify the server under
+    /// [`SslMode::VerifyCa`] / [`SslMode::VerifyIdentity`].
+    pub fn ca_cert_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_cert_path = Some(path.into());
+        self
+    }
+
+    /// Limit concurrent statements; see [`ServerClient::with_max_inflight`].
+    pub fn max_inflight(mut self, max: usize, policy: OverloadPolicy) -> Self {
+        self.max_inflight = Some((max, policy));
+        self
+    }
+
     /// Build a [`ServerClient`] using the current builder configuration.
     pub async fn build(self) -> Result<ServerClient> {
-        ServerClient::connect_internal(
-            &self.host,
-            self.port,
-            &self.tenant,
-            &self.database,
-            &self.user,
-            &self.password,
-            self.max_connections,
-        )
-        .await
+        let client = ServerClient::connect_internal(ServerConfig {
+            host: self.host,
+            port: self.port,
+            tenant: self.tenant,
+            database: self.database,
+            user: self.user,
+            password: self.password,
+            max_connections: self.max_connections,
+            min_connections: self.min_connections,
+            idle_timeout: self.idle_timeout,
+            max_lifetime: self.max_lifetime,
+            max_retries: self.max_retries,
+            base_backoff: self.base_backoff,
+            ssl_mode: self.ssl_mode,
+            ca_cert_path: self.ca_cert_path,
+        })
+        .await?;
+        Ok(match self.max_inflight {
+            Some((max, policy)) => client.with_max_inflight(max, policy),
+            None => client,
+        })
+    }
+}
+
+/// Run `op`, retrying up to `max_retries` times on transient errors with
+/// exponential backoff (`base_backoff`, doubled per attempt).
+///
+/// A pool timeout means the statement never reached the server and is always
+/// retried. An I/O error may have struck after the server received the
+/// statement, so it is retried only when `replay_safe`.
+async fn with_retry<T, F, Fut>(
+    max_retries: u32,
+    base_backoff: Duration,
+    replay_safe: bool,
+    mut op: F,
+) -> std::result::Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, sqlx::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e) if attempt < max_retries && is_transient(&e, replay_safe) => {
+                tokio::time::sleep(base_backoff.saturating_mul(1 << attempt.min(16))).await;
+                attempt += 1;
+            }
+            other => return other,
+        }
+    }
+}
+
+fn is_transient(err: &sqlx::Error, replay_safe: bool) -> bool {
+    match err {
+        sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Io(_) => replay_safe,
+        _ => false,
+    }
+}
+
+/// Whether `sql` starts with a statement that cannot modify data, so that
+/// replaying it after a lost connection is harmless.
+fn is_read_only(sql: &str) -> bool {
+    let keyword: String = sql
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    ["select", "show", "describe", "desc", "explain"]
+        .iter()
+        .any(|k| keyword.eq_ignore_ascii_case(k))
+}
+
+/// Whether any statement in `sql` is an `UPDATE` or `DELETE` with no `WHERE`
+/// keyword of its own. Only words outside parentheses count, so a `WHERE` in
+/// a subquery does not, and a leading `WITH ...` clause is skipped to find
+/// the statement's verb. Quoted strings, quoted identifiers and comments are
+/// skipped too.
+fn lacks_where_clause(sql: &str) -> bool {
+    const VERBS: [&str; 5] = ["select", "insert", "replace", "update", "delete"];
+    let mut chars = sql.chars().peekable();
+    // The statement's verb, once known; `in_with` while inside a CTE prefix.
+    let mut verb: Option<String> = None;
+    let mut in_with = false;
+    let mut has_where = false;
+    let mut depth = 0usize;
+    let mut word = String::new();
+    let unsafe_statement = |verb: &Option<String>, has_where: bool| {
+        !has_where
+            && verb.as_deref().is_some_and(|w| {
+                w.eq_ignore_ascii_case("update") || w.eq_ignore_ascii_case("delete")
+            })
+    };
+    loop {
+        let c = chars.next();
+        if let Some(ch) = c
+            && (ch.is_ascii_alphanumeric() || ch == '_' || ch == '$')
+        {
+            word.push(ch);
+            continue;
+        }
+        // Words inside parentheses belong to subqueries or CTE bodies.
+        if !word.is_empty() && depth == 0 {
+            let w = std::mem::take(&mut word);
+            if verb.is_some() {
+                has_where |= w.eq_ignore_ascii_case("where");
+            } else if w.eq_ignore_ascii_case("with") {
+                in_with = true;
+            } else if !in_with || VERBS.iter().any(|v| w.eq_ignore_ascii_case(v)) {
+                verb = Some(w);
+            }
+        }
+        word.clear();
+        match c {
+            None => return unsafe_statement(&verb, has_where),
+            Some(';') => {
+                if unsafe_statement(&verb, has_where) {
+                    return true;
+                }
+                verb = None;
+                in_with = false;
+                has_where = false;
+                depth = 0;
+            }
+            Some('(') => depth += 1,
+            Some(')') => depth = depth.saturating_sub(1),
+            Some(quote @ ('\'' | '"' | '`')) => {
+                while let Some(ch) = chars.next() {
+                    if ch == '\\' && quote != '`' {
+                        chars.next();
+                    } else if ch == quote {
+                        if chars.peek() == Some(&quote) {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            Some('#') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            Some('-') if chars.peek() == Some(&'-') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            Some('/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for ch in chars.by_ref() {
+                    if prev == '*' && ch == '/' {
+                        break;
+                    }
+                    prev = ch;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Column list and DDL of a collection table.
+struct TableLayout {
+    /// `(name, type)` pairs in table order.
+    columns: Vec<(String, String)>,
+    create_stmt: String,
+}
+
+impl TableLayout {
+    /// Dimension parsed from the `embedding` column type.
+    fn dimension(&self) -> Option<u32> {
+        self.columns
+            .iter()
+            .find(|(name, _)| name == "embedding")
+            .and_then(|(_, type_str)| parse_dimension(type_str))
+    }
+
+    /// Named vector columns other than `embedding`.
+    fn vector_fields(&self) -> Vec<VectorField> {
+        self.columns
+            .iter()
+            .filter(|(name, _)| name != "embedding")
+            .filter_map(|(name, type_str)| {
+                parse_dimension(type_str).map(|dim| VectorField::new(name.clone(), dim))
+            })
+            .collect()
     }
 }
 
@@ -545,10 +1812,10 @@ fn parse_dimension(type_str: &str) -> Option<u32> {
     let lower = type_str.to_lowercase();
     if let Some(start) = lower.find("vector(") {
         let rest = &lower[start + "vector(".len()..];
-        if let Some(end) = rest.find(')') {
-            if let Ok(dim) = rest[..end].trim().parse::<u32>() {
-                return Some(dim);
-            }
+        if let Some(end) = rest.find(')')
+            && let Ok(dim) = rest[..end].trim().parse::<u32>()
+        {
+            return Some(dim);
         }
     }
     None
@@ -567,16 +1834,145 @@ fn parse_distance(create_stmt: &str) -> Option<DistanceMetric> {
             "l2" => Some(DistanceMetric::L2),
             "cosine" => Some(DistanceMetric::Cosine),
             "inner_product" | "ip" => Some(DistanceMetric::InnerProduct),
+            "hamming" => Some(DistanceMetric::Hamming),
+            "jaccard" => Some(DistanceMetric::Jaccard),
             _ => None,
         };
     }
     None
 }
 
+/// `WITH (...)` options of the vector index as lower-cased `key -> value` pairs.
+fn parse_vector_index_options(create_stmt: &str) -> Option<BTreeMap<String, String>> {
+    let lower = create_stmt.to_lowercase();
+    let index_pos = lower
+        .find("vector index")
+        .or_else(|| lower.find("vector key"))?;
+    let rest = &lower[index_pos..];
+    let with_pos = rest.find("with")?;
+    let open = with_pos + rest[with_pos..].find('(')?;
+    let close = open + rest[open..].find(')')?;
+    let options = rest[open + 1..close]
+        .split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    Some(options)
+}
+
+/// Parser name of the full-text index (`... WITH PARSER <name>`).
+fn parse_fulltext_parser(create_stmt: &str) -> Option<String> {
+    let lower = create_stmt.to_lowercase();
+    let rest = &lower[lower.find("fulltext")?..];
+    let rest = &rest[rest.find("with parser")? + "with parser".len()..];
+    let parser: String = rest
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!parser.is_empty()).then_some(parser)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_mismatch() {
+        let cfg = HnswConfig::new(3, DistanceMetric::Cosine);
+        assert_eq!(config_mismatch(3, DistanceMetric::Cosine, &cfg), None);
+        assert_eq!(
+            config_mismatch(4, DistanceMetric::Cosine, &cfg).as_deref(),
+            Some("dimension 4 != 3")
+        );
+        assert_eq!(
+            config_mismatch(4, DistanceMetric::L2, &cfg).as_deref(),
+            Some("dimension 4 != 3; distance L2 != cosine")
+        );
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("  select 1"));
+        assert!(is_read_only("SHOW TABLES"));
+        assert!(is_read_only("DESCRIBE `t`"));
+        assert!(!is_read_only("DELETE FROM t WHERE id = 1"));
+        assert!(!is_read_only("selected"));
+        assert!(!is_read_only(""));
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_policy() {
+        let mut calls = 0;
+        let out: std::result::Result<(), _> = with_retry(3, Duration::ZERO, false, || {
+            calls += 1;
+            std::future::ready(Err(sqlx::Error::PoolTimedOut))
+        })
+        .await;
+        assert!(matches!(out, Err(sqlx::Error::PoolTimedOut)));
+        assert_eq!(calls, 4);
+
+        let mut calls = 0;
+        let out: std::result::Result<(), _> = with_retry(3, Duration::ZERO, false, || {
+            calls += 1;
+            std::future::ready(Err(sqlx::Error::Io(
+                std::io::ErrorKind::ConnectionReset.into(),
+            )))
+        })
+        .await;
+        assert!(out.is_err());
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let out = with_retry(3, Duration::ZERO, true, || {
+            calls += 1;
+            std::future::ready(if calls < 3 {
+                Err(sqlx::Error::Io(
+                    std::io::ErrorKind::ConnectionRefused.into(),
+                ))
+            } else {
+                Ok(calls)
+            })
+        })
+        .await;
+        assert_eq!(out.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_lacks_where_clause() {
+        assert!(lacks_where_clause("DELETE FROM t"));
+        assert!(lacks_where_clause("  update t SET a = 1"));
+        assert!(lacks_where_clause("UPDATE t SET note = 'where'"));
+        assert!(lacks_where_clause("DELETE FROM t -- WHERE id = 1"));
+        assert!(lacks_where_clause("DELETE FROM `where`"));
+        assert!(lacks_where_clause("SELECT 1; DELETE FROM t"));
+        assert!(!lacks_where_clause("DELETE FROM t WHERE id = 1"));
+        assert!(!lacks_where_clause("update t set a = 'it''s' where b = 2"));
+        assert!(!lacks_where_clause("SELECT * FROM t"));
+        assert!(!lacks_where_clause("DROP TABLE t"));
+
+        // Only a WHERE outside parentheses belongs to the statement itself.
+        assert!(lacks_where_clause(
+            "UPDATE t SET a = (SELECT b FROM u WHERE u.id = 1)"
+        ));
+        assert!(!lacks_where_clause(
+            "DELETE FROM t WHERE id IN (SELECT id FROM u)"
+        ));
+        // A CTE prefix is skipped to find the verb.
+        assert!(lacks_where_clause(
+            "WITH x AS (SELECT id FROM u WHERE a = 1) DELETE FROM t"
+        ));
+        assert!(lacks_where_clause(
+            "with recursive x (n) as (select 1) update t set a = 1"
+        ));
+        assert!(!lacks_where_clause(
+            "WITH x AS (SELECT 1) DELETE FROM t WHERE id IN (SELECT * FROM x)"
+        ));
+        assert!(!lacks_where_clause("WITH x AS (SELECT 1) SELECT * FROM x"));
+    }
+
     #[test]
     fn test_parse_dimension() {
         assert_eq!(parse_dimension("vector(384)"), Some(384));
@@ -584,6 +1980,58 @@ mod tests {
         assert_eq!(parse_dimension("text"), None);
     }
 
+    #[tokio::test]
+    async fn test_inflight_limit() {
+        use futures::FutureExt;
+
+        let reject = Inflight::limited(2, OverloadPolicy::Reject);
+        let first = reject.admit().await.unwrap();
+        let _second = reject.admit().await.unwrap();
+        assert_eq!(reject.current.load(Ordering::Relaxed), 2);
+        match reject.admit().await {
+            Err(SeekDbError::Overloaded { max_inflight: 2 }) => {}
+            _ => panic!("expected Overloaded"),
+        }
+        drop(first);
+        assert_eq!(reject.current.load(Ordering::Relaxed), 1);
+        let _third = reject.admit().await.unwrap();
+
+        let wait = Inflight::limited(1, OverloadPolicy::Wait);
+        let held = wait.admit().await.unwrap();
+        assert!(wait.admit().now_or_never().is_none());
+        drop(held);
+        assert!(wait.admit().now_or_never().is_some());
+
+        let unlimited = Inflight::default();
+        let _guards = [
+            unlimited.admit().await.unwrap(),
+            unlimited.admit().await.unwrap(),
+        ];
+        assert_eq!(unlimited.current.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_json_dialect_probe() {
+        assert_eq!(
+            json_dialect_probe(JsonDialect::Operator),
+            "SELECT (metadata->'$.a.n' = 2 AND metadata->>'$.s' = 'x') \
+             FROM (SELECT CAST('{\"a\": {\"n\": 2}, \"s\": \"x\"}' AS JSON) AS metadata) AS probe"
+        );
+    }
+
+    #[test]
+    fn test_append_limit_offset() {
+        let page = |limit, offset| {
+            let mut sql = String::from("SELECT 1");
+            append_limit_offset(&mut sql, limit, offset, 1000);
+            sql
+        };
+        assert_eq!(page(None, None), "SELECT 1");
+        assert_eq!(page(Some(5), None), "SELECT 1 LIMIT 5");
+        assert_eq!(page(Some(5), Some(10)), "SELECT 1 LIMIT 5 OFFSET 10");
+        assert_eq!(page(None, Some(10)), "SELECT 1 LIMIT 1000 OFFSET 10");
+    }
+
     #[test]
     fn test_parse_distance() {
         let stmt = "VECTOR INDEX idx_vec (embedding) with(distance=cosine, type=hnsw, lib=vsag)";
@@ -593,15 +2041,159 @@ mod tests {
             parse_distance(stmt2),
             Some(DistanceMetric::InnerProduct)
         ));
+        assert_eq!(
+            parse_distance("with(distance=hamming, type=hnsw)"),
+            Some(DistanceMetric::Hamming)
+        );
+        assert_eq!(
+            parse_distance("with(distance=jaccard)"),
+            Some(DistanceMetric::Jaccard)
+        );
         assert_eq!(parse_distance("none"), None);
     }
 
+    #[test]
+    fn test_parse_index_details() {
+        let ours = build_create_table_sql(
+            "c$v1$foo",
+            &HnswConfig::new(3, DistanceMetric::Cosine).with_m(16),
+            None,
+        );
+        let opts = parse_vector_index_options(&ours).unwrap();
+        assert_eq!(opts.get("type").map(String::as_str), Some("hnsw"));
+        assert_eq!(opts.get("lib").map(String::as_str), Some("vsag"));
+        assert_eq!(opts.get("m").map(String::as_str), Some("16"));
+        assert_eq!(parse_fulltext_parser(&ours).as_deref(), Some("ik"));
+
+        // Server-rendered DDL uses KEY and upper case.
+        let shown = "CREATE TABLE `c$v1$foo` (\n  `_id` varbinary(512) NOT NULL,\n  \
+                     FULLTEXT KEY `idx_fts` (`document`) WITH PARSER ik,\n  \
+                     VECTOR KEY `idx_vec` (`embedding`) WITH (DISTANCE=L2, TYPE=HNSW, LIB=VSAG) BLOCK_SIZE 16384\n)";
+        let opts = parse_vector_index_options(shown).unwrap();
+        assert_eq!(opts.get("distance").map(String::as_str), Some("l2"));
+        assert_eq!(opts.len(), 3);
+        assert_eq!(parse_fulltext_parser(shown).as_deref(), Some("ik"));
+
+        assert_eq!(parse_vector_index_options("CREATE TABLE t (a int)"), None);
+        assert_eq!(parse_fulltext_parser("FULLTEXT KEY idx (document)"), None);
+    }
+
     #[test]
     fn test_build_create_table_sql() {
-        let sql = build_create_table_sql("c$v1$foo", 384, DistanceMetric::Cosine);
+        let cfg = HnswConfig::new(384, DistanceMetric::Cosine);
+        let sql = build_create_table_sql("c$v1$foo", &cfg, None);
         assert!(sql.contains("c$v1$foo"));
         assert!(sql.contains("vector(384)"));
-        assert!(sql.contains("distance=cosine"));
+        assert!(sql.contains("with(distance=cosine, type=hnsw, lib=vsag)"));
         assert!(sql.contains("FULLTEXT INDEX"));
+        assert!(sql.ends_with("ORGANIZATION = HEAP;"));
+
+        let sql = build_create_table_sql(
+            "c$v1$bits",
+            &HnswConfig::new(64, DistanceMetric::Hamming),
+            None,
+        );
+        assert!(sql.contains("with(distance=hamming, type=hnsw, lib=vsag)"));
+    }
+
+    #[test]
+    fn test_build_create_table_sql_comment_is_escaped() {
+        let cfg = HnswConfig::new(3, DistanceMetric::L2);
+        let sql =
+            build_create_table_sql("c$v1$foo", &cfg, Some(r#"{"owner":"o'brien","p":"a\b"}"#));
+        assert!(
+            sql.ends_with(r#"ORGANIZATION = HEAP COMMENT = '{"owner":"o''brien","p":"a\\b"}';"#)
+        );
+    }
+
+    #[test]
+    fn test_build_create_table_sql_hnsw_tuning() {
+        let cfg = HnswConfig::new(8, DistanceMetric::L2)
+            .with_m(32)
+            .with_ef_construction(400)
+            .with_ef_search(128);
+        let sql = build_create_table_sql("c$v1$foo", &cfg, None);
+        assert!(sql.contains(
+            "with(distance=l2, type=hnsw, lib=vsag, m=32, ef_construction=400, ef_search=128)"
+        ));
+
+        let sql = build_create_table_sql(
+            "c$v1$foo",
+            &HnswConfig::new(8, DistanceMetric::L2).with_m(24),
+            None,
+        );
+        assert!(sql.contains("lib=vsag, m=24)"));
+    }
+
+    #[test]
+    fn test_validate_dimension() {
+        assert!(validate_dimension("embedding", 1).is_ok());
+        assert!(validate_dimension("embedding", MAX_VECTOR_DIMENSION).is_ok());
+        for dimension in [0, MAX_VECTOR_DIMENSION + 1] {
+            let err = validate_dimension("embedding", dimension).unwrap_err();
+            assert!(matches!(err, SeekDbError::InvalidInput(_)));
+            assert!(err.to_string().contains(&dimension.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_build_create_table_sql_vector_fields() {
+        let cfg = HnswConfig::new(8, DistanceMetric::Cosine)
+            .with_vector_field("title_vec", 4)
+            .with_vector_field("body_vec", 16);
+        let sql = build_create_table_sql("c$v1$foo", &cfg, None);
+        assert!(sql.contains("`title_vec` vector(4),"));
+        assert!(sql.contains("`body_vec` vector(16),"));
+        assert!(sql.contains(
+            "VECTOR INDEX `idx_vec_body_vec` (`body_vec`) with(distance=cosine, type=hnsw, lib=vsag)"
+        ));
+        assert!(validate_vector_fields(&cfg.vector_fields).is_ok());
+        assert!(validate_vector_fields(&[VectorField::new("v".repeat(56), 4)]).is_ok());
+
+        for bad in [
+            vec![VectorField::new("", 4)],
+            vec![VectorField::new("v".repeat(57), 4)],
+            vec![VectorField::new("a-b", 4)],
+            vec![VectorField::new("Embedding", 4)],
+            vec![VectorField::new("t", 0)],
+            vec![VectorField::new("t", 4), VectorField::new("T", 8)],
+        ] {
+            assert!(matches!(
+                validate_vector_fields(&bad),
+                Err(SeekDbError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_build_create_table_sql_document_collation() {
+        let cfg =
+            HnswConfig::new(3, DistanceMetric::L2).with_document_collation("utf8mb4_unicode_ci");
+        let sql = build_create_table_sql("c$v1$foo", &cfg, None);
+        assert!(sql.contains("document text COLLATE utf8mb4_unicode_ci,"));
+
+        assert!(validate_collation("utf8mb4_0900_ai_ci").is_ok());
+        assert!(validate_collation("utf8mb4").is_err());
+        assert!(validate_collation("utf8mb4__ci").is_err());
+        assert!(validate_collation("utf8mb4_bin, x int").is_err());
+        assert!(validate_collation("utf8mb4_bin;DROP").is_err());
+    }
+
+    #[test]
+    fn test_is_schema_unavailable() {
+        let db = |code| SeekDbError::Database {
+            code: Some(code),
+            message: "denied".into(),
+            statement: None,
+        };
+        assert!(is_schema_unavailable(&db(1142)));
+        assert!(is_schema_unavailable(&db(1044)));
+        assert!(!is_schema_unavailable(&db(2013)));
+        assert!(!is_schema_unavailable(&SeekDbError::Overloaded {
+            max_inflight: 1
+        }));
+        assert!(!is_schema_unavailable(&SeekDbError::Connection(
+            "reset".into()
+        )));
     }
 }
@@ -0,0 +1,214 @@
+//! Client-side latency accumulation, compiled only with the `stats` feature.
+//!
+//! Every statement issued through a [`ServerClient`](crate::ServerClient) (and the
+//! collections created from it) is timed and recorded into an HDR histogram,
+//! split into reads (queries/gets/counts) and writes (DML/DDL).
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+
+use crate::server::OpKind;
+
+/// Highest latency tracked precisely (60s, in microseconds); slower samples are clamped.
+const MAX_TRACKED_MICROS: u64 = 60_000_000;
+/// Number of significant decimal digits kept by the histograms.
+const SIGNIFICANT_DIGITS: u8 = 3;
+/// Metric name used by [`LatencyStats::render_prometheus`].
+const METRIC_NAME: &str = "seekdb_client_statement_duration_seconds";
+/// Quantiles exported as the Prometheus summary.
+const QUANTILES: [f64; 3] = [0.5, 0.95, 0.99];
+
+/// Percentile summary for one operation kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LatencyPercentiles {
+    /// Number of recorded samples.
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Point-in-time view of the accumulated latencies, as returned by
+/// [`ServerClient::latency_snapshot`](crate::ServerClient::latency_snapshot).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LatencySnapshot {
+    pub reads: LatencyPercentiles,
+    pub writes: LatencyPercentiles,
+}
+
+/// Shared histogram storage; cloned clients point at the same instance.
+pub(crate) struct LatencyStats {
+    reads: Mutex<Histogram<u64>>,
+    writes: Mutex<Histogram<u64>>,
+}
+
+impl LatencyStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            reads: Mutex::new(new_histogram()),
+            writes: Mutex::new(new_histogram()),
+        }
+    }
+
+    pub(crate) fn record(&self, kind: OpKind, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let histogram = match kind {
+            OpKind::Read => &self.reads,
+            OpKind::Write => &self.writes,
+        };
+        lock(histogram).saturating_record(micros.clamp(1, MAX_TRACKED_MICROS));
+    }
+
+    pub(crate) fn snapshot(&self) -> LatencySnapshot {
+        LatencySnapshot {
+            reads: percentiles(&self.reads),
+            writes: percentiles(&self.writes),
+        }
+    }
+
+    /// Both histograms as one Prometheus `summary` in the text exposition
+    /// format, labelled `kind="read"` / `kind="write"`, in seconds. Quantiles
+    /// of a kind with no samples are `NaN`, as Prometheus expects.
+    pub(crate) fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_NAME} Client-side latency of SeekDB statements."
+        );
+        let _ = writeln!(out, "# TYPE {METRIC_NAME} summary");
+        for (kind, histogram) in [("read", &self.reads), ("write", &self.writes)] {
+            let h = lock(histogram);
+            for q in QUANTILES {
+                let value = if h.is_empty() {
+                    f64::NAN
+                } else {
+                    micros_to_secs(h.value_at_quantile(q) as f64)
+                };
+                let _ = writeln!(
+                    out,
+                    "{METRIC_NAME}{{kind=\"{kind}\",quantile=\"{q}\"}} {value}"
+                );
+            }
+            // HDR histograms keep no exact sum; mean x count is accurate to
+            // the histogram's precision.
+            let sum = if h.is_empty() {
+                0.0
+            } else {
+                h.mean() * h.len() as f64
+            };
+            let _ = writeln!(
+                out,
+                "{METRIC_NAME}_sum{{kind=\"{kind}\"}} {}",
+                micros_to_secs(sum)
+            );
+            let _ = writeln!(out, "{METRIC_NAME}_count{{kind=\"{kind}\"}} {}", h.len());
+        }
+        out
+    }
+}
+
+fn micros_to_secs(micros: f64) -> f64 {
+    micros / 1_000_000.0
+}
+
+fn new_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_TRACKED_MICROS, SIGNIFICANT_DIGITS)
+        .expect("static histogram bounds are valid")
+}
+
+/// Locks a histogram, recovering it if a recording thread panicked: the
+/// counts are still valid, so a poisoned lock must not hide them.
+fn lock(histogram: &Mutex<Histogram<u64>>) -> MutexGuard<'_, Histogram<u64>> {
+    histogram.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn percentiles(histogram: &Mutex<Histogram<u64>>) -> LatencyPercentiles {
+    let h = lock(histogram);
+    if h.is_empty() {
+        return LatencyPercentiles::default();
+    }
+    LatencyPercentiles {
+        count: h.len(),
+        p50: Duration::from_micros(h.value_at_quantile(0.50)),
+        p95: Duration::from_micros(h.value_at_quantile(0.95)),
+        p99: Duration::from_micros(h.value_at_quantile(0.99)),
+        max: Duration::from_micros(h.max()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_snapshot_is_zeroed() {
+        let stats = LatencyStats::new();
+        assert_eq!(stats.snapshot(), LatencySnapshot::default());
+    }
+
+    #[test]
+    fn percentiles_are_split_by_kind() {
+        let stats = LatencyStats::new();
+        for ms in 1..=100 {
+            stats.record(OpKind::Read, Duration::from_millis(ms));
+        }
+        stats.record(OpKind::Write, Duration::from_millis(7));
+
+        let snap = stats.snapshot();
+        assert_eq!(snap.reads.count, 100);
+        assert_eq!(snap.writes.count, 1);
+
+        // HDR buckets are accurate to 3 significant digits.
+        let p50 = snap.reads.p50.as_micros() as f64;
+        let p99 = snap.reads.p99.as_micros() as f64;
+        assert!((p50 - 50_000.0).abs() / 50_000.0 < 0.01, "p50 = {p50}");
+        assert!((p99 - 99_000.0).abs() / 99_000.0 < 0.01, "p99 = {p99}");
+        assert!(snap.reads.p95 <= snap.reads.p99);
+        assert!(snap.reads.p99 <= snap.reads.max);
+    }
+
+    #[test]
+    fn prometheus_text_lists_quantiles_sum_and_count() {
+        let stats = LatencyStats::new();
+        stats.record(OpKind::Write, Duration::from_millis(2));
+        stats.record(OpKind::Write, Duration::from_millis(4));
+
+        let text = stats.render_prometheus();
+        assert!(text.starts_with("# HELP seekdb_client_statement_duration_seconds "));
+        assert!(text.contains("# TYPE seekdb_client_statement_duration_seconds summary\n"));
+        assert!(text.contains(
+            "seekdb_client_statement_duration_seconds{kind=\"read\",quantile=\"0.5\"} NaN\n"
+        ));
+        assert!(text.contains("seekdb_client_statement_duration_seconds_count{kind=\"read\"} 0\n"));
+        assert!(
+            text.contains("seekdb_client_statement_duration_seconds_sum{kind=\"write\"} 0.006")
+        );
+        assert!(
+            text.contains("seekdb_client_statement_duration_seconds_count{kind=\"write\"} 2\n")
+        );
+        assert!(text.contains(
+            "seekdb_client_statement_duration_seconds{kind=\"write\",quantile=\"0.99\"} 0.004"
+        ));
+    }
+
+    #[test]
+    fn poisoned_lock_is_still_rendered() {
+        let stats = std::sync::Arc::new(LatencyStats::new());
+        stats.record(OpKind::Read, Duration::from_millis(3));
+        let poisoner = std::sync::Arc::clone(&stats);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.reads.lock().unwrap();
+            panic!("poison the read histogram");
+        })
+        .join();
+        assert!(stats.reads.is_poisoned());
+
+        stats.record(OpKind::Read, Duration::from_millis(5));
+        assert_eq!(stats.snapshot().reads.count, 2);
+        let text = stats.render_prometheus();
+        assert!(text.contains("seekdb_client_statement_duration_seconds_count{kind=\"read\"} 2\n"));
+    }
+}
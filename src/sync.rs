@@ -1,12 +1,16 @@
 use std::sync::Arc;
 
-use crate::collection::{AddBatch, Collection, DeleteQuery, GetQuery, UpdateBatch, UpsertBatch};
-use crate::config::ServerConfig;
+use futures::StreamExt;
+
+use crate::collection::{
+    AddBatch, Collection, DeleteQuery, GetQuery, OnConflict, QueryOptions, UpdateBatch, UpsertBatch,
+};
+use crate::config::{OverloadPolicy, ServerConfig, SslMode};
 use crate::embedding::EmbeddingFunction;
 use crate::error::{Result, SeekDbError};
 use crate::filters::{DocFilter, Filter};
 use crate::server::{ServerClient, ServerClientBuilder};
-use crate::types::{GetResult, IncludeField, QueryResult};
+use crate::types::{GetResult, IncludeField, PageToken, QueryResult, Record, UpsertReport};
 
 /// Shared inner state for synchronous wrappers.
 ///
@@ -52,11 +56,21 @@ impl SyncServerClient {
         })
     }
 
+    /// Build a synchronous client from a connection URL; see
+    /// [`ServerConfig::from_url`] for the format.
+    pub fn from_url(url: &str) -> Result<Self> {
+        Self::from_config(ServerConfig::from_url(url)?)
+    }
+
     /// Start building a [`SyncServerClient`] using a fluent builder API.
     pub fn builder() -> SyncServerClientBuilder {
         SyncServerClientBuilder::new()
     }
 
+    pub fn ping(&self) -> Result<()> {
+        self.inner.rt.block_on(self.inner.client.ping())
+    }
+
     /// Execute a SQL statement that does not return rows.
     pub fn execute(&self, sql: &str) -> Result<()> {
         self.inner
@@ -70,6 +84,18 @@ impl SyncServerClient {
         self.inner.rt.block_on(self.inner.client.fetch_all(sql))
     }
 
+    /// Latency percentiles accumulated by the underlying client (`stats` feature).
+    #[cfg(feature = "stats")]
+    pub fn latency_snapshot(&self) -> crate::stats::LatencySnapshot {
+        self.inner.client.latency_snapshot()
+    }
+
+    /// Accumulated latencies in the Prometheus text format (`stats` feature).
+    #[cfg(feature = "stats")]
+    pub fn metrics_text(&self) -> String {
+        self.inner.client.metrics_text()
+    }
+
     // Collection management
 
     pub fn create_collection<Ef: EmbeddingFunction + 'static>(
@@ -89,6 +115,28 @@ impl SyncServerClient {
         })
     }
 
+    pub fn create_collection_with_metadata<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        config: Option<crate::config::HnswConfig>,
+        embedding_function: Option<Ef>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<SyncCollection<Ef>> {
+        let collection =
+            self.inner
+                .rt
+                .block_on(self.inner.client.create_collection_with_metadata(
+                    name,
+                    config,
+                    embedding_function,
+                    metadata,
+                ))?;
+        Ok(SyncCollection {
+            inner: Arc::clone(&self.inner),
+            collection,
+        })
+    }
+
     pub fn get_collection<Ef: EmbeddingFunction + 'static>(
         &self,
         name: &str,
@@ -104,14 +152,76 @@ impl SyncServerClient {
         })
     }
 
+    pub fn create_collection_in_tenant<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        config: Option<crate::config::HnswConfig>,
+        embedding_function: Option<Ef>,
+        tenant: Option<&str>,
+    ) -> Result<SyncCollection<Ef>> {
+        let collection = self
+            .inner
+            .rt
+            .block_on(self.inner.client.create_collection_in_tenant(
+                name,
+                config,
+                embedding_function,
+                tenant,
+            ))?;
+        Ok(SyncCollection {
+            inner: Arc::clone(&self.inner),
+            collection,
+        })
+    }
+
+    pub fn get_collection_in_tenant<Ef: EmbeddingFunction + 'static>(
+        &self,
+        name: &str,
+        embedding_function: Option<Ef>,
+        tenant: Option<&str>,
+    ) -> Result<SyncCollection<Ef>> {
+        let collection = self
+            .inner
+            .rt
+            .block_on(self.inner.client.get_collection_in_tenant(
+                name,
+                embedding_function,
+                tenant,
+            ))?;
+        Ok(SyncCollection {
+            inner: Arc::clone(&self.inner),
+            collection,
+        })
+    }
+
     pub fn delete_collection(&self, name: &str) -> Result<()> {
         self.inner
             .rt
             .block_on(self.inner.client.delete_collection(name))
     }
 
-    pub fn list_collections(&self) -> Result<Vec<String>> {
-        self.inner.rt.block_on(self.inner.client.list_collections())
+    pub fn rename_collection(&self, old: &str, new: &str) -> Result<()> {
+        self.inner
+            .rt
+            .block_on(self.inner.client.rename_collection(old, new))
+    }
+
+    pub fn list_collections(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<String>> {
+        self.inner
+            .rt
+            .block_on(self.inner.client.list_collections(limit, offset))
+    }
+
+    pub fn list_collections_matching(&self, pattern: &str) -> Result<Vec<String>> {
+        self.inner
+            .rt
+            .block_on(self.inner.client.list_collections_matching(pattern))
+    }
+
+    pub fn list_collections_detailed(&self) -> Result<Vec<crate::types::CollectionInfo>> {
+        self.inner
+            .rt
+            .block_on(self.inner.client.list_collections_detailed())
     }
 
     pub fn has_collection(&self, name: &str) -> Result<bool> {
@@ -230,6 +340,41 @@ impl SyncServerClientBuilder {
         self
     }
 
+    pub fn min_connections(mut self, min_connections: u32) -> Self {
+        self.inner = self.inner.min_connections(min_connections);
+        self
+    }
+
+    pub fn idle_timeout(mut self, idle_timeout: Option<std::time::Duration>) -> Self {
+        self.inner = self.inner.idle_timeout(idle_timeout);
+        self
+    }
+
+    pub fn max_lifetime(mut self, max_lifetime: Option<std::time::Duration>) -> Self {
+        self.inner = self.inner.max_lifetime(max_lifetime);
+        self
+    }
+
+    pub fn retry(mut self, max_retries: u32, base_backoff: std::time::Duration) -> Self {
+        self.inner = self.inner.retry(max_retries, base_backoff);
+        self
+    }
+
+    pub fn ssl_mode(mut self, ssl_mode: SslMode) -> Self {
+        self.inner = self.inner.ssl_mode(ssl_mode);
+        self
+    }
+
+    pub fn ca_cert_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.inner = self.inner.ca_cert_path(path);
+        self
+    }
+
+    pub fn max_inflight(mut self, max: usize, policy: OverloadPolicy) -> Self {
+        self.inner = self.inner.max_inflight(max, policy);
+        self
+    }
+
     /// Build a [`SyncServerClient`] using the current builder configuration.
     pub fn build(self) -> Result<SyncServerClient> {
         let rt = tokio::runtime::Runtime::new()
@@ -270,6 +415,81 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         self.collection.metadata()
     }
 
+    pub fn vector_fields(&self) -> &[crate::config::VectorField] {
+        self.collection.vector_fields()
+    }
+
+    /// See [`Collection::with_max_statement_bytes`].
+    pub fn with_max_statement_bytes(mut self, bytes: usize) -> Self {
+        self.collection = self.collection.with_max_statement_bytes(bytes);
+        self
+    }
+
+    /// See [`Collection::with_max_metadata_bytes`].
+    pub fn with_max_metadata_bytes(mut self, bytes: usize) -> Self {
+        self.collection = self.collection.with_max_metadata_bytes(bytes);
+        self
+    }
+
+    /// See [`Collection::with_insert_batch_size`].
+    pub fn with_insert_batch_size(mut self, rows: usize) -> Self {
+        self.collection = self.collection.with_insert_batch_size(rows);
+        self
+    }
+
+    /// See [`Collection::no_transaction`].
+    pub fn no_transaction(mut self) -> Self {
+        self.collection = self.collection.no_transaction();
+        self
+    }
+
+    /// See [`Collection::with_vector_literal_format`].
+    pub fn with_vector_literal_format(mut self, format: crate::types::VectorLiteralFormat) -> Self {
+        self.collection = self.collection.with_vector_literal_format(format);
+        self
+    }
+
+    /// See [`Collection::with_normalize_queries`].
+    pub fn with_normalize_queries(mut self, enabled: bool) -> Self {
+        self.collection = self.collection.with_normalize_queries(enabled);
+        self
+    }
+
+    /// See [`Collection::with_snapshot_scans`].
+    pub fn with_snapshot_scans(mut self, enabled: bool) -> Self {
+        self.collection = self.collection.with_snapshot_scans(enabled);
+        self
+    }
+
+    pub fn with_id_strategy(mut self, strategy: crate::ids::IdStrategy) -> Self {
+        self.collection = self.collection.with_id_strategy(strategy);
+        self
+    }
+
+    pub fn set_embedding_function(&mut self, ef: Ef) {
+        self.collection.set_embedding_function(ef);
+    }
+
+    pub fn with_embedding_function(mut self, ef: Ef) -> Self {
+        self.collection.set_embedding_function(ef);
+        self
+    }
+
+    pub fn embedding_function(&self) -> Option<&Ef> {
+        self.collection.embedding_function()
+    }
+
+    pub fn boxed(self) -> SyncCollection<Box<dyn EmbeddingFunction>> {
+        SyncCollection {
+            inner: self.inner,
+            collection: self.collection.boxed(),
+        }
+    }
+
+    pub fn describe(&self) -> Result<crate::types::CollectionSchema> {
+        self.inner.rt.block_on(self.collection.describe())
+    }
+
     pub fn add(
         &self,
         ids: &[String],
@@ -286,23 +506,50 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         self.inner.rt.block_on(self.collection.add_batch(batch))
     }
 
+    pub fn add_auto(
+        &self,
+        embeddings: Option<&[crate::types::Embedding]>,
+        metadatas: Option<&[crate::types::Metadata]>,
+        documents: Option<&[String]>,
+    ) -> Result<Vec<String>> {
+        self.inner
+            .rt
+            .block_on(self.collection.add_auto(embeddings, metadatas, documents))
+    }
+
+    /// Blocking counterpart of `Collection::add_stream`, fed from an iterator.
+    pub fn add_iter<I>(&self, items: I, batch_size: usize) -> Result<u64>
+    where
+        I: IntoIterator<
+            Item = (
+                String,
+                crate::types::Embedding,
+                Option<crate::types::Metadata>,
+                Option<String>,
+            ),
+        >,
+    {
+        self.inner.rt.block_on(
+            self.collection
+                .add_stream(futures::stream::iter(items), batch_size),
+        )
+    }
+
     pub fn update(
         &self,
         ids: &[String],
         embeddings: Option<&[crate::types::Embedding]>,
         metadatas: Option<&[crate::types::Metadata]>,
         documents: Option<&[String]>,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         self.inner.rt.block_on(
             self.collection
                 .update(ids, embeddings, metadatas, documents),
         )
     }
 
-    pub fn update_batch(&self, batch: UpdateBatch<'_>) -> Result<()> {
-        self.inner
-            .rt
-            .block_on(self.collection.update_batch(batch))
+    pub fn update_batch(&self, batch: UpdateBatch<'_>) -> Result<u64> {
+        self.inner.rt.block_on(self.collection.update_batch(batch))
     }
 
     pub fn upsert(
@@ -311,17 +558,15 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         embeddings: Option<&[crate::types::Embedding]>,
         metadatas: Option<&[crate::types::Metadata]>,
         documents: Option<&[String]>,
-    ) -> Result<()> {
+    ) -> Result<UpsertReport> {
         self.inner.rt.block_on(
             self.collection
                 .upsert(ids, embeddings, metadatas, documents),
         )
     }
 
-    pub fn upsert_batch(&self, batch: UpsertBatch<'_>) -> Result<()> {
-        self.inner
-            .rt
-            .block_on(self.collection.upsert_batch(batch))
+    pub fn upsert_batch(&self, batch: UpsertBatch<'_>) -> Result<UpsertReport> {
+        self.inner.rt.block_on(self.collection.upsert_batch(batch))
     }
 
     pub fn delete(
@@ -329,16 +574,65 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         ids: Option<&[String]>,
         where_meta: Option<&Filter>,
         where_doc: Option<&DocFilter>,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         self.inner
             .rt
             .block_on(self.collection.delete(ids, where_meta, where_doc))
     }
 
-    pub fn delete_query(&self, query: DeleteQuery<'_>) -> Result<()> {
+    pub fn delete_query(&self, query: DeleteQuery<'_>) -> Result<u64> {
+        self.inner.rt.block_on(self.collection.delete_query(query))
+    }
+
+    pub fn clear(&self) -> Result<u64> {
+        self.inner.rt.block_on(self.collection.clear())
+    }
+
+    pub fn delete_where_streaming(
+        &self,
+        filter: &Filter,
+        batch_size: u32,
+        progress: impl FnMut(u64),
+    ) -> Result<u64> {
+        self.inner.rt.block_on(
+            self.collection
+                .delete_where_streaming(filter, batch_size, progress),
+        )
+    }
+
+    pub fn schema_compatible_with(&self, other: &crate::types::CollectionSchema) -> Result<()> {
+        self.inner
+            .rt
+            .block_on(self.collection.schema_compatible_with(other))
+    }
+
+    pub fn merge_from<S: EmbeddingFunction + 'static>(
+        &self,
+        source: &SyncCollection<S>,
+        on_conflict: OnConflict,
+    ) -> Result<u64> {
+        self.inner
+            .rt
+            .block_on(self.collection.merge_from(&source.collection, on_conflict))
+    }
+
+    pub fn reembed(&self, new_ef: &dyn EmbeddingFunction, batch_size: u32) -> Result<u64> {
         self.inner
             .rt
-            .block_on(self.collection.delete_query(query))
+            .block_on(self.collection.reembed(new_ef, batch_size))
+    }
+
+    pub fn query_by_ids(
+        &self,
+        ids: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        self.inner.rt.block_on(
+            self.collection
+                .query_by_ids(ids, n_results, where_meta, include),
+        )
     }
 
     pub fn query_embeddings(
@@ -355,6 +649,52 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         )
     }
 
+    pub fn query_ids(
+        &self,
+        embeddings: &[crate::types::Embedding],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+    ) -> Result<Vec<Vec<(String, f32)>>> {
+        self.inner
+            .rt
+            .block_on(self.collection.query_ids(embeddings, n_results, where_meta))
+    }
+
+    pub fn query_grouped(
+        &self,
+        query: &[f32],
+        group_field: &str,
+        per_group: u32,
+        total: u32,
+        where_meta: Option<&Filter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<Vec<crate::types::QueryGroup>> {
+        self.inner.rt.block_on(self.collection.query_grouped(
+            query,
+            group_field,
+            per_group,
+            total,
+            where_meta,
+            include,
+        ))
+    }
+
+    pub fn query_embeddings_with_options(
+        &self,
+        embeddings: &[crate::types::Embedding],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+        options: &QueryOptions,
+    ) -> Result<QueryResult> {
+        self.inner
+            .rt
+            .block_on(self.collection.query_embeddings_with_options(
+                embeddings, n_results, where_meta, where_doc, include, options,
+            ))
+    }
+
     pub fn query_texts(
         &self,
         texts: &[String],
@@ -369,6 +709,70 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         )
     }
 
+    pub fn query_texts_with_options(
+        &self,
+        texts: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+        options: &QueryOptions,
+    ) -> Result<QueryResult> {
+        self.inner.rt.block_on(
+            self.collection.query_texts_with_options(
+                texts, n_results, where_meta, where_doc, include, options,
+            ),
+        )
+    }
+
+    #[cfg(feature = "ndarray")]
+    pub fn add_ndarray(
+        &self,
+        ids: &[String],
+        embeddings: ndarray::ArrayView2<'_, f32>,
+        metadatas: Option<&[crate::types::Metadata]>,
+        documents: Option<&[String]>,
+    ) -> Result<()> {
+        self.inner.rt.block_on(
+            self.collection
+                .add_ndarray(ids, embeddings, metadatas, documents),
+        )
+    }
+
+    #[cfg(feature = "ndarray")]
+    pub fn query_embeddings_ndarray(
+        &self,
+        query_embeddings: ndarray::ArrayView2<'_, f32>,
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<QueryResult> {
+        self.inner
+            .rt
+            .block_on(self.collection.query_embeddings_ndarray(
+                query_embeddings,
+                n_results,
+                where_meta,
+                where_doc,
+                include,
+            ))
+    }
+
+    pub fn query_texts_lenient(
+        &self,
+        texts: &[String],
+        n_results: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<Vec<Result<QueryResult>>> {
+        self.inner.rt.block_on(
+            self.collection
+                .query_texts_lenient(texts, n_results, where_meta, where_doc, include),
+        )
+    }
+
     pub fn hybrid_search(
         &self,
         queries: &[String],
@@ -417,17 +821,192 @@ impl<Ef: EmbeddingFunction + 'static> SyncCollection<Ef> {
         )
     }
 
+    pub fn get_parents(
+        &self,
+        chunks: &QueryResult,
+        include: Option<&[IncludeField]>,
+    ) -> Result<GetResult> {
+        self.inner
+            .rt
+            .block_on(self.collection.get_parents(chunks, include))
+    }
+
     pub fn get_query(&self, query: GetQuery<'_>) -> Result<GetResult> {
+        self.inner.rt.block_on(self.collection.get_query(query))
+    }
+
+    pub fn get_page(
+        &self,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        limit: u32,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<PageToken>)> {
+        self.inner.rt.block_on(
+            self.collection
+                .get_page(where_meta, where_doc, limit, include),
+        )
+    }
+
+    pub fn get_next_page(
+        &self,
+        token: &PageToken,
+        limit: u32,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<PageToken>)> {
         self.inner
             .rt
-            .block_on(self.collection.get_query(query))
+            .block_on(self.collection.get_next_page(token, limit, include))
+    }
+
+    /// See [`Collection::get_after`].
+    pub fn get_after(
+        &self,
+        last_id: Option<&str>,
+        limit: u32,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        include: Option<&[IncludeField]>,
+    ) -> Result<(GetResult, Option<String>)> {
+        self.inner.rt.block_on(
+            self.collection
+                .get_after(last_id, limit, where_meta, where_doc, include),
+        )
+    }
+
+    /// Blocking iterator over [`Collection::get_stream`], one batch per item.
+    pub fn get_stream<'a>(
+        &'a self,
+        where_meta: Option<&'a Filter>,
+        where_doc: Option<&'a DocFilter>,
+        batch_size: u32,
+        include: Option<&'a [IncludeField]>,
+    ) -> impl Iterator<Item = Result<GetResult>> + 'a {
+        let mut stream = Box::pin(
+            self.collection
+                .get_stream(where_meta, where_doc, batch_size, include),
+        );
+        std::iter::from_fn(move || self.inner.rt.block_on(stream.next()))
+    }
+
+    /// Blocking iterator over [`Collection::iter_all`].
+    pub fn iter_all<'a>(
+        &'a self,
+        batch_size: u32,
+        include: Option<&'a [IncludeField]>,
+        after: Option<String>,
+    ) -> impl Iterator<Item = Result<Record>> + 'a {
+        let mut stream = Box::pin(self.collection.iter_all(batch_size, include, after));
+        std::iter::from_fn(move || self.inner.rt.block_on(stream.next()))
     }
 
     pub fn count(&self) -> Result<u64> {
         self.inner.rt.block_on(self.collection.count())
     }
 
+    pub fn count_approx(&self) -> Result<u64> {
+        self.inner.rt.block_on(self.collection.count_approx())
+    }
+
+    pub fn count_where(
+        &self,
+        where_meta: Option<&Filter>,
+        where_doc: Option<&DocFilter>,
+        ids: Option<&[String]>,
+    ) -> Result<u64> {
+        self.inner
+            .rt
+            .block_on(self.collection.count_where(where_meta, where_doc, ids))
+    }
+
+    pub fn validate_dimensions(&self) -> Result<Vec<String>> {
+        self.inner
+            .rt
+            .block_on(self.collection.validate_dimensions())
+    }
+
+    pub fn export_npy<W, I>(&self, vectors: W, ids: I) -> Result<u64>
+    where
+        W: std::io::Write,
+        I: std::io::Write,
+    {
+        self.inner
+            .rt
+            .block_on(self.collection.export_npy(vectors, ids))
+    }
+
+    pub fn export_jsonl<W: std::io::Write>(&self, mut writer: W) -> Result<u64> {
+        self.inner
+            .rt
+            .block_on(self.collection.export_jsonl(BlockingIo(&mut writer)))
+    }
+
+    pub fn import_jsonl<R: std::io::BufRead>(
+        &self,
+        mut reader: R,
+        batch_size: usize,
+    ) -> Result<u64> {
+        self.inner.rt.block_on(
+            self.collection
+                .import_jsonl(BlockingIo(&mut reader), batch_size),
+        )
+    }
+
     pub fn peek(&self, limit: u32) -> Result<GetResult> {
         self.inner.rt.block_on(self.collection.peek(limit))
     }
 }
+
+/// Exposes a blocking `std::io` reader or writer through tokio's async I/O
+/// traits, for async methods called from `block_on`. Each poll completes the
+/// blocking call in place.
+struct BlockingIo<T>(T);
+
+impl<T: std::io::Write + Unpin> tokio::io::AsyncWrite for BlockingIo<T> {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T: std::io::Read + Unpin> tokio::io::AsyncRead for BlockingIo<T> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let n = self.get_mut().0.read(buf.initialize_unfilled())?;
+        buf.advance(n);
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: std::io::BufRead + Unpin> tokio::io::AsyncBufRead for BlockingIo<T> {
+    fn poll_fill_buf(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<&[u8]>> {
+        std::task::Poll::Ready(self.get_mut().0.fill_buf())
+    }
+
+    fn consume(self: std::pin::Pin<&mut Self>, amt: usize) {
+        self.get_mut().0.consume(amt);
+    }
+}
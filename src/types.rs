@@ -1,5 +1,13 @@
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use serde::{Deserialize, Serialize};
 
+use crate::config::DistanceMetric;
+use crate::error::{Result, SeekDbError};
+use crate::filters::{DocFilter, Filter, JsonDialect};
+
 pub type Document = String;
 pub type Documents = Vec<Document>;
 pub type Embedding = Vec<f32>;
@@ -16,11 +24,129 @@ pub struct Database {
 }
 
 /// Selects which fields to include in query/get responses.
-#[derive(Clone, Copy, Debug)]
+///
+/// `include: None` means documents, metadatas and (for queries) distances.
+/// An explicit list returns exactly the fields it names, plus ids, which are
+/// always returned; `Distances` only applies to queries.
+///
+/// Serializes as the lowercase names `"documents"`, `"metadatas"`,
+/// `"embeddings"` and `"distances"`; both [`FromStr`](std::str::FromStr) and
+/// `Deserialize` accept them in any case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IncludeField {
     Documents,
     Metadatas,
     Embeddings,
+    Distances,
+}
+
+impl IncludeField {
+    /// Every field, embeddings included.
+    pub fn all() -> &'static [IncludeField] {
+        &[
+            IncludeField::Documents,
+            IncludeField::Metadatas,
+            IncludeField::Embeddings,
+            IncludeField::Distances,
+        ]
+    }
+
+    /// No optional fields: ids only.
+    pub fn none() -> &'static [IncludeField] {
+        &[]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncludeField::Documents => "documents",
+            IncludeField::Metadatas => "metadatas",
+            IncludeField::Embeddings => "embeddings",
+            IncludeField::Distances => "distances",
+        }
+    }
+}
+
+impl std::str::FromStr for IncludeField {
+    type Err = SeekDbError;
+
+    /// Parse a field name case-insensitively.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "documents" => Ok(IncludeField::Documents),
+            "metadatas" => Ok(IncludeField::Metadatas),
+            "embeddings" => Ok(IncludeField::Embeddings),
+            "distances" => Ok(IncludeField::Distances),
+            other => Err(SeekDbError::InvalidInput(format!(
+                "unknown include field '{other}'; expected one of documents, metadatas, embeddings, distances"
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for IncludeField {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The optional fields a `get` or `query` call was asked to return, carried
+/// on its result as `included`. Tells a field that was not requested (`None`
+/// and `false` here) apart from one that was requested but matched no rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncludeFlags {
+    pub documents: bool,
+    pub metadatas: bool,
+    pub embeddings: bool,
+    pub distances: bool,
+}
+
+impl IncludeFlags {
+    /// Flags for an `include` argument. `None` stands for the defaults:
+    /// documents, metadatas and distances, but not embeddings.
+    pub fn from_include(include: Option<&[IncludeField]>) -> Self {
+        match include {
+            None => IncludeFlags {
+                documents: true,
+                metadatas: true,
+                embeddings: false,
+                distances: true,
+            },
+            Some(fields) => {
+                let has = |field| fields.contains(&field);
+                IncludeFlags {
+                    documents: has(IncludeField::Documents),
+                    metadatas: has(IncludeField::Metadatas),
+                    embeddings: has(IncludeField::Embeddings),
+                    distances: has(IncludeField::Distances),
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, field: IncludeField) -> bool {
+        match field {
+            IncludeField::Documents => self.documents,
+            IncludeField::Metadatas => self.metadatas,
+            IncludeField::Embeddings => self.embeddings,
+            IncludeField::Distances => self.distances,
+        }
+    }
+}
+
+/// How embeddings are written as vector literals in SQL statements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VectorLiteralFormat {
+    /// The shortest decimal that parses back to the same `f32` (`0.1`, `3`).
+    #[default]
+    Shortest,
+    /// Nine significant digits in scientific notation (`1.00000001e-1`), so
+    /// the value survives a server that parses it as a double before
+    /// narrowing to `f32`. Literals are roughly 1.5x longer.
+    FullPrecision,
 }
 
 /// Result shape for similarity queries (aligns with Python SDK).
@@ -31,6 +157,170 @@ pub struct QueryResult {
     pub metadatas: Option<Vec<Vec<Metadata>>>,
     pub embeddings: Option<Vec<Vec<Embedding>>>,
     pub distances: Option<Vec<Vec<f32>>>,
+    /// Distances at full double precision, filled only when requested with
+    /// `QueryOptions::with_f64_distances`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distances_f64: Option<Vec<Vec<f64>>>,
+    /// The fields the query was asked to return.
+    #[serde(default)]
+    pub included: IncludeFlags,
+}
+
+impl QueryResult {
+    /// Distances converted to similarity scores with
+    /// [`DistanceMetric::similarity`]; pass the collection's metric.
+    /// Empty when the result carries no distances.
+    pub fn similarities(&self, metric: DistanceMetric) -> Vec<Vec<f32>> {
+        self.distances
+            .iter()
+            .flatten()
+            .map(|row| row.iter().map(|d| metric.similarity(*d)).collect())
+            .collect()
+    }
+
+    /// The hits of query `query_index`, one [`QueryHit`] each, nearest first.
+    /// Fields the result does not carry are `None`; an out-of-range index
+    /// yields no hits.
+    pub fn rows(&self, query_index: usize) -> impl Iterator<Item = QueryHit<'_>> {
+        fn column<T>(col: &Option<Vec<Vec<T>>>, q: usize) -> Option<&[T]> {
+            col.as_ref().and_then(|c| c.get(q)).map(Vec::as_slice)
+        }
+        let documents = column(&self.documents, query_index);
+        let metadatas = column(&self.metadatas, query_index);
+        let embeddings = column(&self.embeddings, query_index);
+        let distances = column(&self.distances, query_index);
+        self.ids
+            .get(query_index)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(i, id)| QueryHit {
+                id,
+                document: documents.and_then(|d| d.get(i)).map(String::as_str),
+                metadata: metadatas.and_then(|m| m.get(i)),
+                embedding: embeddings.and_then(|e| e.get(i)).map(Vec::as_slice),
+                distance: distances.and_then(|d| d.get(i)).copied(),
+            })
+    }
+}
+
+/// One hit of a [`QueryResult`], borrowed from its parallel columns; see
+/// [`QueryResult::rows`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryHit<'a> {
+    pub id: &'a str,
+    pub document: Option<&'a str>,
+    pub metadata: Option<&'a Metadata>,
+    pub embedding: Option<&'a [f32]>,
+    pub distance: Option<f32>,
+}
+
+/// Merge per-shard results of the same queries into a global top-`k` per query.
+///
+/// Each input holds the hits of one shard (e.g. one collection), with
+/// `ids[q]` belonging to query `q` in every input. Hits are ordered by
+/// distance in `metric`'s direction (see [`DistanceMetric::higher_is_closer`]);
+/// ties keep input order and hits without a finite distance sort last.
+/// Documents, metadatas, embeddings and both distance columns are kept only
+/// when every input has them; when distances are kept, hits whose distance is
+/// missing, NaN or infinite are dropped, so every merged distance is finite.
+pub fn merge_query_results(
+    results: &[QueryResult],
+    k: usize,
+    metric: DistanceMetric,
+) -> QueryResult {
+    let all = |has: fn(&QueryResult) -> bool| !results.is_empty() && results.iter().all(has);
+    let keep_documents = all(|r| r.documents.is_some());
+    let keep_metadatas = all(|r| r.metadatas.is_some());
+    let keep_embeddings = all(|r| r.embeddings.is_some());
+    let keep_distances = all(|r| r.distances.is_some());
+    let keep_distances_f64 = all(|r| r.distances_f64.is_some());
+
+    let n_queries = results.iter().map(|r| r.ids.len()).max().unwrap_or(0);
+    let worst = if metric.higher_is_closer() {
+        f32::NEG_INFINITY
+    } else {
+        f32::INFINITY
+    };
+
+    let mut merged = QueryResult {
+        ids: Vec::with_capacity(n_queries),
+        documents: keep_documents.then(Vec::new),
+        metadatas: keep_metadatas.then(Vec::new),
+        embeddings: keep_embeddings.then(Vec::new),
+        distances: keep_distances.then(Vec::new),
+        distances_f64: keep_distances_f64.then(Vec::new),
+        included: IncludeFlags {
+            documents: keep_documents,
+            metadatas: keep_metadatas,
+            embeddings: keep_embeddings,
+            distances: keep_distances,
+        },
+    };
+    for q in 0..n_queries {
+        // (input index, hit index within the query, distance)
+        let mut hits: Vec<(usize, usize, f32)> = Vec::new();
+        for (r, result) in results.iter().enumerate() {
+            let Some(ids) = result.ids.get(q) else {
+                continue;
+            };
+            for i in 0..ids.len() {
+                let distance = result
+                    .distances
+                    .as_ref()
+                    .and_then(|d| d.get(q)?.get(i).copied())
+                    .filter(|d| d.is_finite());
+                if keep_distances && distance.is_none() {
+                    continue;
+                }
+                hits.push((r, i, distance.unwrap_or(worst)));
+            }
+        }
+        if metric.higher_is_closer() {
+            hits.sort_by(|a, b| b.2.total_cmp(&a.2));
+        } else {
+            hits.sort_by(|a, b| a.2.total_cmp(&b.2));
+        }
+        hits.truncate(k);
+
+        merged.ids.push(
+            hits.iter()
+                .map(|&(r, i, _)| results[r].ids[q][i].clone())
+                .collect(),
+        );
+        if let Some(out) = merged.documents.as_mut() {
+            out.push(pick(results, &hits, q, |r| r.documents.as_ref()));
+        }
+        if let Some(out) = merged.metadatas.as_mut() {
+            out.push(pick(results, &hits, q, |r| r.metadatas.as_ref()));
+        }
+        if let Some(out) = merged.embeddings.as_mut() {
+            out.push(pick(results, &hits, q, |r| r.embeddings.as_ref()));
+        }
+        if let Some(out) = merged.distances.as_mut() {
+            out.push(hits.iter().map(|&(_, _, d)| d).collect());
+        }
+        if let Some(out) = merged.distances_f64.as_mut() {
+            out.push(pick(results, &hits, q, |r| r.distances_f64.as_ref()));
+        }
+    }
+    merged
+}
+
+/// Collect one per-hit column (documents, metadatas, ...) for query `q` in `hits` order.
+fn pick<T: Clone + Default>(
+    results: &[QueryResult],
+    hits: &[(usize, usize, f32)],
+    q: usize,
+    column: impl Fn(&QueryResult) -> Option<&Vec<Vec<T>>>,
+) -> Vec<T> {
+    hits.iter()
+        .map(|&(r, i, _)| {
+            column(&results[r])
+                .and_then(|c| c.get(q)?.get(i).cloned())
+                .unwrap_or_default()
+        })
+        .collect()
 }
 
 /// Result shape for get/peek calls.
@@ -40,4 +330,379 @@ pub struct GetResult {
     pub documents: Option<Vec<Document>>,
     pub metadatas: Option<Vec<Metadata>>,
     pub embeddings: Option<Vec<Embedding>>,
+    /// The fields the call was asked to return; `distances` is always false.
+    #[serde(default)]
+    pub included: IncludeFlags,
+}
+
+impl GetResult {
+    /// Split the column-oriented result into one [`Record`] per id.
+    pub fn into_records(self) -> Vec<Record> {
+        let mut documents = self.documents.map(Vec::into_iter);
+        let mut metadatas = self.metadatas.map(Vec::into_iter);
+        let mut embeddings = self.embeddings.map(Vec::into_iter);
+        self.ids
+            .into_iter()
+            .map(|id| Record {
+                id,
+                document: documents.as_mut().and_then(Iterator::next),
+                metadata: metadatas.as_mut().and_then(Iterator::next),
+                embedding: embeddings.as_mut().and_then(Iterator::next),
+            })
+            .collect()
+    }
+}
+
+/// A single stored record; fields not requested via `include` are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub id: String,
+    pub document: Option<Document>,
+    pub metadata: Option<Metadata>,
+    pub embedding: Option<Embedding>,
+}
+
+/// One group of a grouped query: the shared value of the grouping field and
+/// the group's hits as a single-query [`QueryResult`], nearest first.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct QueryGroup {
+    /// `metadata[group_field]` of the group's hits; `Null` for hits that lack
+    /// the field.
+    pub key: Metadata,
+    pub hits: QueryResult,
+}
+
+/// Outcome of an `upsert` call: which ids were newly inserted and which
+/// already existed and were updated, each in input order.
+///
+/// An id repeated within one call is reported once, under what happened to
+/// its first occurrence.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpsertReport {
+    pub inserted: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+/// Opaque continuation token for `Collection::get_page` / `get_next_page`.
+///
+/// Carries the collection name, the filters of the original request and the
+/// last id returned, so a stateless caller (e.g. an HTTP handler) can resume
+/// the scan from [`PageToken::encode`]'s string alone. Paging is keyset-based
+/// over `_id`, so rows written between pages are neither skipped nor repeated
+/// unless their id sorts before the cursor.
+///
+/// The encoding is plain base64 JSON, not signed or encrypted: anyone holding
+/// a token can read its filters and forge one with different filters. Do not
+/// rely on a token to enforce access control; callers that hand tokens to
+/// untrusted clients should re-check the filters or wrap the token in a MAC.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PageToken {
+    #[serde(rename = "c")]
+    pub(crate) collection: String,
+    #[serde(rename = "m", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) where_meta: Option<Filter>,
+    #[serde(rename = "d", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) where_doc: Option<DocFilter>,
+    #[serde(rename = "a")]
+    pub(crate) after: String,
+}
+
+impl PageToken {
+    /// URL-safe base64 of the token's JSON form.
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("page token serializes to JSON");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Parse a string produced by [`PageToken::encode`].
+    pub fn decode(token: &str) -> Result<Self> {
+        let invalid = || SeekDbError::InvalidInput("invalid page token".into());
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(|_| invalid())?;
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+
+    /// Name of the collection the token was issued for.
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    /// Metadata filter the next page will apply.
+    pub fn where_meta(&self) -> Option<&Filter> {
+        self.where_meta.as_ref()
+    }
+
+    /// Document filter the next page will apply.
+    pub fn where_doc(&self) -> Option<&DocFilter> {
+        self.where_doc.as_ref()
+    }
+}
+
+/// Structured view of a collection's table, as returned by `Collection::describe`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionSchema {
+    pub name: String,
+    pub dimension: u32,
+    pub distance: DistanceMetric,
+    /// Vector index type (e.g. `"hnsw"`), if the table has a vector index.
+    pub index_type: Option<String>,
+    /// Every `WITH (...)` option of the vector index (`distance`, `lib`, `m`, ...),
+    /// keys and values lower-cased.
+    pub index_params: BTreeMap<String, String>,
+    pub has_fulltext: bool,
+    /// Parser of the full-text index (e.g. `"ik"`), when one is declared.
+    pub fulltext_parser: Option<String>,
+    /// Column names in table order.
+    pub columns: Vec<String>,
+    pub row_count: u64,
+}
+
+/// Summary of one collection, as returned by
+/// `ServerClient::list_collections_detailed`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionInfo {
+    pub name: String,
+    pub dimension: u32,
+    pub distance: DistanceMetric,
+    /// The server's row estimate from `information_schema.TABLES`, which can
+    /// lag recent writes; `None` when the server does not report one. Use
+    /// `Collection::count` for an exact figure.
+    pub row_count: Option<u64>,
+}
+
+/// What `ServerClient::capabilities` found out about the connected server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerCapabilities {
+    /// The server's `VERSION()` string.
+    pub version: String,
+    /// The first [`JsonDialect`] whose probe the server evaluated correctly;
+    /// [`JsonDialect::Function`] when none did.
+    pub json_dialect: JsonDialect,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_result_rows() {
+        let result = QueryResult {
+            ids: vec![vec!["a".into(), "b".into()], vec!["c".into()]],
+            documents: Some(vec![
+                vec!["doc a".into(), "doc b".into()],
+                vec!["doc c".into()],
+            ]),
+            metadatas: None,
+            embeddings: None,
+            distances: Some(vec![vec![0.1, 0.2], vec![0.3]]),
+            distances_f64: None,
+            included: IncludeFlags::default(),
+        };
+        let hits: Vec<QueryHit<'_>> = result.rows(0).collect();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[1].id, "b");
+        assert_eq!(hits[1].document, Some("doc b"));
+        assert_eq!(hits[1].distance, Some(0.2));
+        assert!(hits[1].metadata.is_none() && hits[1].embedding.is_none());
+        assert_eq!(result.rows(1).map(|h| h.id).collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!(result.rows(2).count(), 0);
+    }
+
+    #[test]
+    fn include_field_parses_and_serializes() {
+        for field in IncludeField::all() {
+            assert_eq!(field.as_str().parse::<IncludeField>().unwrap(), *field);
+            assert_eq!(
+                serde_json::to_value(field).unwrap(),
+                serde_json::json!(field.as_str())
+            );
+        }
+        assert_eq!(
+            " Metadatas ".parse::<IncludeField>().unwrap(),
+            IncludeField::Metadatas
+        );
+        assert!("scores".parse::<IncludeField>().is_err());
+
+        let parsed: Vec<IncludeField> =
+            serde_json::from_str(r#"["documents", "distances"]"#).unwrap();
+        assert_eq!(
+            parsed,
+            vec![IncludeField::Documents, IncludeField::Distances]
+        );
+        assert_eq!(
+            serde_json::from_str::<IncludeField>(r#""Documents""#).unwrap(),
+            IncludeField::Documents
+        );
+        assert!(serde_json::from_str::<IncludeField>(r#""scores""#).is_err());
+    }
+
+    #[test]
+    fn include_flags_from_include() {
+        let defaults = IncludeFlags::from_include(None);
+        assert!(defaults.documents && defaults.metadatas && defaults.distances);
+        assert!(!defaults.embeddings);
+
+        let flags = IncludeFlags::from_include(Some(&[IncludeField::Embeddings]));
+        assert_eq!(
+            flags,
+            IncludeFlags {
+                embeddings: true,
+                ..IncludeFlags::default()
+            }
+        );
+        assert!(flags.contains(IncludeField::Embeddings));
+        assert!(!flags.contains(IncludeField::Documents));
+        assert_eq!(
+            IncludeFlags::from_include(Some(IncludeField::none())),
+            IncludeFlags::default()
+        );
+
+        // Results serialized before `included` existed still parse.
+        let old: QueryResult = serde_json::from_str(
+            r#"{"ids": [["a"]], "documents": null, "metadatas": null, "embeddings": null, "distances": null}"#,
+        )
+        .unwrap();
+        assert_eq!(old.included, IncludeFlags::default());
+    }
+
+    #[test]
+    fn get_result_into_records() {
+        let result = GetResult {
+            ids: vec!["a".into(), "b".into()],
+            documents: Some(vec!["da".into(), "db".into()]),
+            metadatas: None,
+            embeddings: Some(vec![vec![1.0], vec![2.0]]),
+            included: IncludeFlags::default(),
+        };
+        let records = result.into_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].id, "b");
+        assert_eq!(records[1].document.as_deref(), Some("db"));
+        assert_eq!(records[1].metadata, None);
+        assert_eq!(records[1].embedding, Some(vec![2.0]));
+    }
+
+    #[test]
+    fn page_token_roundtrip() {
+        let token = PageToken {
+            collection: "docs".into(),
+            where_meta: Some(Filter::Eq {
+                field: "user".into(),
+                value: serde_json::json!("x"),
+            }),
+            where_doc: Some(DocFilter::Contains("rust".into())),
+            after: "id-42".into(),
+        };
+        let encoded = token.encode();
+        assert!(
+            encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_eq!(PageToken::decode(&encoded).unwrap(), token);
+        assert!(PageToken::decode("not a token").is_err());
+        assert!(PageToken::decode(&URL_SAFE_NO_PAD.encode(b"{}")).is_err());
+    }
+
+    fn shard(ids: &[&str], distances: &[f32]) -> QueryResult {
+        QueryResult {
+            ids: vec![ids.iter().map(|s| s.to_string()).collect()],
+            documents: Some(vec![ids.iter().map(|s| format!("doc-{s}")).collect()]),
+            distances: Some(vec![distances.to_vec()]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_keeps_global_top_k_ascending() {
+        let merged = merge_query_results(
+            &[
+                shard(&["a", "b"], &[0.1, 0.5]),
+                shard(&["c", "d"], &[0.2, 0.3]),
+            ],
+            3,
+            DistanceMetric::L2,
+        );
+        assert_eq!(merged.ids, vec![vec!["a", "c", "d"]]);
+        assert_eq!(merged.distances, Some(vec![vec![0.1, 0.2, 0.3]]));
+        assert_eq!(
+            merged.documents,
+            Some(vec![vec![
+                "doc-a".to_string(),
+                "doc-c".to_string(),
+                "doc-d".to_string()
+            ]])
+        );
+        assert_eq!(merged.metadatas, None);
+    }
+
+    #[test]
+    fn merge_inner_product_prefers_larger() {
+        let merged = merge_query_results(
+            &[shard(&["a", "b"], &[0.9, 0.1]), shard(&["c"], &[0.5])],
+            2,
+            DistanceMetric::InnerProduct,
+        );
+        assert_eq!(merged.ids, vec![vec!["a", "c"]]);
+    }
+
+    #[test]
+    fn merge_drops_non_finite_distances() {
+        let merged = merge_query_results(
+            &[
+                shard(&["a", "b"], &[f32::NEG_INFINITY, 0.5]),
+                shard(&["c", "d"], &[f32::NAN, f32::INFINITY]),
+            ],
+            4,
+            DistanceMetric::L2,
+        );
+        assert_eq!(merged.ids, vec![vec!["b"]]);
+        assert_eq!(merged.distances, Some(vec![vec![0.5]]));
+        assert_eq!(merged.documents, Some(vec![vec!["doc-b".to_string()]]));
+    }
+
+    #[test]
+    fn merge_handles_multiple_queries_and_missing_columns() {
+        let mut left = shard(&["a"], &[0.4]);
+        left.ids.push(vec!["x".into()]);
+        left.distances.as_mut().unwrap().push(vec![0.2]);
+        left.documents = None;
+        let right = shard(&["b"], &[0.3]);
+
+        let merged = merge_query_results(&[left, right], 5, DistanceMetric::Cosine);
+        assert_eq!(merged.ids, vec![vec!["b", "a"], vec!["x"]]);
+        assert_eq!(merged.documents, None);
+        assert!(
+            merge_query_results(&[], 3, DistanceMetric::L2)
+                .ids
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn similarities_follow_metric_direction() {
+        let result = QueryResult {
+            ids: vec![vec!["a".into(), "b".into()]],
+            distances: Some(vec![vec![0.0, 0.5]]),
+            ..Default::default()
+        };
+        assert_eq!(
+            result.similarities(DistanceMetric::Cosine),
+            vec![vec![1.0, 0.5]]
+        );
+        assert_eq!(
+            result.similarities(DistanceMetric::L2),
+            vec![vec![1.0, 1.0 / 1.5]]
+        );
+        assert_eq!(
+            result.similarities(DistanceMetric::InnerProduct),
+            vec![vec![0.0, 0.5]]
+        );
+        assert!(
+            QueryResult::default()
+                .similarities(DistanceMetric::Cosine)
+                .is_empty()
+        );
+    }
 }
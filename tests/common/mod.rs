@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
@@ -22,6 +24,57 @@ pub fn ts_suffix() -> String {
     ts.to_string()
 }
 
+/// Run a test body against a fresh database and collection, then drop the
+/// database again whether the body passed, failed or panicked.
+///
+/// Expands to an early `return Ok(())` when integration tests are disabled, so
+/// use it as the whole body of an `async fn ... -> anyhow::Result<()>` test.
+/// `$client` is bound to a `ServerClient` on the new database and `$coll` to a
+/// `Collection<DummyEmbedding>` created with `$hnsw`; the body is a block
+/// returning `anyhow::Result<()>`.
+///
+/// ```ignore
+/// with_test_collection!(HnswConfig::new(3, DistanceMetric::L2), |client, coll| {
+///     coll.add_batch(AddBatch::new(&ids).embeddings(&embs)).await?;
+///     assert_eq!(coll.count().await?, 2);
+///     Ok(())
+/// })
+/// ```
+#[allow(unused_macros)]
+macro_rules! with_test_collection {
+    ($hnsw:expr, |$client:ident, $coll:ident| $body:block) => {{
+        let Some(config) = $crate::common::load_config_for_integration() else {
+            return Ok(());
+        };
+        let admin = seekdb_rs::ServerClient::from_config(config.clone()).await?;
+        let db_name = format!("rs_test_{}", $crate::common::ts_suffix());
+        admin.create_database(&db_name, None).await?;
+
+        let outcome = futures::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(async {
+            let mut db_config = config;
+            db_config.database = db_name.clone();
+            let $client = seekdb_rs::ServerClient::from_config(db_config).await?;
+            let coll_name = format!("coll_{}", $crate::common::ts_suffix());
+            let $coll = $client
+                .create_collection::<$crate::common::DummyEmbedding>(
+                    &coll_name,
+                    Some($hnsw),
+                    None::<$crate::common::DummyEmbedding>,
+                )
+                .await?;
+            let result: anyhow::Result<()> = $body;
+            result
+        }))
+        .await;
+
+        admin.delete_database(&db_name, None).await.ok();
+        // Re-raise a failed assertion only after the database is gone.
+        outcome.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }};
+}
+#[allow(unused_imports)]
+pub(crate) use with_test_collection;
+
 /// Dummy embedding function to satisfy type parameters; not used in these tests.
 pub struct DummyEmbedding;
 
@@ -58,3 +111,22 @@ impl EmbeddingFunction for ConstantEmbedding {
         self.dim
     }
 }
+
+/// Constant embedding that fails for blank texts, to exercise per-text error handling.
+pub struct RejectBlankEmbedding {
+    pub dim: usize,
+}
+
+#[async_trait::async_trait]
+impl EmbeddingFunction for RejectBlankEmbedding {
+    async fn embed_documents(&self, docs: &[String]) -> Result<Embeddings, SeekDbError> {
+        if docs.iter().any(|d| d.trim().is_empty()) {
+            return Err(SeekDbError::Embedding("cannot embed blank text".into()));
+        }
+        Ok(vec![vec![0.5; self.dim]; docs.len()])
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+}
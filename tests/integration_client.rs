@@ -4,10 +4,12 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use seekdb_rs::{AdminApi, AdminClient, ServerClient};
+use seekdb_rs::{
+    AdminApi, AdminClient, DistanceMetric, HnswConfig, OverloadPolicy, SeekDbError, ServerClient,
+};
 
 mod common;
-use common::{load_config_for_integration, ts_suffix};
+use common::{DummyEmbedding, load_config_for_integration, ts_suffix};
 
 /// Smoke test for a basic `ServerClient` connection using explicit parameters.
 #[tokio::test]
@@ -103,3 +105,78 @@ async fn admin_database_crud() -> Result<()> {
 
     Ok(())
 }
+
+/// Collections can be created and opened through an explicit tenant; an
+/// unknown tenant is reported as a connection error naming it.
+#[tokio::test]
+async fn client_collection_in_tenant() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+
+    let client = ServerClient::from_config(config.clone()).await?;
+    let name = format!("rs_tenant_{}", ts_suffix());
+
+    let created = client
+        .create_collection_in_tenant::<DummyEmbedding>(
+            &name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None,
+            Some(&config.tenant),
+        )
+        .await?;
+    assert_eq!(created.tenant(), config.tenant);
+
+    let fetched = client
+        .get_collection_in_tenant::<DummyEmbedding>(&name, None, None)
+        .await?;
+    assert_eq!(fetched.dimension(), 3);
+
+    let missing = format!("no_such_tenant_{}", ts_suffix());
+    let err = client
+        .get_collection_in_tenant::<DummyEmbedding>(&name, None, Some(&missing))
+        .await
+        .err()
+        .expect("unknown tenant should fail");
+    assert!(err.to_string().contains(&missing), "{err}");
+
+    client.delete_collection(&name).await?;
+    Ok(())
+}
+
+/// `ping` succeeds against a live server.
+#[tokio::test]
+async fn client_ping() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+    client.ping().await?;
+    Ok(())
+}
+
+/// `ping` bypasses the in-flight limit: it still answers while the only slot
+/// is held, even though other statements are rejected.
+#[tokio::test]
+async fn client_ping_bypasses_inflight_limit() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config)
+        .await?
+        .with_max_inflight(1, OverloadPolicy::Reject);
+
+    let busy = client.fetch_all("SELECT SLEEP(1)");
+    let probe = async {
+        while client.inflight() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let rejected = client.fetch_all("SELECT 1").await;
+        assert!(matches!(rejected, Err(SeekDbError::Overloaded { .. })));
+        client.ping().await
+    };
+    let (busy, probe) = tokio::join!(busy, probe);
+    busy?;
+    probe?;
+    Ok(())
+}
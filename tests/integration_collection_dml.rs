@@ -2,13 +2,15 @@
 
 use anyhow::Result;
 use seekdb_rs::{
-    AddBatch, DeleteQuery, DistanceMetric, Filter, GetQuery, HnswConfig, IncludeField,
-    SeekDbError, ServerClient, UpdateBatch, UpsertBatch,
+    AddBatch, DeleteQuery, DistanceMetric, Filter, GetQuery, HnswConfig, IncludeField, OnConflict,
+    PageToken, SeekDbError, ServerClient, UpdateBatch, UpsertBatch,
 };
 use serde_json::json;
 
 mod common;
-use common::{ConstantEmbedding, DummyEmbedding, load_config_for_integration, ts_suffix};
+use common::{
+    ConstantEmbedding, DummyEmbedding, load_config_for_integration, ts_suffix, with_test_collection,
+};
 
 /// Creating a collection without HnswConfig should return a config error.
 #[tokio::test]
@@ -38,6 +40,37 @@ async fn collection_create_without_hnsw_config_errors() -> Result<()> {
     Ok(())
 }
 
+/// Creating a collection that already exists should point at get_collection.
+#[tokio::test]
+async fn collection_create_existing_errors() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+
+    let client = ServerClient::from_config(config).await?;
+    let name = format!("dup_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
+    client
+        .create_collection::<DummyEmbedding>(&name, Some(hnsw.clone()), None::<DummyEmbedding>)
+        .await?;
+
+    let res = client
+        .create_collection::<DummyEmbedding>(&name, Some(hnsw), None::<DummyEmbedding>)
+        .await;
+    match res {
+        Err(SeekDbError::InvalidInput(msg)) => {
+            assert!(
+                msg.contains("collection already exists") && msg.contains("get_collection"),
+                "unexpected invalid-input message: {msg}"
+            );
+        }
+        other => panic!("expected SeekDbError::InvalidInput, got: {:?}", other.err()),
+    }
+
+    client.delete_collection(&name).await.ok();
+    Ok(())
+}
+
 /// Invalid embedding dimension should surface as SeekDbError::InvalidInput.
 #[tokio::test]
 async fn collection_add_invalid_embedding_dimension_errors() -> Result<()> {
@@ -53,10 +86,7 @@ async fn collection_add_invalid_embedding_dimension_errors() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("invalid_dim_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -98,10 +128,7 @@ async fn collection_add_with_auto_embedding() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("auto_emb_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let ef = ConstantEmbedding { value: 0.5, dim: 3 };
     let coll = client
         .create_collection::<ConstantEmbedding>(&coll_name, Some(hnsw), Some(ef))
@@ -151,10 +178,7 @@ async fn collection_add_length_mismatch_errors() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("len_mismatch_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -181,6 +205,250 @@ async fn collection_add_length_mismatch_errors() -> Result<()> {
     Ok(())
 }
 
+/// Inserts are chunked by insert_batch_size. By default a failing chunk rolls
+/// back the whole call; with no_transaction, earlier chunks stay and are
+/// reported via PartialWrite.
+#[tokio::test]
+async fn collection_add_batched_inserts_transaction_and_partial_writes() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_batch_insert_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    let client = ServerClient::from_config(db_config).await?;
+
+    let coll_name = format!("batch_insert_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
+    let coll = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
+        .await?
+        .with_insert_batch_size(2);
+
+    let ids: Vec<String> = (0..5).map(|i| format!("b{i}")).collect();
+    let embs: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 0.0, 1.0]).collect();
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embs))
+        .await?;
+    assert_eq!(coll.count().await?, 5);
+
+    // Chunks: [n0, n1], [n2, b0] -> the second chunk hits a duplicate key.
+    let ids: Vec<String> = vec!["n0".into(), "n1".into(), "n2".into(), "b0".into()];
+    let embs: Vec<Vec<f32>> = vec![vec![1.0, 1.0, 1.0]; 4];
+
+    // Default: the whole call is rolled back.
+    let res = coll.add_batch(AddBatch::new(&ids).embeddings(&embs)).await;
+    assert!(
+        matches!(
+            res,
+            Err(SeekDbError::Database {
+                code: Some(1062),
+                ..
+            })
+        ),
+        "got: {res:?}"
+    );
+    assert_eq!(coll.count().await?, 5);
+
+    // Autocommit: the first chunk stays and is reported.
+    let coll = coll.no_transaction();
+    let res = coll.add_batch(AddBatch::new(&ids).embeddings(&embs)).await;
+    match res {
+        Err(SeekDbError::PartialWrite { written, .. }) => assert_eq!(written, 2),
+        other => panic!("expected SeekDbError::PartialWrite, got: {:?}", other),
+    }
+    assert_eq!(coll.count().await?, 7);
+
+    client.delete_collection(&coll_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
+
+/// describe() aggregates dimension, metric, index details, columns and row count.
+#[tokio::test]
+async fn collection_describe_schema() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_describe_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    let client = ServerClient::from_config(db_config).await?;
+
+    let coll_name = format!("describe_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
+    let coll = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
+        .await?;
+    let ids = vec!["d1".to_string(), "d2".to_string()];
+    let embs = vec![vec![1.0_f32, 0.0, 0.0], vec![0.0_f32, 1.0, 0.0]];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embs))
+        .await?;
+
+    let schema = coll.describe().await?;
+    assert_eq!(schema.name, coll_name);
+    assert_eq!(schema.dimension, 3);
+    assert_eq!(schema.distance, DistanceMetric::Cosine);
+    assert_eq!(schema.index_type.as_deref(), Some("hnsw"));
+    assert!(schema.has_fulltext);
+    assert_eq!(schema.fulltext_parser.as_deref(), Some("ik"));
+    for col in ["_id", "document", "embedding", "metadata"] {
+        assert!(schema.columns.iter().any(|c| c == col), "missing {col}");
+    }
+    assert_eq!(schema.row_count, 2);
+
+    client.delete_collection(&coll_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
+
+/// `count_where` applies metadata, document and id filters.
+#[tokio::test]
+async fn collection_count_where_filters() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("count_where_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["c1".to_string(), "c2".to_string(), "c3".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0],
+            ])
+            .metadatas(&[json!({"tag":"a"}), json!({"tag":"a"}), json!({"tag":"b"})])
+            .documents(&["red apple".into(), "green pear".into(), "red cherry".into()]),
+    )
+    .await?;
+
+    assert_eq!(coll.count().await?, 3);
+    let tag_a = Filter::Eq {
+        field: "tag".into(),
+        value: json!("a"),
+    };
+    assert_eq!(coll.count_where(Some(&tag_a), None, None).await?, 2);
+    assert_eq!(coll.count_where(None, None, Some(&ids[1..])).await?, 2);
+    assert_eq!(
+        coll.count_where(Some(&tag_a), None, Some(&ids[1..]))
+            .await?,
+        1
+    );
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `upsert` reports which ids were inserted and which updated an existing row.
+#[tokio::test]
+async fn collection_upsert_reports_inserted_and_updated() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("upsert_report_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let seeded = vec!["seeded".to_string()];
+    coll.add_batch(AddBatch::new(&seeded).embeddings(&[vec![1.0, 0.0, 0.0]]))
+        .await?;
+
+    let ids = vec![
+        "fresh".to_string(),
+        "seeded".to_string(),
+        "fresh".to_string(),
+    ];
+    let report = coll
+        .upsert_batch(
+            UpsertBatch::new(&ids)
+                .embeddings(&[
+                    vec![0.0, 1.0, 0.0],
+                    vec![0.0, 0.0, 1.0],
+                    vec![1.0, 1.0, 0.0],
+                ])
+                .metadatas(&[json!({"n": 1}), json!({"n": 2}), json!({"n": 3})]),
+        )
+        .await?;
+    assert_eq!(report.inserted, vec!["fresh".to_string()]);
+    assert_eq!(report.updated, vec!["seeded".to_string()]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Metadata given at creation is stored with the table and read back by get_collection.
+#[tokio::test]
+async fn collection_metadata_roundtrip() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_coll_meta_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    let client = ServerClient::from_config(db_config.clone()).await?;
+
+    let coll_name = format!("coll_meta_{}", ts_suffix());
+    let meta = json!({"description": "it's a test", "owner": "rust-sdk"});
+    let coll = client
+        .create_collection_with_metadata::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None,
+            Some(meta.clone()),
+        )
+        .await?;
+    assert_eq!(coll.metadata(), Some(&meta));
+
+    // A fresh client (new connections) sees the same metadata.
+    let reconnected = ServerClient::from_config(db_config).await?;
+    let coll = reconnected
+        .get_collection::<DummyEmbedding>(&coll_name, None)
+        .await?;
+    assert_eq!(coll.metadata(), Some(&meta));
+
+    // Collections created without metadata report none.
+    let plain_name = format!("coll_no_meta_{}", ts_suffix());
+    client
+        .create_collection::<DummyEmbedding>(
+            &plain_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None,
+        )
+        .await?;
+    let plain = client
+        .get_collection::<DummyEmbedding>(&plain_name, None)
+        .await?;
+    assert_eq!(plain.metadata(), None);
+
+    client.delete_collection(&coll_name).await.ok();
+    client.delete_collection(&plain_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
+
 /// Full DML roundtrip: add/update/upsert/delete/count/peek.
 #[tokio::test]
 async fn collection_dml_roundtrip() -> Result<()> {
@@ -198,10 +466,7 @@ async fn collection_dml_roundtrip() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -221,16 +486,18 @@ async fn collection_dml_roundtrip() -> Result<()> {
 
     // Basic get
     let got = coll
-        .get_query(GetQuery::by_ids(&[id1.clone()]))
+        .get_query(GetQuery::by_ids(std::slice::from_ref(&id1)))
         .await?;
     assert_eq!(got.ids.len(), 1);
 
     // Update metadata only
-    coll.update_batch(
-        UpdateBatch::new(&[id1.clone()])
-            .metadatas(&[json!({"category":"a","updated":true})]),
-    )
-    .await?;
+    let updated = coll
+        .update_batch(
+            UpdateBatch::new(std::slice::from_ref(&id1))
+                .metadatas(&[json!({"category":"a","updated":true})]),
+        )
+        .await?;
+    assert_eq!(updated, 1);
 
     // Upsert existing and new
     coll.upsert_batch(
@@ -242,14 +509,23 @@ async fn collection_dml_roundtrip() -> Result<()> {
     .await?;
 
     // Delete by id
-    coll.delete_query(DeleteQuery::by_ids(&[id2.clone()]))
+    let deleted = coll
+        .delete_query(DeleteQuery::by_ids(std::slice::from_ref(&id2)))
         .await?;
+    assert_eq!(deleted, 1);
     // Delete by metadata filter
-    coll.delete_query(DeleteQuery::new().with_where_meta(&Filter::Eq {
-        field: "category".into(),
-        value: json!("remove"),
-    }))
-    .await?;
+    let deleted = coll
+        .delete_query(DeleteQuery::new().with_where_meta(&Filter::Eq {
+            field: "category".into(),
+            value: json!("remove"),
+        }))
+        .await?;
+    assert_eq!(deleted, 1);
+    // Nothing left to match
+    let deleted = coll
+        .delete_query(DeleteQuery::by_ids(std::slice::from_ref(&id2)))
+        .await?;
+    assert_eq!(deleted, 0);
 
     // Count and peek
     let cnt = coll.count().await?;
@@ -278,10 +554,7 @@ async fn collection_quickstart_like_flow() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("quickstart_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
 
     // Use the boxed trait-object type parameter as shown in README.
     let coll = client
@@ -339,10 +612,7 @@ async fn collection_upsert_metadata_and_partial_fields() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("upsert_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -351,7 +621,7 @@ async fn collection_upsert_metadata_and_partial_fields() -> Result<()> {
 
     // Seed record
     coll.add_batch(
-        AddBatch::new(&[id.clone()])
+        AddBatch::new(std::slice::from_ref(&id))
             .embeddings(&[vec![1.0, 2.0, 3.0]])
             .metadatas(&[json!({"field": "orig", "cnt": 1})])
             .documents(&["orig_doc".to_string()]),
@@ -360,37 +630,37 @@ async fn collection_upsert_metadata_and_partial_fields() -> Result<()> {
 
     // 1) metadata-only upsert: update cnt, keep doc and embedding
     coll.upsert_batch(
-        UpsertBatch::new(&[id.clone()])
+        UpsertBatch::new(std::slice::from_ref(&id))
             .metadatas(&[json!({"field": "orig", "cnt": 2})]),
     )
     .await?;
 
     let got1 = coll
-        .get_query(GetQuery::by_ids(&[id.clone()]))
+        .get_query(GetQuery::by_ids(std::slice::from_ref(&id)))
         .await?;
     assert_eq!(got1.documents.as_ref().unwrap()[0], "orig_doc");
     assert_eq!(got1.metadatas.as_ref().unwrap()[0]["cnt"], 2);
 
     // 2) document-only upsert: change doc, keep metadata and embedding
     coll.upsert_batch(
-        UpsertBatch::new(&[id.clone()]).documents(&["new_doc".to_string()]),
+        UpsertBatch::new(std::slice::from_ref(&id)).documents(&["new_doc".to_string()]),
     )
     .await?;
     let got2 = coll
-        .get_query(GetQuery::by_ids(&[id.clone()]))
+        .get_query(GetQuery::by_ids(std::slice::from_ref(&id)))
         .await?;
     assert_eq!(got2.documents.as_ref().unwrap()[0], "new_doc");
     assert_eq!(got2.metadatas.as_ref().unwrap()[0]["cnt"], 2);
 
     // 3) embeddings-only upsert: change vector, keep doc and metadata
     coll.upsert_batch(
-        UpsertBatch::new(&[id.clone()]).embeddings(&[vec![3.0, 2.0, 1.0]]),
+        UpsertBatch::new(std::slice::from_ref(&id)).embeddings(&[vec![3.0, 2.0, 1.0]]),
     )
     .await?;
 
     let got3 = coll
         .get_query(
-            GetQuery::by_ids(&[id.clone()]).with_include(&[
+            GetQuery::by_ids(std::slice::from_ref(&id)).with_include(&[
                 IncludeField::Embeddings,
                 IncludeField::Documents,
                 IncludeField::Metadatas,
@@ -422,10 +692,7 @@ async fn collection_delete_without_any_condition_errors() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("delete_guard_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -467,10 +734,7 @@ async fn collection_list_and_has() -> Result<()> {
     db_config.database = db_name.clone();
     let client = ServerClient::from_config(db_config).await?;
 
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll1 = format!("list_coll1_{}", ts_suffix());
     let coll2 = format!("list_coll2_{}", ts_suffix());
     client
@@ -480,7 +744,7 @@ async fn collection_list_and_has() -> Result<()> {
         .create_collection::<DummyEmbedding>(&coll2, Some(hnsw), None::<DummyEmbedding>)
         .await?;
 
-    let names = client.list_collections().await?;
+    let names = client.list_collections(None, None).await?;
     assert!(names.contains(&coll1));
     assert!(names.contains(&coll2));
     assert!(client.has_collection(&coll1).await?);
@@ -497,3 +761,1091 @@ async fn collection_list_and_has() -> Result<()> {
     admin.delete_database(&db_name, None).await.ok();
     Ok(())
 }
+
+/// `add_stream` inserts every item in batches and reports the total.
+#[tokio::test]
+async fn collection_add_stream_batches() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("add_stream_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+
+    let items = (0..5).map(|i| {
+        (
+            format!("s{i}"),
+            vec![i as f32, 0.0, 1.0],
+            (i % 2 == 0).then(|| json!({"i": i})),
+            Some(format!("doc {i}")),
+        )
+    });
+    let stored = coll.add_stream(futures::stream::iter(items), 2).await?;
+    assert_eq!(stored, 5);
+    assert_eq!(coll.count().await?, 5);
+
+    // A bad item stops the stream; the batch before it is already stored.
+    let items = vec![
+        ("t0".to_string(), vec![1.0, 1.0, 1.0], None, None),
+        ("t1".to_string(), vec![1.0, 1.0, 1.0], None, None),
+        ("t2".to_string(), vec![1.0, 1.0], None, None),
+    ];
+    match coll.add_stream(futures::stream::iter(items), 2).await {
+        Err(SeekDbError::PartialWrite { written, .. }) => assert_eq!(written, 2),
+        other => panic!("expected PartialWrite, got: {:?}", other),
+    }
+    assert_eq!(coll.count().await?, 7);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `export_npy` writes a numpy header, f32 rows in `_id` order and one id per line.
+#[tokio::test]
+async fn collection_export_npy() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("export_npy_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(2, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["b".to_string(), "a".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![3.0, 4.0], vec![1.0, 2.0]]))
+        .await?;
+
+    let mut vectors = Vec::new();
+    let mut id_lines = Vec::new();
+    assert_eq!(coll.export_npy(&mut vectors, &mut id_lines).await?, 2);
+
+    assert!(vectors.starts_with(b"\x93NUMPY"));
+    let header_len = 10 + u16::from_le_bytes([vectors[8], vectors[9]]) as usize;
+    let header = String::from_utf8_lossy(&vectors[..header_len]);
+    assert!(header.contains("'shape': (2, 2)"), "{header}");
+    let data: Vec<f32> = vectors[header_len..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(String::from_utf8(id_lines)?, "\"a\"\n\"b\"\n");
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `validate_dimensions` flags rows whose embedding does not match the collection dimension.
+#[tokio::test]
+async fn collection_validate_dimensions_flags_bad_rows() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("validate_dims_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["ok1".to_string(), "ok2".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]))
+        .await?;
+    assert!(coll.validate_dimensions().await?.is_empty());
+
+    // A row written behind the SDK's back without an embedding.
+    client
+        .execute(&format!(
+            "INSERT INTO `{}` (_id, document) VALUES ('no_vec', 'orphan')",
+            seekdb_rs::CollectionNames::table_name(&coll_name)
+        ))
+        .await?;
+    assert_eq!(
+        coll.validate_dimensions().await?,
+        vec!["no_vec".to_string()]
+    );
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `delete_where_streaming` removes matches in batches and reports running totals.
+#[tokio::test]
+async fn collection_delete_where_streaming() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("del_stream_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..7).map(|i| format!("d{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..7).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    let metadatas: Vec<serde_json::Value> = (0..7)
+        .map(|i| json!({"user": if i < 5 { "x" } else { "y" }}))
+        .collect();
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&embeddings)
+            .metadatas(&metadatas),
+    )
+    .await?;
+
+    let user_x = Filter::Eq {
+        field: "user".into(),
+        value: json!("x"),
+    };
+    let mut reported = Vec::new();
+    let deleted = coll
+        .delete_where_streaming(&user_x, 2, |n| reported.push(n))
+        .await?;
+    assert_eq!(deleted, 5);
+    assert_eq!(reported, vec![2, 4, 5]);
+    assert_eq!(coll.count().await?, 2);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `count_approx` reads table statistics; it only has to succeed and stay
+/// within a sane range for a freshly written table.
+#[tokio::test]
+async fn collection_count_approx() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("count_approx_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["a1".to_string(), "a2".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]))
+        .await?;
+
+    let approx = coll.count_approx().await?;
+    assert!(approx <= 1000, "unexpected approximate count {approx}");
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `get_page` / `get_next_page` walk a filtered collection through an encoded token.
+#[tokio::test]
+async fn collection_get_page_tokens() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("page_token_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..5).map(|i| format!("p{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    let metadatas: Vec<serde_json::Value> = (0..5).map(|i| json!({"keep": i != 2})).collect();
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&embeddings)
+            .metadatas(&metadatas),
+    )
+    .await?;
+
+    let keep = Filter::Eq {
+        field: "keep".into(),
+        value: json!(true),
+    };
+    let (first, token) = coll.get_page(Some(&keep), None, 2, Some(&[])).await?;
+    assert_eq!(first.ids, vec!["p0".to_string(), "p1".to_string()]);
+    let mut seen = first.ids;
+    let mut token = token.map(|t| t.encode());
+    while let Some(encoded) = token {
+        let decoded = PageToken::decode(&encoded)?;
+        let (page, next) = coll.get_next_page(&decoded, 2, Some(&[])).await?;
+        seen.extend(page.ids);
+        token = next.map(|t| t.encode());
+    }
+    assert_eq!(seen, vec!["p0", "p1", "p3", "p4"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `iter_all` yields every record in id order and can resume after a given id.
+#[tokio::test]
+async fn collection_iter_all_resume() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("iter_all_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..5).map(|i| format!("r{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embeddings))
+        .await?;
+
+    let all: Vec<_> = coll.iter_all(2, None, None).try_collect().await?;
+    let got: Vec<&str> = all.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(got, vec!["r0", "r1", "r2", "r3", "r4"]);
+    assert_eq!(all[3].embedding.as_deref(), Some(&[3.0, 0.0, 0.0][..]));
+
+    let rest: Vec<_> = coll
+        .iter_all(2, Some(&[]), Some("r2".to_string()))
+        .try_collect()
+        .await?;
+    let got: Vec<&str> = rest.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(got, vec!["r3", "r4"]);
+    assert!(rest[0].embedding.is_none());
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// A document collation is applied at creation; malformed names are rejected.
+#[tokio::test]
+async fn collection_document_collation() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("collation_{}", ts_suffix());
+    let bad = HnswConfig::new(3, DistanceMetric::L2).with_document_collation("bin; DROP TABLE x");
+    let err = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(bad), None::<DummyEmbedding>)
+        .await
+        .err()
+        .expect("malformed collation must be rejected");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)), "{err:?}");
+
+    let cfg = HnswConfig::new(3, DistanceMetric::L2).with_document_collation("utf8mb4_unicode_ci");
+    let coll = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(cfg), None::<DummyEmbedding>)
+        .await?;
+    let ids = vec!["c1".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![1.0, 0.0, 0.0]])
+            .documents(&["Crème brûlée".into()]),
+    )
+    .await?;
+    assert_eq!(coll.count().await?, 1);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `merge_from` copies rows across collections, honouring the conflict
+/// strategy, and refuses collections with a different dimension.
+#[tokio::test]
+async fn collection_merge_from() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let suffix = ts_suffix();
+    let create = |name: String, dim: u32| {
+        let client = &client;
+        async move {
+            client
+                .create_collection::<DummyEmbedding>(
+                    &name,
+                    Some(HnswConfig::new(dim, DistanceMetric::L2)),
+                    None::<DummyEmbedding>,
+                )
+                .await
+        }
+    };
+    let source = create(format!("merge_src_{suffix}"), 3).await?;
+    let target = create(format!("merge_dst_{suffix}"), 3).await?;
+    let other = create(format!("merge_dim_{suffix}"), 4).await?;
+
+    let src_ids = vec!["m1".to_string(), "m2".to_string()];
+    source
+        .add_batch(
+            AddBatch::new(&src_ids)
+                .embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]])
+                .documents(&["from source".into(), "from source".into()]),
+        )
+        .await?;
+    let dst_ids = vec!["m1".to_string()];
+    target
+        .add_batch(
+            AddBatch::new(&dst_ids)
+                .embeddings(&[vec![0.0, 0.0, 1.0]])
+                .documents(&["from target".into()]),
+        )
+        .await?;
+
+    let err = target
+        .merge_from(&source, OnConflict::Error)
+        .await
+        .expect_err("existing id must be reported");
+    assert_eq!(err.database_code(), Some(1062), "{err:?}");
+
+    assert_eq!(target.merge_from(&source, OnConflict::Skip).await?, 1);
+    let docs = target
+        .get_query(GetQuery::by_ids(&src_ids).with_include(&[IncludeField::Documents]))
+        .await?
+        .documents
+        .unwrap_or_default();
+    assert_eq!(docs, vec!["from target", "from source"]);
+
+    assert_eq!(target.merge_from(&source, OnConflict::Replace).await?, 2);
+    let docs = target
+        .get_query(GetQuery::by_ids(&dst_ids).with_include(&[IncludeField::Documents]))
+        .await?
+        .documents
+        .unwrap_or_default();
+    assert_eq!(docs, vec!["from source"]);
+
+    let err = other
+        .merge_from(&source, OnConflict::Replace)
+        .await
+        .expect_err("dimension mismatch must be rejected");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)), "{err:?}");
+
+    for coll in [&source, &target, &other] {
+        client.delete_collection(coll.name()).await.ok();
+    }
+    Ok(())
+}
+
+/// `schema_compatible_with` accepts an identical schema and names a
+/// dimension mismatch.
+#[tokio::test]
+async fn collection_schema_compatible_with() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let suffix = ts_suffix();
+    let small = client
+        .create_collection::<DummyEmbedding>(
+            &format!("schema_a_{suffix}"),
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let large = client
+        .create_collection::<DummyEmbedding>(
+            &format!("schema_b_{suffix}"),
+            Some(HnswConfig::new(4, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+
+    small
+        .schema_compatible_with(&small.describe().await?)
+        .await?;
+    let err = small
+        .schema_compatible_with(&large.describe().await?)
+        .await
+        .expect_err("dimension mismatch must be rejected");
+    assert!(err.to_string().contains("dimension 3 != 4"), "{err}");
+
+    client.delete_collection(small.name()).await.ok();
+    client.delete_collection(large.name()).await.ok();
+    Ok(())
+}
+
+/// A boxed collection can swap its embedding function; later writes use the
+/// new one.
+#[tokio::test]
+async fn collection_swap_embedding_function() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("swap_ef_{}", ts_suffix());
+    let mut coll = client
+        .create_collection::<ConstantEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            Some(ConstantEmbedding { value: 1.0, dim: 3 }),
+        )
+        .await?
+        .boxed();
+    let first = vec!["v1".to_string()];
+    coll.add_batch(AddBatch::new(&first).documents(&["old model".into()]))
+        .await?;
+
+    coll.set_embedding_function(Box::new(ConstantEmbedding { value: 2.0, dim: 3 }));
+    let second = vec!["v2".to_string()];
+    coll.add_batch(AddBatch::new(&second).documents(&["new model".into()]))
+        .await?;
+
+    let ids = vec!["v1".to_string(), "v2".to_string()];
+    let got = coll
+        .get_query(GetQuery::by_ids(&ids).with_include(&[IncludeField::Embeddings]))
+        .await?;
+    assert_eq!(
+        got.embeddings,
+        Some(vec![vec![1.0, 1.0, 1.0], vec![2.0, 2.0, 2.0]])
+    );
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `export_jsonl` writes one parsable record per row, in id order.
+#[tokio::test]
+async fn collection_export_jsonl() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("export_jsonl_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["j2".to_string(), "j1".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![0.0, 1.0, 0.0], vec![1.0, 0.0, 0.0]])
+            .metadatas(&[json!({"n": 2}), json!({"n": 1})])
+            .documents(&["two".into(), "one".into()]),
+    )
+    .await?;
+
+    let mut out = Vec::new();
+    assert_eq!(coll.export_jsonl(&mut out).await?, 2);
+    let records: Vec<seekdb_rs::Record> = String::from_utf8(out)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, "j1");
+    assert_eq!(records[0].document.as_deref(), Some("one"));
+    assert_eq!(records[0].metadata, Some(json!({"n": 1})));
+    assert_eq!(records[1].embedding, Some(vec![0.0, 1.0, 0.0]));
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `reembed` rewrites the vectors of rows with documents and rejects a
+/// function of another dimension.
+#[tokio::test]
+async fn collection_reembed() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("reembed_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..3).map(|i| format!("e{i}")).collect();
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&vec![vec![1.0, 0.0, 0.0]; 3])
+            .documents(&["a".into(), "b".into(), "c".into()]),
+    )
+    .await?;
+    let bare = vec!["e3".to_string()];
+    coll.add_batch(AddBatch::new(&bare).embeddings(&[vec![1.0, 0.0, 0.0]]))
+        .await?;
+
+    let wrong = ConstantEmbedding { value: 1.0, dim: 4 };
+    let err = coll
+        .reembed(&wrong, 2)
+        .await
+        .expect_err("dimension must match");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)), "{err:?}");
+
+    let new_ef = ConstantEmbedding { value: 0.5, dim: 3 };
+    assert_eq!(coll.reembed(&new_ef, 2).await?, 3);
+    let all = vec!["e0".to_string(), "e2".to_string(), "e3".to_string()];
+    let got = coll
+        .get_query(GetQuery::by_ids(&all).with_include(&[IncludeField::Embeddings]))
+        .await?;
+    assert_eq!(
+        got.embeddings,
+        Some(vec![
+            vec![0.5, 0.5, 0.5],
+            vec![0.5, 0.5, 0.5],
+            vec![1.0, 0.0, 0.0]
+        ])
+    );
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// An export re-imported into a fresh collection restores every row; a bad
+/// line is reported by number.
+#[tokio::test]
+async fn collection_import_jsonl_round_trip() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let suffix = ts_suffix();
+    let create = |name: String| {
+        let client = &client;
+        async move {
+            client
+                .create_collection::<DummyEmbedding>(
+                    &name,
+                    Some(HnswConfig::new(3, DistanceMetric::L2)),
+                    None::<DummyEmbedding>,
+                )
+                .await
+        }
+    };
+    let source = create(format!("import_src_{suffix}")).await?;
+    let target = create(format!("import_dst_{suffix}")).await?;
+
+    let ids: Vec<String> = (0..5).map(|i| format!("i{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 1.0, 0.0]).collect();
+    let metadatas: Vec<serde_json::Value> = (0..5).map(|i| json!({"n": i})).collect();
+    source
+        .add_batch(
+            AddBatch::new(&ids)
+                .embeddings(&embeddings)
+                .metadatas(&metadatas),
+        )
+        .await?;
+
+    let mut dump = Vec::new();
+    source.export_jsonl(&mut dump).await?;
+    assert_eq!(target.import_jsonl(dump.as_slice(), 2).await?, 5);
+    let got = target
+        .get_query(GetQuery::by_ids(&ids).with_include(&[IncludeField::Metadatas]))
+        .await?;
+    assert_eq!(got.ids, ids);
+    assert_eq!(got.metadatas, Some(metadatas));
+
+    let bad = "{\"id\":\"x1\",\"embedding\":[1,0,0]}\n\n{\"id\":\"x2\",\"embedding\":[1,0]}\n";
+    let err = target
+        .import_jsonl(bad.as_bytes(), 10)
+        .await
+        .expect_err("short embedding must be rejected");
+    assert!(err.to_string().contains("line 3"), "{err}");
+
+    client.delete_collection(source.name()).await.ok();
+    client.delete_collection(target.name()).await.ok();
+    Ok(())
+}
+
+/// `add_auto` returns generated ids; content-hash ids are stable, so
+/// re-adding the same content collides.
+#[tokio::test]
+async fn collection_add_auto_id_strategies() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("add_auto_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let embeddings = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+
+    let uuids = coll.add_auto(Some(&embeddings), None, None).await?;
+    assert_eq!(uuids.len(), 2);
+    assert_ne!(uuids[0], uuids[1]);
+
+    let coll = coll.with_id_strategy(seekdb_rs::IdStrategy::Ulid);
+    let ulids = coll.add_auto(Some(&embeddings), None, None).await?;
+    assert!(ulids[0] < ulids[1]);
+
+    let coll = coll.with_id_strategy(seekdb_rs::IdStrategy::ContentHash);
+    let docs = vec!["same".to_string()];
+    let first = coll
+        .add_auto(Some(&embeddings[..1]), None, Some(&docs))
+        .await?;
+    assert!(
+        coll.add_auto(Some(&embeddings[..1]), None, Some(&docs))
+            .await
+            .is_err()
+    );
+    assert_eq!(coll.count().await?, 5);
+    assert_eq!(first[0].len(), 64);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `rename_collection` moves the table and rejects missing sources and
+/// existing targets.
+#[tokio::test]
+async fn collection_rename() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
+    let old = format!("rename_old_{}", ts_suffix());
+    let new = format!("rename_new_{}", ts_suffix());
+    let other = format!("rename_other_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(&old, Some(hnsw.clone()), None::<DummyEmbedding>)
+        .await?;
+    coll.add_batch(AddBatch::new(&["r1".to_string()]).embeddings(&[vec![1.0, 0.0, 0.0]]))
+        .await?;
+    client
+        .create_collection::<DummyEmbedding>(&other, Some(hnsw), None::<DummyEmbedding>)
+        .await?;
+
+    client.rename_collection(&old, &new).await?;
+    assert!(!client.has_collection(&old).await?);
+    let renamed = client
+        .get_collection::<DummyEmbedding>(&new, None::<DummyEmbedding>)
+        .await?;
+    assert_eq!(renamed.count().await?, 1);
+
+    let err = client
+        .rename_collection(&old, "rename_anywhere")
+        .await
+        .expect_err("source is gone");
+    assert!(matches!(err, SeekDbError::NotFound(_)));
+    let err = client
+        .rename_collection(&new, &other)
+        .await
+        .expect_err("target exists");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+    client.delete_collection(&new).await.ok();
+    client.delete_collection(&other).await.ok();
+    Ok(())
+}
+
+/// `clear` empties the collection but keeps it usable with the same schema.
+#[tokio::test]
+async fn collection_clear_keeps_schema() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::Cosine),
+        |_client, coll| {
+            let ids = vec!["c1".to_string(), "c2".to_string()];
+            coll.add_batch(
+                AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]),
+            )
+            .await?;
+
+            assert_eq!(coll.clear().await?, 2);
+            assert_eq!(coll.count().await?, 0);
+            let schema = coll.describe().await?;
+            assert_eq!(schema.dimension, 3);
+            assert_eq!(schema.distance, DistanceMetric::Cosine);
+            assert_eq!(schema.index_type.as_deref(), Some("hnsw"));
+            assert!(schema.has_fulltext);
+
+            coll.add_batch(AddBatch::new(&ids[..1]).embeddings(&[vec![1.0, 0.0, 0.0]]))
+                .await?;
+            let hits = coll
+                .query_embeddings(&[vec![1.0, 0.0, 0.0]], 1, None, None, None)
+                .await?;
+            assert_eq!(hits.ids[0], vec!["c1"]);
+            Ok(())
+        }
+    )
+}
+
+/// `get_or_create_collection` rejects a config that does not match the
+/// existing collection, and accepts a matching one or none.
+#[tokio::test]
+async fn collection_get_or_create_checks_config() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::Cosine),
+        |client, coll| {
+            let name = coll.name().to_string();
+            let res = client
+                .get_or_create_collection(
+                    &name,
+                    Some(HnswConfig::new(4, DistanceMetric::L2)),
+                    None::<DummyEmbedding>,
+                )
+                .await;
+            match res {
+                Err(SeekDbError::InvalidInput(msg)) => {
+                    assert!(msg.contains("dimension 3 != 4"), "{msg}");
+                    assert!(msg.contains("distance cosine != L2"), "{msg}");
+                }
+                Err(other) => panic!("unexpected error: {other:?}"),
+                Ok(_) => panic!("expected a config mismatch error"),
+            }
+
+            let same = client
+                .get_or_create_collection(
+                    &name,
+                    Some(HnswConfig::new(3, DistanceMetric::Cosine)),
+                    None::<DummyEmbedding>,
+                )
+                .await?;
+            assert_eq!(same.dimension(), 3);
+            client
+                .get_or_create_collection(&name, None, None::<DummyEmbedding>)
+                .await?;
+            Ok(())
+        }
+    )
+}
+
+/// `OnConflict` decides whether adding an existing id fails, keeps the stored
+/// row or replaces it.
+#[tokio::test]
+async fn collection_add_on_conflict() -> Result<()> {
+    with_test_collection!(HnswConfig::new(3, DistanceMetric::L2), |_client, coll| {
+        let ids = vec!["dup".to_string()];
+        coll.add_batch(
+            AddBatch::new(&ids)
+                .embeddings(&[vec![1.0, 0.0, 0.0]])
+                .documents(&["first".to_string()]),
+        )
+        .await?;
+
+        let second = ["second".to_string()];
+        let res = coll
+            .add_batch(
+                AddBatch::new(&ids)
+                    .embeddings(&[vec![0.0, 1.0, 0.0]])
+                    .documents(&second),
+            )
+            .await;
+        let err = res.expect_err("duplicate id");
+        assert_eq!(err.database_code(), Some(1062));
+
+        let both = vec!["dup".to_string(), "fresh".to_string()];
+        coll.add_batch(
+            AddBatch::new(&both)
+                .embeddings(&[vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]])
+                .on_conflict(OnConflict::Skip),
+        )
+        .await?;
+        let got = coll.get(Some(&ids), None, None, None, None, None).await?;
+        assert_eq!(got.documents, Some(vec!["first".to_string()]));
+        assert_eq!(coll.count().await?, 2);
+
+        coll.add_batch(
+            AddBatch::new(&ids)
+                .embeddings(&[vec![0.0, 1.0, 0.0]])
+                .documents(&second)
+                .on_conflict(OnConflict::Replace),
+        )
+        .await?;
+        let got = coll.get(Some(&ids), None, None, None, None, None).await?;
+        assert_eq!(got.documents, Some(vec!["second".to_string()]));
+        assert_eq!(coll.count().await?, 2);
+        Ok(())
+    })
+}
+
+/// Metadata above the size cap is rejected before anything is written.
+#[tokio::test]
+async fn collection_add_rejects_oversized_metadata() -> Result<()> {
+    with_test_collection!(HnswConfig::new(3, DistanceMetric::L2), |_client, coll| {
+        let coll = coll.with_max_metadata_bytes(64);
+        let ids = vec!["small".to_string(), "big".to_string()];
+        let res = coll
+            .add_batch(
+                AddBatch::new(&ids)
+                    .embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]])
+                    .metadatas(&[json!({"k": 1}), json!({"blob": "x".repeat(100)})]),
+            )
+            .await;
+        match res {
+            Err(SeekDbError::InvalidInput(msg)) => {
+                assert!(
+                    msg.contains("metadata for id big exceeds 64 bytes"),
+                    "{msg}"
+                )
+            }
+            other => panic!("expected InvalidInput, got {other:?}"),
+        }
+        assert_eq!(coll.count().await?, 0);
+        Ok(())
+    })
+}
+
+/// Named vector fields are stored next to `embedding` and searchable on their own.
+#[tokio::test]
+async fn collection_named_vector_fields() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::L2).with_vector_field("title_vec", 2),
+        |client, coll| {
+            assert_eq!(coll.vector_fields()[0].name, "title_vec");
+            let ids = vec!["a".to_string(), "b".to_string()];
+            let embeddings = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+            let titles = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+            coll.add_batch(
+                AddBatch::new(&ids)
+                    .embeddings(&embeddings)
+                    .vector_field("title_vec", &titles),
+            )
+            .await?;
+
+            // The main embedding ranks "a" first; the title vector ranks "b" first.
+            let res = coll
+                .query_embeddings(&[vec![1.0, 0.0, 0.0]], 2, None, None, None)
+                .await?;
+            assert_eq!(res.ids[0], vec!["a", "b"]);
+            let by_title = seekdb_rs::QueryOptions::new().with_vector_field("title_vec");
+            let res = coll
+                .query_embeddings_with_options(&[vec![1.0, 0.0]], 2, None, None, None, &by_title)
+                .await?;
+            assert_eq!(res.ids[0], vec!["b", "a"]);
+
+            // Reopened collections pick the fields up from the schema.
+            let reopened = client
+                .get_collection::<DummyEmbedding>(coll.name(), None)
+                .await?;
+            assert_eq!(reopened.vector_fields(), coll.vector_fields());
+
+            let bad = AddBatch::new(&ids)
+                .embeddings(&embeddings)
+                .vector_field("summary_vec", &titles);
+            let err = coll.add_batch(bad).await.expect_err("unknown field");
+            assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+            let by_missing = seekdb_rs::QueryOptions::new().with_vector_field("nope");
+            let err = coll
+                .query_embeddings_with_options(&[vec![1.0, 0.0]], 2, None, None, None, &by_missing)
+                .await
+                .expect_err("unknown field");
+            assert!(matches!(err, SeekDbError::InvalidInput(_)));
+            Ok(())
+        }
+    )
+}
+
+/// A zero dimension is rejected before any DDL reaches the server.
+#[tokio::test]
+async fn collection_create_zero_dimension_errors() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+
+    let client = ServerClient::from_config(config).await?;
+    let name = format!("zero_dim_coll_{}", ts_suffix());
+
+    let res = client
+        .create_collection::<DummyEmbedding>(
+            &name,
+            Some(HnswConfig::new(0, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await;
+    match res {
+        Err(SeekDbError::InvalidInput(msg)) => assert!(msg.contains("dimension"), "{msg}"),
+        Ok(_) => panic!("expected SeekDbError::InvalidInput, got Ok(_)"),
+        Err(e) => panic!("expected SeekDbError::InvalidInput, got {e:?}"),
+    }
+    assert!(!client.has_collection(&name).await?);
+
+    Ok(())
+}
+
+/// Detailed listing reports each collection's dimension and distance.
+#[tokio::test]
+async fn collection_list_detailed() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::Cosine),
+        |client, coll| {
+            let other = format!("list_detailed_{}", ts_suffix());
+            client
+                .create_collection::<DummyEmbedding>(
+                    &other,
+                    Some(HnswConfig::new(5, DistanceMetric::L2)),
+                    None::<DummyEmbedding>,
+                )
+                .await?;
+
+            let infos = client.list_collections_detailed().await?;
+            let names: Vec<String> = infos.iter().map(|i| i.name.clone()).collect();
+            let mut expected = client.list_collections(None, None).await?;
+            expected.sort();
+            assert_eq!(names, expected);
+            let first = infos
+                .iter()
+                .find(|i| i.name == coll.name())
+                .expect("listed");
+            assert_eq!(
+                (first.dimension, first.distance),
+                (3, DistanceMetric::Cosine)
+            );
+            let second = infos.iter().find(|i| i.name == other).expect("listed");
+            assert_eq!((second.dimension, second.distance), (5, DistanceMetric::L2));
+            Ok(())
+        }
+    )
+}
+
+/// `list_collections` pages through names in sorted order.
+#[tokio::test]
+async fn collection_list_paged() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::Cosine),
+        |client, coll| {
+            let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
+            for name in ["page_b", "page_a"] {
+                client
+                    .create_collection::<DummyEmbedding>(
+                        name,
+                        Some(hnsw.clone()),
+                        None::<DummyEmbedding>,
+                    )
+                    .await?;
+            }
+
+            let all = client.list_collections(None, None).await?;
+            let mut sorted = vec![
+                coll.name().to_string(),
+                "page_a".to_string(),
+                "page_b".to_string(),
+            ];
+            sorted.sort();
+            assert_eq!(all, sorted);
+            assert_eq!(client.list_collections(Some(2), None).await?, sorted[..2]);
+            assert_eq!(
+                client.list_collections(Some(2), Some(2)).await?,
+                sorted[2..]
+            );
+            assert_eq!(client.list_collections(None, Some(1)).await?, sorted[1..]);
+            Ok(())
+        }
+    )
+}
+
+/// `list_collections_matching` applies a LIKE pattern to collection names.
+#[tokio::test]
+async fn collection_list_matching() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::Cosine),
+        |client, _coll| {
+            let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
+            for name in ["docs_b", "docs_a", "docsx", "notes_a"] {
+                client
+                    .create_collection::<DummyEmbedding>(
+                        name,
+                        Some(hnsw.clone()),
+                        None::<DummyEmbedding>,
+                    )
+                    .await?;
+            }
+
+            assert_eq!(
+                client.list_collections_matching("docs%").await?,
+                vec!["docs_a", "docs_b", "docsx"]
+            );
+            assert_eq!(
+                client.list_collections_matching("docs\\_%").await?,
+                vec!["docs_a", "docs_b"]
+            );
+            assert_eq!(
+                client.list_collections_matching("%_a").await?,
+                vec!["docs_a", "notes_a"]
+            );
+            assert!(client.list_collections_matching("nope%").await?.is_empty());
+            Ok(())
+        }
+    )
+}
+
+/// `OnConflict::Replace` keeps named vectors the new row does not carry, and
+/// paths that would drop them refuse the collection.
+#[tokio::test]
+async fn collection_named_vector_fields_replace_and_reject() -> Result<()> {
+    with_test_collection!(
+        HnswConfig::new(3, DistanceMetric::L2).with_vector_field("title_vec", 2),
+        |_client, coll| {
+            let ids = vec!["a".to_string()];
+            let titles = vec![vec![0.0, 1.0]];
+            coll.add_batch(
+                AddBatch::new(&ids)
+                    .embeddings(&[vec![1.0, 0.0, 0.0]])
+                    .vector_field("title_vec", &titles),
+            )
+            .await?;
+            coll.add_batch(
+                AddBatch::new(&ids)
+                    .embeddings(&[vec![0.0, 1.0, 0.0]])
+                    .documents(&["replaced".to_string()])
+                    .on_conflict(OnConflict::Replace),
+            )
+            .await?;
+
+            let got = coll.get(Some(&ids), None, None, None, None, None).await?;
+            assert_eq!(got.documents.unwrap()[0], "replaced");
+            let by_title = seekdb_rs::QueryOptions::new().with_vector_field("title_vec");
+            let res = coll
+                .query_embeddings_with_options(&[vec![0.0, 1.0]], 1, None, None, None, &by_title)
+                .await?;
+            assert_eq!(res.ids[0], vec!["a"]);
+            assert!(res.distances.unwrap()[0][0].abs() < 1e-6);
+
+            let err = coll
+                .export_jsonl(Vec::new())
+                .await
+                .expect_err("named vectors");
+            assert!(matches!(err, SeekDbError::InvalidInput(_)));
+            let err = coll
+                .merge_from(&coll, OnConflict::Skip)
+                .await
+                .expect_err("named vectors");
+            assert!(matches!(err, SeekDbError::InvalidInput(_)));
+            Ok(())
+        }
+    )
+}
@@ -31,10 +31,7 @@ async fn collection_add_with_auto_embedding_default_embedding() -> Result<()> {
     let ef = DefaultEmbedding::new()?;
     let dim = ef.dimension() as u32;
 
-    let hnsw = HnswConfig {
-        dimension: dim,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(dim, DistanceMetric::Cosine);
 
     let coll = client
         .create_collection::<DefaultEmbedding>(&coll_name, Some(hnsw), Some(ef))
@@ -102,10 +99,7 @@ async fn collection_query_texts_with_default_embedding() -> Result<()> {
     let ef = DefaultEmbedding::new()?;
     let dim = ef.dimension() as u32;
 
-    let hnsw = HnswConfig {
-        dimension: dim,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(dim, DistanceMetric::Cosine);
 
     let coll = client
         .create_collection::<DefaultEmbedding>(&coll_name, Some(hnsw), Some(ef))
@@ -26,10 +26,7 @@ async fn collection_hybrid_search_basic() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("hybrid_ok_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let ef = ConstantEmbedding { value: 0.3, dim: 3 };
     let coll = client
         .create_collection::<ConstantEmbedding>(&coll_name, Some(hnsw), Some(ef))
@@ -79,10 +76,7 @@ async fn collection_hybrid_search_advanced_vector_only() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("hybrid_adv_vec_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -155,10 +149,7 @@ async fn collection_hybrid_search_advanced_query_knn_rank() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("hybrid_adv_full_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -265,10 +256,7 @@ async fn collection_hybrid_search_not_implemented() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("hybrid_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -283,3 +271,107 @@ async fn collection_hybrid_search_not_implemented() -> Result<()> {
     admin.delete_database(&db_name, None).await.ok();
     Ok(())
 }
+
+/// Back-to-back hybrid searches (including one dropped mid-flight) must not
+/// observe a stale `@search_parm` or leak connections from a one-connection pool.
+#[tokio::test]
+async fn collection_hybrid_search_sequential_after_cancel() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_hybrid_seq_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    db_config.max_connections = 1;
+    let client = ServerClient::from_config(db_config).await?;
+
+    let coll_name = format!("hybrid_seq_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
+    let coll = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
+        .await?;
+
+    let ids = vec!["s1".to_string(), "s2".to_string()];
+    let embs: Vec<Embedding> = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    let docs = vec!["first doc".to_string(), "second doc".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embs).documents(&docs))
+        .await?;
+
+    // Full-text only, so every call goes through `@search_parm` / GET_SQL.
+    let text_query = |word: &str| HybridQuery {
+        where_meta: None,
+        where_doc: Some(DocFilter::Contains(word.to_string())),
+    };
+
+    // Poll a search once and drop it before it can finish.
+    tokio::select! {
+        biased;
+        _ = coll.hybrid_search_advanced(Some(text_query("second")), None, None, 1, None) => {}
+        _ = std::future::ready(()) => {}
+    }
+
+    let first = coll
+        .hybrid_search_advanced(Some(text_query("first")), None, None, 1, None)
+        .await?;
+    let second = coll
+        .hybrid_search_advanced(Some(text_query("second")), None, None, 1, None)
+        .await?;
+
+    assert_eq!(first.ids[0], vec!["s1".to_string()]);
+    assert_eq!(second.ids[0], vec!["s2".to_string()]);
+
+    client.delete_collection(&coll_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
+
+/// With a small pool, statements of one hybrid search would otherwise be spread
+/// across connections and GET_SQL would see an unset `@search_parm`.
+#[tokio::test]
+async fn collection_hybrid_search_small_pool() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_hybrid_pool_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    db_config.max_connections = 2;
+    let client = ServerClient::from_config(db_config).await?;
+
+    let coll_name = format!("hybrid_pool_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
+    let coll = client
+        .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
+        .await?;
+
+    let ids = vec!["p1".to_string(), "p2".to_string()];
+    let embs: Vec<Embedding> = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    let docs = vec!["alpha doc".to_string(), "beta doc".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embs).documents(&docs))
+        .await?;
+
+    let text_query = |word: &str| HybridQuery {
+        where_meta: None,
+        where_doc: Some(DocFilter::Contains(word.to_string())),
+    };
+
+    // Run searches concurrently so both pooled connections are in use.
+    for _ in 0..5 {
+        let (a, b) = tokio::join!(
+            coll.hybrid_search_advanced(Some(text_query("alpha")), None, None, 1, None),
+            coll.hybrid_search_advanced(Some(text_query("beta")), None, None, 1, None),
+        );
+        assert_eq!(a?.ids[0], vec!["p1".to_string()]);
+        assert_eq!(b?.ids[0], vec!["p2".to_string()]);
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
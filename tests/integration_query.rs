@@ -2,13 +2,16 @@
 
 use anyhow::Result;
 use seekdb_rs::{
-    AddBatch, DistanceMetric, DocFilter, Filter, GetQuery, HnswConfig, IncludeField, SeekDbError,
-    ServerClient,
+    AddBatch, DistanceMetric, DocFilter, Filter, GetQuery, HnswConfig, IncludeField, QueryOptions,
+    SeekDbError, ServerClient, SortDir,
 };
 use serde_json::json;
 
 mod common;
-use common::{ConstantEmbedding, DummyEmbedding, load_config_for_integration, ts_suffix};
+use common::{
+    ConstantEmbedding, DummyEmbedding, RejectBlankEmbedding, load_config_for_integration,
+    ts_suffix,
+};
 
 #[tokio::test]
 async fn collection_query_and_filters() -> Result<()> {
@@ -24,10 +27,7 @@ async fn collection_query_and_filters() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("q_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::L2,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::L2);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -66,14 +66,14 @@ async fn collection_query_and_filters() -> Result<()> {
     let got = coll
         .get_query(GetQuery::new().with_where_meta(&where_meta))
         .await?;
-    assert!(got.ids.len() >= 1);
+    assert!(!got.ids.is_empty());
 
     // get with document filter
     let where_doc = DocFilter::Contains("rust".into());
     let got_doc = coll
         .get_query(GetQuery::new().with_where_doc(&where_doc))
         .await?;
-    assert!(got_doc.ids.len() >= 1);
+    assert!(!got_doc.ids.is_empty());
 
     // query_embeddings default include: documents+metadatas, no embeddings
     let q = vec![vec![0.0, 0.0, 0.0]];
@@ -108,14 +108,14 @@ async fn collection_query_and_filters() -> Result<()> {
     let got_in = coll
         .get_query(GetQuery::new().with_where_meta(&where_in))
         .await?;
-    assert!(got_in.ids.len() >= 1);
+    assert!(!got_in.ids.is_empty());
 
     // README-style `DocFilter::Regex` document filter.
     let where_doc_regex = DocFilter::Regex("rust".into());
     let got_regex = coll
         .get_query(GetQuery::new().with_where_doc(&where_doc_regex))
         .await?;
-    assert!(got_regex.ids.len() >= 1);
+    assert!(!got_regex.ids.is_empty());
 
     client.delete_collection(&coll_name).await.ok();
     admin.delete_database(&db_name, None).await.ok();
@@ -137,10 +137,7 @@ async fn collection_query_texts_with_embedding_function() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("qtexts_ok_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let ef = ConstantEmbedding { value: 0.2, dim: 3 };
     let coll = client
         .create_collection::<ConstantEmbedding>(&coll_name, Some(hnsw), Some(ef))
@@ -168,6 +165,49 @@ async fn collection_query_texts_with_embedding_function() -> Result<()> {
     Ok(())
 }
 
+/// query_texts_lenient returns one Result per text; a failed embedding only
+/// affects its own entry.
+#[tokio::test]
+async fn collection_query_texts_lenient_per_query_results() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let admin = ServerClient::from_config(config.clone()).await?;
+    let db_name = format!("rs_qtexts_lenient_{}", ts_suffix());
+    admin.create_database(&db_name, None).await?;
+
+    let mut db_config = config.clone();
+    db_config.database = db_name.clone();
+    let client = ServerClient::from_config(db_config).await?;
+
+    let coll_name = format!("qtexts_lenient_coll_{}", ts_suffix());
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
+    let ef = RejectBlankEmbedding { dim: 3 };
+    let coll = client
+        .create_collection::<RejectBlankEmbedding>(&coll_name, Some(hnsw), Some(ef))
+        .await?;
+
+    let ids = vec!["ql1".to_string(), "ql2".to_string()];
+    let docs = vec!["hello rust".to_string(), "hello seekdb".to_string()];
+    coll.add_batch(AddBatch::new(&ids).documents(&docs)).await?;
+
+    let texts = vec!["hello".to_string(), "   ".to_string(), "seekdb".to_string()];
+    let results = coll
+        .query_texts_lenient(&texts, 2, None, None, None)
+        .await?;
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[1], Err(SeekDbError::Embedding(_))));
+    for idx in [0, 2] {
+        let qr = results[idx].as_ref().expect("query should succeed");
+        assert_eq!(qr.ids.len(), 1);
+        assert_eq!(qr.ids[0].len(), 2);
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    admin.delete_database(&db_name, None).await.ok();
+    Ok(())
+}
+
 /// query_texts should error when collection has no embedding_function.
 #[tokio::test]
 async fn collection_query_texts_not_implemented() -> Result<()> {
@@ -183,10 +223,7 @@ async fn collection_query_texts_not_implemented() -> Result<()> {
     let client = ServerClient::from_config(db_config).await?;
 
     let coll_name = format!("qtexts_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     let coll = client
         .create_collection::<DummyEmbedding>(&coll_name, Some(hnsw), None::<DummyEmbedding>)
         .await?;
@@ -207,3 +244,1146 @@ async fn collection_query_texts_not_implemented() -> Result<()> {
     admin.delete_database(&db_name, None).await.ok();
     Ok(())
 }
+
+/// `max_distance` drops hits beyond the threshold even when fewer than `n_results` remain.
+#[tokio::test]
+async fn collection_query_max_distance() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_maxdist_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["near".to_string(), "mid".to_string(), "far".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[
+        vec![0.1, 0.0, 0.0],
+        vec![1.0, 0.0, 0.0],
+        vec![5.0, 0.0, 0.0],
+    ]))
+    .await?;
+
+    let q = vec![vec![0.0, 0.0, 0.0]];
+    let options = QueryOptions::new().with_max_distance(2.0);
+    let qr = coll
+        .query_embeddings_with_options(&q, 3, None, None, None, &options)
+        .await?;
+    assert_eq!(qr.ids[0], vec!["near".to_string(), "mid".to_string()]);
+    let dists = &qr.distances.as_ref().expect("distances")[0];
+    assert_eq!(dists.len(), qr.ids[0].len());
+    assert!(dists.iter().all(|d| *d <= 2.0));
+
+    let none = coll
+        .query_embeddings_with_options(
+            &q,
+            3,
+            None,
+            None,
+            None,
+            &QueryOptions::new().with_max_distance(0.01),
+        )
+        .await?;
+    assert!(none.ids[0].is_empty());
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `query_ids` returns bare `(id, distance)` pairs, nearest first.
+#[tokio::test]
+async fn collection_query_ids_candidates() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_ids_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 0.0, 0.0],
+                vec![3.0, 0.0, 0.0],
+            ])
+            .metadatas(&[
+                json!({"keep": true}),
+                json!({"keep": false}),
+                json!({"keep": true}),
+            ]),
+    )
+    .await?;
+
+    let q = vec![vec![0.0, 0.0, 0.0], vec![3.0, 0.0, 0.0]];
+    let hits = coll.query_ids(&q, 2, None).await?;
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0][0].0, "a");
+    assert_eq!(hits[0][1].0, "b");
+    assert!(hits[0][0].1 <= hits[0][1].1);
+    assert_eq!(hits[1][0].0, "c");
+
+    let keep = Filter::Eq {
+        field: "keep".into(),
+        value: json!(true),
+    };
+    let filtered = coll.query_ids(&q[..1], 3, Some(&keep)).await?;
+    let got: Vec<&str> = filtered[0].iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(got, vec!["a", "c"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `Like` and `StartsWith` match string metadata; `StartsWith` treats `%` literally.
+#[tokio::test]
+async fn collection_filter_like_and_starts_with() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_like_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["l1".to_string(), "l2".to_string(), "l3".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+            ])
+            .metadatas(&[
+                json!({"title": "rust in action"}),
+                json!({"title": "100% rust"}),
+                json!({"title": "100 recipes"}),
+            ]),
+    )
+    .await?;
+
+    let like = Filter::Like {
+        field: "title".into(),
+        pattern: "%rust%".into(),
+    };
+    let mut got = coll
+        .get_query(GetQuery::new().with_where_meta(&like))
+        .await?
+        .ids;
+    got.sort();
+    assert_eq!(got, vec!["l1".to_string(), "l2".to_string()]);
+
+    let prefix = Filter::StartsWith {
+        field: "title".into(),
+        prefix: "100%".into(),
+    };
+    let got = coll
+        .get_query(GetQuery::new().with_where_meta(&prefix))
+        .await?
+        .ids;
+    assert_eq!(got, vec!["l2".to_string()]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `Exists` / `NotExists` select rows by presence of a metadata key.
+#[tokio::test]
+async fn collection_filter_exists_and_not_exists() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_exists_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["e1".to_string(), "e2".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0]])
+            .metadatas(&[json!({"version": 2}), json!({"tag": "legacy"})]),
+    )
+    .await?;
+
+    let exists = Filter::Exists {
+        field: "version".into(),
+    };
+    let got = coll
+        .get_query(GetQuery::new().with_where_meta(&exists))
+        .await?;
+    assert_eq!(got.ids, vec!["e1".to_string()]);
+
+    let missing = Filter::NotExists {
+        field: "version".into(),
+    };
+    assert_eq!(coll.count_where(Some(&missing), None, None).await?, 1);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Dotted and indexed field names address nested metadata.
+#[tokio::test]
+async fn collection_filter_nested_paths() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_nested_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["n1".to_string(), "n2".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0]])
+            .metadatas(&[
+                json!({"a": {"b": {"c": 1}}, "tags": ["x", "y"], "odd-key": "v"}),
+                json!({"a": {"b": {"c": 2}}, "tags": ["y", "x"]}),
+            ]),
+    )
+    .await?;
+
+    for (filter, expected) in [
+        (
+            Filter::Eq {
+                field: "a.b.c".into(),
+                value: json!(2),
+            },
+            "n2",
+        ),
+        (
+            Filter::Eq {
+                field: "tags[0]".into(),
+                value: json!("x"),
+            },
+            "n1",
+        ),
+        (
+            Filter::Eq {
+                field: "odd-key".into(),
+                value: json!("v"),
+            },
+            "n1",
+        ),
+    ] {
+        let got = coll
+            .get_query(GetQuery::new().with_where_meta(&filter))
+            .await?;
+        assert_eq!(got.ids, vec![expected.to_string()], "{filter:?}");
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `with_f64_distances` fills `distances_f64` alongside the f32 distances.
+#[tokio::test]
+async fn collection_query_f64_distances() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_f64_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::InnerProduct)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["p1".to_string(), "p2".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![0.1, 0.2, 0.3], vec![0.3, 0.2, 0.1]]))
+        .await?;
+
+    let q = vec![vec![0.7, 0.5, 0.3]];
+    let plain = coll.query_embeddings(&q, 2, None, None, None).await?;
+    assert!(plain.distances_f64.is_none());
+
+    let options = QueryOptions::new().with_f64_distances(true);
+    let precise = coll
+        .query_embeddings_with_options(&q, 2, None, None, None, &options)
+        .await?;
+    let f32s = &precise.distances.as_ref().expect("distances")[0];
+    let f64s = &precise.distances_f64.as_ref().expect("distances_f64")[0];
+    assert_eq!(f32s.len(), f64s.len());
+    for (a, b) in f32s.iter().zip(f64s) {
+        assert!((f64::from(*a) - b).abs() < 1e-6);
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `ArrayContains` matches array elements, unlike `In` which compares scalars.
+#[tokio::test]
+async fn collection_filter_array_contains() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_arr_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["t1".to_string(), "t2".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0]])
+            .metadatas(&[json!({"tags": ["a", "b"]}), json!({"tags": ["c"]})]),
+    )
+    .await?;
+
+    let contains = Filter::ArrayContains {
+        field: "tags".into(),
+        value: json!("b"),
+    };
+    let got = coll
+        .get_query(GetQuery::new().with_where_meta(&contains))
+        .await?;
+    assert_eq!(got.ids, vec!["t1".to_string()]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// A per-query `ef_search` runs on a pinned connection and does not change results
+/// for a tiny collection; zero is rejected up front.
+#[tokio::test]
+async fn collection_query_ef_search() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_ef_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["e1".to_string(), "e2".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]))
+        .await?;
+
+    let q = vec![vec![0.9, 0.1, 0.0]];
+    let plain = coll.query_embeddings(&q, 2, None, None, None).await?;
+    let options = QueryOptions::new().with_ef_search(200);
+    let tuned = coll
+        .query_embeddings_with_options(&q, 2, None, None, None, &options)
+        .await?;
+    assert_eq!(tuned.ids, plain.ids);
+
+    let zero = QueryOptions::new().with_ef_search(0);
+    assert!(
+        coll.query_embeddings_with_options(&q, 2, None, None, None, &zero)
+            .await
+            .is_err()
+    );
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// With `with_normalize_queries`, scaling a query vector does not change inner
+/// product distances unless the caller opts out via `assume_normalized`.
+#[tokio::test]
+async fn collection_query_normalize_queries() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_norm_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(2, DistanceMetric::InnerProduct)),
+            None::<DummyEmbedding>,
+        )
+        .await?
+        .with_normalize_queries(true);
+    let ids = vec!["n1".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![0.6, 0.8]]))
+        .await?;
+
+    let scaled = vec![vec![3.0, 4.0]];
+    let normalized = coll.query_embeddings(&scaled, 1, None, None, None).await?;
+    let d = normalized.distances.as_ref().expect("distances")[0][0];
+    assert!((d.abs() - 1.0).abs() < 1e-4, "distance {d}");
+
+    let options = QueryOptions::new().with_assume_normalized(true);
+    let raw = coll
+        .query_embeddings_with_options(&scaled, 1, None, None, None, &options)
+        .await?;
+    let d = raw.distances.as_ref().expect("distances")[0][0];
+    assert!((d.abs() - 5.0).abs() < 1e-3, "distance {d}");
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `query_by_ids` returns neighbours per seed, aligned with the input, without
+/// the seed itself; unknown seeds get empty lists.
+#[tokio::test]
+async fn collection_query_by_ids() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_by_ids_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = ["a", "a2", "b", "b2"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[
+        vec![1.0, 0.0, 0.0],
+        vec![0.9, 0.1, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.1, 0.9, 0.0],
+    ]))
+    .await?;
+
+    let seeds = vec!["b".to_string(), "missing".to_string(), "a".to_string()];
+    let result = coll
+        .query_by_ids(&seeds, 1, None, Some(&[IncludeField::Distances]))
+        .await?;
+    assert_eq!(
+        result.ids,
+        vec![vec!["b2".to_string()], Vec::new(), vec!["a2".to_string()]]
+    );
+    assert_eq!(result.distances.as_ref().map(Vec::len), Some(3));
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Rows at identical distances come back ordered by `_id`, every time.
+#[tokio::test]
+async fn collection_query_ties_ordered_by_id() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_ties_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = ["d", "b", "e", "a", "c"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let embeddings = vec![vec![1.0, 2.0, 3.0]; ids.len()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embeddings))
+        .await?;
+
+    let query = vec![vec![0.0, 0.0, 0.0]];
+    for _ in 0..3 {
+        let result = coll
+            .query_embeddings(&query, 5, None, None, Some(&[]))
+            .await?;
+        assert_eq!(result.ids[0], vec!["a", "b", "c", "d", "e"]);
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `get_stream` pages through the filtered rows in id order.
+#[tokio::test]
+async fn collection_get_stream_pages() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("get_stream_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..7).map(|i| format!("s{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..7).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    let metadatas: Vec<serde_json::Value> = (0..7).map(|i| json!({"n": i})).collect();
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&embeddings)
+            .metadatas(&metadatas),
+    )
+    .await?;
+
+    let filter = Filter::Gte {
+        field: "n".into(),
+        value: json!(2),
+    };
+    let pages: Vec<_> = coll
+        .get_stream(Some(&filter), None, 2, Some(&[IncludeField::Metadatas]))
+        .try_collect()
+        .await?;
+    let sizes: Vec<usize> = pages.iter().map(|p| p.ids.len()).collect();
+    assert_eq!(sizes, vec![2, 2, 1]);
+    let got: Vec<String> = pages.into_iter().flat_map(|p| p.ids).collect();
+    assert_eq!(got, vec!["s2", "s3", "s4", "s5", "s6"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// `get_after` walks the collection by id cursor until the cursor runs out.
+#[tokio::test]
+async fn collection_get_after_cursor() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("get_after_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (0..5).map(|i| format!("k{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..5).map(|i| vec![i as f32, 1.0, 0.0]).collect();
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embeddings))
+        .await?;
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let (page, next) = coll
+            .get_after(cursor.as_deref(), 2, None, None, Some(&[]))
+            .await?;
+        seen.extend(page.ids);
+        match next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, ids);
+
+    let (page, next) = coll.get_after(Some("k3"), 2, None, None, None).await?;
+    assert_eq!(page.ids, vec!["k4"]);
+    assert!(next.is_none());
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Distances are returned by default and only when listed in an explicit
+/// include.
+#[tokio::test]
+async fn collection_query_include_distances() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_include_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["d1".to_string(), "d2".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]))
+        .await?;
+
+    let q = vec![vec![1.0, 0.0, 0.0]];
+    let default = coll.query_embeddings(&q, 2, None, None, None).await?;
+    assert_eq!(default.distances.map(|d| d[0].len()), Some(2));
+
+    let bare = coll
+        .query_embeddings(&q, 2, None, None, Some(IncludeField::none()))
+        .await?;
+    assert_eq!(bare.ids[0], vec!["d1", "d2"]);
+    assert!(bare.distances.is_none() && bare.documents.is_none());
+
+    let all = coll
+        .query_embeddings(&q, 1, None, None, Some(IncludeField::all()))
+        .await?;
+    assert_eq!(all.embeddings, Some(vec![vec![vec![1.0, 0.0, 0.0]]]));
+    assert_eq!(all.distances, Some(vec![vec![0.0]]));
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+#[tokio::test]
+async fn collection_query_grouped_by_metadata() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_grouped_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids: Vec<String> = (1..=5).map(|i| format!("g{i}")).collect();
+    let embs: Vec<Vec<f32>> = (1..=5).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    let metas = vec![
+        json!({"cat": "a"}),
+        json!({"cat": "a"}),
+        json!({"cat": "a"}),
+        json!({"cat": "b"}),
+        json!({"cat": "b"}),
+    ];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embs).metadatas(&metas))
+        .await?;
+
+    let groups = coll
+        .query_grouped(&[0.0, 0.0, 0.0], "cat", 2, 3, None, None)
+        .await?;
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].key, json!("a"));
+    assert_eq!(groups[0].hits.ids, vec![vec!["g1", "g2"]]);
+    assert_eq!(groups[1].key, json!("b"));
+    assert_eq!(groups[1].hits.ids, vec![vec!["g4"]]);
+
+    let err = coll
+        .query_grouped(&[0.0, 0.0, 0.0], "cat", 0, 3, None, None)
+        .await
+        .expect_err("per_group 0 is rejected");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// With snapshot scans a row added mid-scan is not seen by the running scan.
+#[tokio::test]
+async fn collection_iter_all_snapshot_ignores_concurrent_writes() -> Result<()> {
+    use futures::StreamExt;
+
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("snapshot_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?
+        .with_snapshot_scans(true);
+    assert!(coll.snapshot_scans());
+    let ids: Vec<String> = (0..4).map(|i| format!("s{i}")).collect();
+    let embeddings: Vec<Vec<f32>> = (0..4).map(|i| vec![i as f32, 0.0, 0.0]).collect();
+    coll.add_batch(AddBatch::new(&ids).embeddings(&embeddings))
+        .await?;
+
+    let mut seen = Vec::new();
+    {
+        let mut records = Box::pin(coll.iter_all(2, Some(IncludeField::none()), None));
+        seen.push(records.next().await.expect("first record")?.id);
+        coll.add_batch(AddBatch::new(&["s9".to_string()]).embeddings(&[vec![9.0, 0.0, 0.0]]))
+            .await?;
+        while let Some(record) = records.next().await {
+            seen.push(record?.id);
+        }
+    }
+    assert_eq!(seen, ids);
+    assert_eq!(coll.count().await?, 5);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Integer metadata above `i64::MAX` filters by its exact value.
+#[tokio::test]
+async fn collection_filter_on_large_unsigned_metadata() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_u64_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["big".to_string(), "small".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]])
+            .metadatas(&[json!({"sid": u64::MAX}), json!({"sid": -1})]),
+    )
+    .await?;
+
+    let filter = Filter::Eq {
+        field: "sid".into(),
+        value: json!(u64::MAX),
+    };
+    let got = coll
+        .get(None, Some(&filter), None, None, None, None)
+        .await?;
+    assert_eq!(got.ids, vec!["big"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Equal-distance hits follow the secondary metadata sort.
+#[tokio::test]
+async fn collection_query_then_by_metadata() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_then_by_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["old".to_string(), "new".to_string(), "mid".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids)
+            .embeddings(&vec![vec![1.0, 0.0, 0.0]; 3])
+            .metadatas(&[json!({"ts": 1}), json!({"ts": 3}), json!({"ts": 2})]),
+    )
+    .await?;
+
+    let q = vec![vec![1.0, 0.0, 0.0]];
+    let newest_first = QueryOptions::new().with_then_by("ts", SortDir::Desc);
+    let res = coll
+        .query_embeddings_with_options(&q, 3, None, None, None, &newest_first)
+        .await?;
+    assert_eq!(res.ids[0], vec!["new", "mid", "old"]);
+
+    let oldest_first = QueryOptions::new().with_then_by("ts", SortDir::Asc);
+    let res = coll
+        .query_embeddings_with_options(&q, 3, None, None, None, &oldest_first)
+        .await?;
+    assert_eq!(res.ids[0], vec!["old", "mid", "new"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Chunk hits resolve to their distinct parents, best-ranked parent first.
+#[tokio::test]
+async fn collection_get_parents_of_chunks() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let suffix = ts_suffix();
+    let chunks_name = format!("q_chunks_{suffix}");
+    let parents_name = format!("q_parents_{suffix}");
+    let chunks = client
+        .create_collection::<DummyEmbedding>(
+            &chunks_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let parents = client
+        .create_collection::<DummyEmbedding>(
+            &parents_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+
+    let parent_ids = vec!["doc1".to_string(), "doc2".to_string()];
+    parents
+        .add_batch(
+            AddBatch::new(&parent_ids)
+                .embeddings(&vec![vec![0.0, 0.0, 1.0]; 2])
+                .documents(&["full text one".to_string(), "full text two".to_string()]),
+        )
+        .await?;
+    let chunk_ids = vec![
+        "doc1#0".to_string(),
+        "doc1#1".to_string(),
+        "doc2#0".to_string(),
+    ];
+    chunks
+        .add_batch(
+            AddBatch::new(&chunk_ids)
+                .embeddings(&[
+                    vec![0.5, 0.0, 0.0],
+                    vec![0.9, 0.0, 0.0],
+                    vec![1.0, 0.0, 0.0],
+                ])
+                .metadatas(&[
+                    json!({"parent_id": "doc1"}),
+                    json!({"parent_id": "doc1"}),
+                    json!({"parent_id": "doc2"}),
+                ]),
+        )
+        .await?;
+
+    // doc2's only chunk is nearest, so doc2 comes first; doc1 appears once.
+    let hits = chunks
+        .query_embeddings(&[vec![1.0, 0.0, 0.0]], 3, None, None, None)
+        .await?;
+    let got = parents.get_parents(&hits, None).await?;
+    assert_eq!(got.ids, vec!["doc2", "doc1"]);
+    assert_eq!(
+        got.documents,
+        Some(vec![
+            "full text two".to_string(),
+            "full text one".to_string()
+        ])
+    );
+
+    let ids_only = chunks
+        .query_embeddings(
+            &[vec![1.0, 0.0, 0.0]],
+            3,
+            None,
+            None,
+            Some(&[IncludeField::Distances]),
+        )
+        .await?;
+    let err = parents
+        .get_parents(&ids_only, None)
+        .await
+        .expect_err("metadatas missing");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+    client.delete_collection(&chunks_name).await.ok();
+    client.delete_collection(&parents_name).await.ok();
+    Ok(())
+}
+
+/// Under inner product the largest score is the closest hit and comes first.
+#[tokio::test]
+async fn collection_query_inner_product_orders_most_similar_first() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_dot_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::Dot)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["far".to_string(), "near".to_string(), "mid".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[
+        vec![0.1, 0.0, 0.0],
+        vec![0.9, 0.1, 0.0],
+        vec![0.5, 0.0, 0.0],
+    ]))
+    .await?;
+
+    let res = coll
+        .query_embeddings(&[vec![1.0, 0.0, 0.0]], 3, None, None, None)
+        .await?;
+    assert_eq!(res.ids[0], vec!["near", "mid", "far"]);
+    let scores = res.distances.unwrap().remove(0);
+    assert!(scores[0] > scores[1] && scores[1] > scores[2], "{scores:?}");
+
+    // The threshold is a minimum score under inner product.
+    let opts = QueryOptions::new().with_max_distance(0.4);
+    let res = coll
+        .query_embeddings_with_options(&[vec![1.0, 0.0, 0.0]], 3, None, None, None, &opts)
+        .await?;
+    assert_eq!(res.ids[0], vec!["near", "mid"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// A batch of query vectors searched concurrently keeps the input order.
+#[tokio::test]
+async fn collection_query_batch_concurrently_keeps_order() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_conc_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ]))
+    .await?;
+
+    let axes = [
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ];
+    let queries: Vec<Vec<f32>> = (0..50).map(|i| axes[i % 3].clone()).collect();
+    for concurrency in [1, 8] {
+        let opts = QueryOptions::new().with_concurrency(concurrency);
+        let res = coll
+            .query_embeddings_with_options(&queries, 1, None, None, None, &opts)
+            .await?;
+        let nearest: Vec<&str> = res.ids.iter().map(|hits| hits[0].as_str()).collect();
+        let expected: Vec<&str> = (0..50).map(|i| ids[i % 3].as_str()).collect();
+        assert_eq!(nearest, expected, "concurrency {concurrency}");
+    }
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// Results record which fields were requested, including the defaults.
+#[tokio::test]
+async fn collection_results_record_included_fields() -> Result<()> {
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_included_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["d1".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0]]))
+        .await?;
+
+    let q = vec![vec![1.0, 0.0, 0.0]];
+    let default = coll.query_embeddings(&q, 1, None, None, None).await?;
+    assert_eq!(
+        default.included,
+        seekdb_rs::IncludeFlags::from_include(None)
+    );
+    assert!(!default.included.embeddings && default.embeddings.is_none());
+
+    let with_embeddings = coll
+        .query_embeddings(&q, 1, None, None, Some(&[IncludeField::Embeddings]))
+        .await?;
+    assert!(with_embeddings.included.embeddings);
+    assert!(!with_embeddings.included.documents);
+
+    let got = coll
+        .get(None, None, None, None, None, Some(IncludeField::all()))
+        .await?;
+    assert!(got.included.embeddings && got.included.documents);
+    assert!(!got.included.distances);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// A per-query `ef_search` puts the connection's own session value back.
+#[tokio::test]
+async fn collection_query_ef_search_restores_session_value() -> Result<()> {
+    use sqlx::Row;
+
+    let Some(mut config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    // One connection, so the session checked below is the one the query used.
+    config.max_connections = 1;
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_ef_restore_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(3, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["e1".to_string()];
+    coll.add_batch(AddBatch::new(&ids).embeddings(&[vec![1.0, 0.0, 0.0]]))
+        .await?;
+
+    client.execute("SET ob_hnsw_ef_search = 77").await?;
+    let options = QueryOptions::new().with_ef_search(300);
+    coll.query_embeddings_with_options(&[vec![1.0, 0.0, 0.0]], 1, None, None, None, &options)
+        .await?;
+
+    let rows = client
+        .fetch_all("SELECT CAST(@@ob_hnsw_ef_search AS SIGNED)")
+        .await?;
+    assert_eq!(rows[0].try_get::<i64, _>(0)?, 77);
+
+    client.execute("SET ob_hnsw_ef_search = DEFAULT").await?;
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
+
+/// With `DimensionFit::PadOrTruncate`, a prefix-length query searches full
+/// stored vectors under inner product; L2 collections reject the option.
+#[tokio::test]
+async fn collection_query_with_fitted_dimension() -> Result<()> {
+    use seekdb_rs::DimensionFit;
+
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_fit_{}", ts_suffix());
+    let coll = client
+        .create_collection::<DummyEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(4, DistanceMetric::InnerProduct)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let ids = vec!["a".to_string(), "b".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids).embeddings(&[vec![0.9, 0.1, 0.3, 0.2], vec![0.1, 0.9, 0.2, 0.3]]),
+    )
+    .await?;
+
+    let fit = QueryOptions::new().with_dimension_fit(DimensionFit::PadOrTruncate);
+    let short = coll
+        .query_embeddings_with_options(&[vec![1.0, 0.0]], 2, None, None, None, &fit)
+        .await?;
+    assert_eq!(short.ids[0], vec!["a", "b"]);
+    let long = coll
+        .query_embeddings_with_options(&[vec![0.0, 1.0, 0.0, 0.0, 5.0]], 2, None, None, None, &fit)
+        .await?;
+    assert_eq!(long.ids[0], vec!["b", "a"]);
+
+    let l2_name = format!("q_fit_l2_{}", ts_suffix());
+    let l2 = client
+        .create_collection::<DummyEmbedding>(
+            &l2_name,
+            Some(HnswConfig::new(4, DistanceMetric::L2)),
+            None::<DummyEmbedding>,
+        )
+        .await?;
+    let err = l2
+        .query_embeddings_with_options(&[vec![1.0, 0.0]], 1, None, None, None, &fit)
+        .await
+        .expect_err("L2 cannot fit dimensions");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+    client.delete_collection(&coll_name).await.ok();
+    client.delete_collection(&l2_name).await.ok();
+    Ok(())
+}
+
+/// `query_texts_with_options` fits a query model's shorter vectors too,
+/// instead of rejecting them before the options are applied.
+#[tokio::test]
+async fn collection_query_texts_with_fitted_dimension() -> Result<()> {
+    use seekdb_rs::DimensionFit;
+
+    let Some(config) = load_config_for_integration() else {
+        return Ok(());
+    };
+    let client = ServerClient::from_config(config).await?;
+
+    let coll_name = format!("q_fit_text_{}", ts_suffix());
+    let ef = ConstantEmbedding { value: 1.0, dim: 2 };
+    let coll = client
+        .create_collection::<ConstantEmbedding>(
+            &coll_name,
+            Some(HnswConfig::new(4, DistanceMetric::InnerProduct)),
+            Some(ef),
+        )
+        .await?;
+    let ids = vec!["a".to_string(), "b".to_string()];
+    coll.add_batch(
+        AddBatch::new(&ids).embeddings(&[vec![0.9, 0.8, 0.0, 0.0], vec![0.1, 0.2, 0.9, 0.9]]),
+    )
+    .await?;
+
+    let texts = vec!["prefix query".to_string()];
+    let err = coll
+        .query_texts(&texts, 2, None, None, None)
+        .await
+        .expect_err("a 2-dimensional query cannot search 4 dimensions as is");
+    assert!(matches!(err, SeekDbError::InvalidInput(_)));
+
+    let fit = QueryOptions::new().with_dimension_fit(DimensionFit::PadOrTruncate);
+    let res = coll
+        .query_texts_with_options(&texts, 2, None, None, None, &fit)
+        .await?;
+    assert_eq!(res.ids[0], vec!["a", "b"]);
+
+    client.delete_collection(&coll_name).await.ok();
+    Ok(())
+}
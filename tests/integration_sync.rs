@@ -25,10 +25,7 @@ fn sync_collection_dml_roundtrip() -> Result<()> {
     let client = SyncServerClient::from_config(config.clone())?;
 
     let coll_name = format!("sync_coll_{}", ts_suffix());
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
 
     let coll = client.create_collection::<DummyEmbedding>(
         &coll_name,
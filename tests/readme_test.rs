@@ -21,19 +21,16 @@ async fn test_readme_doc() -> Result<(), SeekDbError> {
     let db = admin.get_database("my_test_readme", Some("sys")).await?;
     println!("Database {:?} created successfully", db);
 
-    let list = admin.list_databases(None, None, None).await?;
+    let _list = admin.list_databases(None, None, None).await?;
     // println!("Database list: {:?}", list);
 
     admin.delete_database("my_test_readme", None).await?;
 
-    let list = admin.list_databases(None, None, None).await?;
+    let _list = admin.list_databases(None, None, None).await?;
     // println!("Database list: {:?}", list);
 
     // 试试 server client
-    let hnsw = HnswConfig {
-        dimension: 3,
-        distance: DistanceMetric::Cosine,
-    };
+    let hnsw = HnswConfig::new(3, DistanceMetric::Cosine);
     if client.has_collection("test_readme").await? {
         client.delete_collection("test_readme").await?;
     }
@@ -45,7 +42,7 @@ async fn test_readme_doc() -> Result<(), SeekDbError> {
         )
         .await?;
 
-    let coll_list = client.list_collections().await?;
+    let coll_list = client.list_collections(None, None).await?;
     println!("Collection list: {:?}", coll_list);
 
     let coll_test_readme = client
@@ -80,9 +77,9 @@ async fn test_readme_doc() -> Result<(), SeekDbError> {
         .await?;
     coll.update(
         &["item1".to_string()],
-        Some(&vec![vec![0.7, 0.8, 0.9]]),
-        Some(&vec![json!({"category": "AI", "score": 96})]),
-        Some(&vec!["Updated Document 1".to_string()]),
+        Some(&[vec![0.7, 0.8, 0.9]]),
+        Some(&[json!({"category": "AI", "score": 96})]),
+        Some(&["Updated Document 1".to_string()]),
     )
     .await?;
     let r = coll